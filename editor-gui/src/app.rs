@@ -115,6 +115,7 @@ impl GuideRowItem for Color {
             Color::LightPurple => "Pink",
             Color::Yellow => "Yellow",
             Color::White => "White",
+            Color::Rgb(..) => "Custom",
         }
     }
 
@@ -137,6 +138,7 @@ impl GuideRowItem for Color {
             Color::LightPurple => "d",
             Color::Yellow => "e",
             Color::White => "f",
+            Color::Rgb(..) => "#rrggbb",
         }
     }
 
@@ -25,6 +25,7 @@ pub fn span_ui(ui: &mut Ui, span: Span<'_>) {
             text,
             color,
             styles,
+            ..
         } => ui.add(label_from_style(text, color, styles)),
         Span::StrikethroughWhitespace {
             text,
@@ -32,6 +33,12 @@ pub fn span_ui(ui: &mut Ui, span: Span<'_>) {
             styles,
         } => ui.add(label_from_style(&"-".repeat(text.len()), color, styles)),
         Span::Plain(text) => ui.add(Label::new(RichText::new(text).color(Color32::WHITE))),
+        Span::Url {
+            text,
+            color,
+            styles,
+        } => ui.add(label_from_style(text, color, styles)),
+        span => ui.add(label_from_style(span.text(), span.color(), span.styles())),
     };
 }
 
@@ -0,0 +1,120 @@
+//! Benchmarks for `SpanIter` over a range of representative inputs
+//!
+//! Run with `cargo bench -p mc-legacy-formatting`. Every workload is measured
+//! both by iterating over all of the spans and by collecting them into a
+//! `Vec`, and throughput is reported in bytes of input per second.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mc_legacy_formatting::{Span, SpanIter};
+
+/// The size of the generated workloads
+const SIZE: usize = 10 * 1024;
+
+/// A dense MOTD with lots of codes and strikethrough whitespace
+const PURPLE_WTF: &str = "§5§m                  §6>§7§l§6§l>§6§l[§5§l§oPurple §8§l§oPrison§6§l]§6§l<§6<§5§m                     \
+    §R §7              (§4!§7) §e§lSERVER HAS §D§LRESET! §7(§4!§7)";
+
+const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
+    eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis \
+    nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.\n";
+
+/// Repeat `f` (which is passed the repetition number) until the output is at
+/// least [`SIZE`] bytes long
+fn repeat_until_size(mut f: impl FnMut(usize, &mut String)) -> String {
+    let mut s = String::new();
+    let mut i = 0;
+
+    while s.len() < SIZE {
+        f(i, &mut s);
+        i += 1;
+    }
+
+    s
+}
+
+/// Text without any codes at all
+fn plain() -> String {
+    repeat_until_size(|_, s| s.push_str(LOREM_IPSUM))
+}
+
+/// Text with a color code every few hundred chars, like a book
+fn mostly_plain() -> String {
+    let codes = b"0123456789abcdef";
+    repeat_until_size(|i, s| {
+        s.push('§');
+        s.push(char::from(codes[i % codes.len()]));
+        s.push_str(LOREM_IPSUM);
+    })
+}
+
+/// A chat log, with codes every few words
+fn chat_log() -> String {
+    let names = ["Steve", "Alex", "Notch", "jeb_"];
+    repeat_until_size(|i, s| {
+        s.push_str("§7[§a+§7] §6§l");
+        s.push_str(names[i % names.len()]);
+        s.push_str("§r§7: §fhas anyone seen §b§odiamonds §fnear §e§nspawn§r?\n");
+    })
+}
+
+/// Nothing but codes
+fn only_codes() -> String {
+    repeat_until_size(|_, s| s.push_str("§a§l§m§n§o§k§r§6§x"))
+}
+
+/// Lines of strikethrough whitespace separating short pieces of text
+fn strikethrough_whitespace() -> String {
+    repeat_until_size(|_, s| {
+        s.push_str("§8§m");
+        s.push_str(&" ".repeat(40));
+        s.push_str("§r §6Server News §8§m");
+        s.push_str(&" ".repeat(40));
+        s.push('\n');
+    })
+}
+
+fn span_iter(c: &mut Criterion) {
+    let workloads = [
+        ("plain", plain()),
+        ("purple_wtf", PURPLE_WTF.to_string()),
+        ("mostly_plain", mostly_plain()),
+        ("chat_log", chat_log()),
+        ("only_codes", only_codes()),
+        ("strikethrough_whitespace", strikethrough_whitespace()),
+        // A single span in each of these
+        ("plain_span", format!("§6{}", "a".repeat(SIZE))),
+        (
+            "strikethrough_whitespace_span",
+            format!("§m{}§rend", " ".repeat(SIZE)),
+        ),
+    ];
+
+    for (name, s) in &workloads {
+        let mut group = c.benchmark_group(*name);
+        group.throughput(Throughput::Bytes(s.len() as u64));
+
+        group.bench_function("iterate", |b| {
+            b.iter(|| SpanIter::new(black_box(s)).count())
+        });
+        group.bench_function("collect", |b| {
+            b.iter(|| SpanIter::new(black_box(s)).collect::<Vec<Span>>())
+        });
+
+        group.finish();
+    }
+
+    let mut group = c.benchmark_group("mostly_plain_ampersand");
+    let mostly_plain = mostly_plain().replace('§', "&");
+    group.throughput(Throughput::Bytes(mostly_plain.len() as u64));
+    group.bench_function("iterate", |b| {
+        b.iter(|| {
+            SpanIter::new(black_box(&mostly_plain))
+                .with_start_char('&')
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, span_iter);
+criterion_main!(benches);
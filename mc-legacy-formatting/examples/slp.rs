@@ -1,53 +1,128 @@
 //! An example of handling the format codes in the Server List Ping response
-//! (the one that isn't a chat object, anyway)
+//!
+//! Run with `cargo run --example slp --features json`
 
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+};
+
+use anyhow::{anyhow, Context};
 use dialoguer::Input;
-use mc_legacy_formatting::SpanExt;
+use mc_legacy_formatting::{flatten_chat_component, ColoredSpans, SpanExt};
+use serde_json::Value;
 
 fn main() -> Result<(), anyhow::Error> {
     let server_address = Input::<String>::new()
         .with_prompt("Minecraft server address")
         .interact()?;
 
-    let (_, status) = mcping::get_status(&server_address, None)?;
-    let description = status.description.text();
-
-    print!("version: ");
-    status
-        .version
-        .name
-        .span_iter()
-        .map(|s| s.wrap_colored())
-        .for_each(|s| print!("{}", s));
+    let status = get_status(&server_address)?;
+    // Most servers leave the top-level `text` of the description empty and put
+    // the whole MOTD in `extra`, so the full component has to be flattened
+    // (which still takes care of any legacy codes inside of it)
+    let description = &status["description"];
 
-    println!();
-    println!("description text: {:?}", description);
+    println!(
+        "version: {}",
+        ColoredSpans::new(status["version"]["name"].as_str().unwrap_or("").span_iter())
+    );
+    println!("description text: {:?}", description["text"]);
     println!("description:");
-    description
-        .span_iter()
-        .map(|s| s.wrap_colored())
-        .for_each(|s| print!("{}", s));
-
-    println!();
+    let description_spans = flatten_chat_component(description);
+    println!(
+        "{}",
+        ColoredSpans::new(description_spans.iter().map(|s| s.as_span()))
+    );
     print!("sample: ");
 
-    status
-        .players
-        .sample
+    status["players"]["sample"]
+        .as_array()
         .filter(|sample| !sample.is_empty())
         .map(|sample| {
             println!();
 
             for player in sample {
-                player
-                    .name
-                    .span_iter()
-                    .map(|s| s.wrap_colored())
-                    .for_each(|s| print!("{}", s));
-                println!();
+                let name = player["name"].as_str().unwrap_or("");
+                println!("{}", ColoredSpans::new(name.span_iter()));
             }
         })
         .unwrap_or_else(|| println!("N/A"));
 
     Ok(())
 }
+
+/// The longest status response that's read
+///
+/// Vanilla servers are limited to 32767 chars, but modded servers can send
+/// more than that (mostly because of large favicons). The length comes from the
+/// server, so it has to be checked before allocating room for the response.
+const MAX_RESPONSE_LEN: u32 = 1024 * 1024;
+
+/// Send a status request to the server at `address` (`host` or `host:port`)
+/// and return the JSON response as-is
+///
+/// `mcping` throws away everything in the description but its top-level
+/// `text`, so this speaks the protocol itself. SRV records aren't looked up,
+/// so the port has to be given for servers that don't run on the default one.
+fn get_status(address: &str) -> Result<Value, anyhow::Error> {
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().context("invalid port")?),
+        None => (address, 25565u16),
+    };
+    let mut stream = TcpStream::connect((host, port))?;
+
+    let mut handshake = vec![0x00];
+    write_varint(&mut handshake, 47);
+    write_varint(&mut handshake, host.len() as u32);
+    handshake.extend_from_slice(host.as_bytes());
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    write_packet(&mut stream, &handshake)?;
+    write_packet(&mut stream, &[0x00])?;
+
+    let _len = read_varint(&mut stream)?;
+    if read_varint(&mut stream)? != 0x00 {
+        return Err(anyhow!("unexpected packet in response"));
+    }
+    let len = read_varint(&mut stream)?;
+    if len > MAX_RESPONSE_LEN {
+        return Err(anyhow!("response is too long ({} bytes)", len));
+    }
+    let mut json = vec![0; len as usize];
+    stream.read_exact(&mut json)?;
+
+    Ok(serde_json::from_slice(&json)?)
+}
+
+fn write_packet(stream: &mut TcpStream, packet: &[u8]) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    write_varint(&mut buf, packet.len() as u32);
+    buf.extend_from_slice(packet);
+    stream.write_all(&buf)
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u32) {
+    loop {
+        if value < 0x80 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn read_varint(stream: &mut TcpStream) -> Result<u32, anyhow::Error> {
+    let mut value = 0;
+    for i in 0..5 {
+        let mut byte = [0];
+        stream.read_exact(&mut byte)?;
+        value |= u32::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(anyhow!("varint is too long"))
+}
@@ -0,0 +1,147 @@
+//! Runs `SpanIter` over arbitrary input, with arbitrary settings
+//!
+//! Run with `cargo +nightly fuzz run span_iter` from the `mc-legacy-formatting`
+//! folder.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use mc_legacy_formatting::{
+    raw_tokens, Color, CustomAction, SpanIter, Styles, TargetVersion, Token,
+};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    /// Interpreted as UTF-8, with invalid sequences replaced
+    text: Vec<u8>,
+    /// Used in addition to `§`
+    start_char: char,
+    /// Replaces the default reset char if `Some`
+    reset_char: Option<Option<char>>,
+    doubled_escape: bool,
+    backslash_escape: bool,
+    hex_colors: bool,
+    quantize_hex: bool,
+    split_on_newline: bool,
+    url_detection: bool,
+    skip_empty: bool,
+    trailing_reset: bool,
+    background_prefix: Option<char>,
+    /// Whether to recognize the codes from [`custom_code`]
+    custom_codes: bool,
+    /// `Some(None)` targets Bedrock Edition, `Some(Some(minor))` targets Java
+    /// Edition `1.minor`
+    target_version: Option<Option<u8>>,
+    initial_state: Option<(Color, Styles)>,
+}
+
+/// A custom code hook that recognizes a few codes of each kind
+fn custom_code(c: char, modifier: char) -> Option<CustomAction> {
+    match (c, modifier) {
+        ('g', c) => Color::from_char(c).map(CustomAction::Color),
+        ('s', c) => Styles::from_char(c).map(CustomAction::Style),
+        ('x', 'x') => Some(CustomAction::Set(Color::Gold, Styles::BOLD)),
+        ('z', 'z') => Some(CustomAction::Reset),
+        _ => None,
+    }
+}
+
+/// The text of `s` with its valid codes removed
+fn without_codes(s: &str, start_char: char) -> String {
+    let mut out = String::new();
+
+    for token in raw_tokens(s, start_char) {
+        match token {
+            Token::Text(text) => out.push_str(text),
+            Token::LiteralStart => out.push(start_char),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Assert that each of `texts` is a slice of `s` that starts at or after the
+/// end of the one before it, and concatenate them
+fn in_order_text<'a>(s: &str, texts: impl Iterator<Item = &'a str>) -> String {
+    let end_of_s = s.as_ptr() as usize + s.len();
+    let mut end = s.as_ptr() as usize;
+    let mut out = String::new();
+
+    for text in texts {
+        let start = text.as_ptr() as usize;
+        assert!(
+            start >= end && start + text.len() <= end_of_s,
+            "{:?} is out of order in {:?}",
+            text,
+            s
+        );
+
+        end = start + text.len();
+        out.push_str(text);
+    }
+
+    out
+}
+
+fuzz_target!(|input: Input| {
+    let s = String::from_utf8_lossy(&input.text);
+
+    for start_char in ['§', input.start_char] {
+        let expected = without_codes(&s, start_char);
+
+        let text = in_order_text(
+            &s,
+            SpanIter::new(&s)
+                .with_start_char(start_char)
+                .map(|span| span.text()),
+        );
+        assert_eq!(text, expected);
+
+        let mut span_iter = SpanIter::new(&s).with_start_char(start_char);
+        // Set first, since it changes the hex color setting below
+        if let Some(version) = input.target_version {
+            span_iter = span_iter.with_target_version(match version {
+                Some(minor) => TargetVersion::Java(minor),
+                None => TargetVersion::Bedrock,
+            });
+        }
+        span_iter = span_iter
+            .with_doubled_escape(input.doubled_escape)
+            .with_backslash_escape(input.backslash_escape)
+            .with_hex_colors(input.hex_colors)
+            .with_quantize_hex(input.quantize_hex)
+            .split_on_newline(input.split_on_newline)
+            .with_url_detection(input.url_detection)
+            .with_background_prefix(input.background_prefix);
+        if input.custom_codes {
+            span_iter = span_iter.with_custom_code(Some(custom_code));
+        }
+        if let Some(reset_char) = input.reset_char {
+            span_iter = span_iter.with_reset_char(reset_char);
+        }
+        if input.skip_empty {
+            span_iter = span_iter.skip_empty();
+        }
+        if input.trailing_reset {
+            span_iter = span_iter.with_trailing_reset();
+        }
+        if let Some((color, styles)) = input.initial_state {
+            span_iter = span_iter.with_initial_state(color, styles);
+        }
+
+        let text = in_order_text(&s, span_iter.map(|span| span.text()));
+        // The other settings only split spans up or change their formatting
+        if !input.doubled_escape
+            && !input.backslash_escape
+            && !input.hex_colors
+            && input.reset_char.is_none()
+            && input.background_prefix.is_none()
+            && !input.custom_codes
+            && input.target_version.is_none()
+        {
+            assert_eq!(text, expected);
+        }
+    }
+});
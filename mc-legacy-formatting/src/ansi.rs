@@ -0,0 +1,158 @@
+use alloc::string::String;
+
+use crate::{Color, LegacyStringBuilder, Span, Styles};
+
+/// Convert a string containing ANSI escape sequences (such as terminal output)
+/// into a string with legacy formatting codes, using `§` as the start char.
+///
+/// The foreground color and the bold, italic, underline, and strikethrough
+/// attributes of SGR sequences (`\x1b[...m`) are mapped to codes:
+///
+/// * The 16 basic colors map to the named colors they're printed as with the
+///   `color-print` feature (for example, red is [`Color::DarkRed`] and bright
+///   red is [`Color::Red`])
+/// * 256-color palette entries map to the [nearest named
+///   color](Color::nearest_named)
+/// * Truecolor is kept as [`Color::Rgb`] and written in the `§x§r§r§g§g§b§b`
+///   format
+///
+/// Everything else (background colors, other attributes, and any other escape
+/// sequences) is stripped. The codes are emitted by a [`LegacyStringBuilder`],
+/// so only the codes needed to change the formatting are written, and `§`
+/// chars in the text are escaped by doubling them.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::from_ansi;
+///
+/// assert_eq!(
+///     from_ansi("\x1b[1;31mError:\x1b[0m file not found"),
+///     "§4§lError:§r file not found"
+/// );
+/// ```
+pub fn from_ansi(s: &str) -> String {
+    let mut builder = LegacyStringBuilder::new();
+    let mut color = Color::White;
+    let mut styles = Styles::empty();
+    let mut rest = s;
+
+    while let Some(esc_idx) = rest.find('\x1b') {
+        builder.push(Span::new_styled(&rest[..esc_idx], color, styles));
+        rest = &rest[esc_idx + 1..];
+
+        let mut chars = rest.chars();
+        rest = match chars.next() {
+            Some('[') => {
+                let seq = chars.as_str();
+                // The final byte of a CSI sequence is in the range `@` to `~`
+                match seq.find(|c| ('@'..='~').contains(&c)) {
+                    Some(end) => {
+                        if seq[end..].starts_with('m') {
+                            apply_sgr(&seq[..end], &mut color, &mut styles);
+                        }
+                        &seq[end + 1..]
+                    }
+                    None => "",
+                }
+            }
+            // Other escape sequences are only stripped along with the char
+            // that follows the escape
+            Some(_) => chars.as_str(),
+            None => "",
+        };
+    }
+
+    builder.push(Span::new_styled(rest, color, styles));
+    builder.finish()
+}
+
+/// Apply the parameters of an SGR sequence to `color` and `styles`
+fn apply_sgr(params: &str, color: &mut Color, styles: &mut Styles) {
+    // Empty parameters are treated as zero, so `\x1b[m` is a reset
+    let mut params = params.split(';').map(|p| p.parse::<u32>().unwrap_or(0));
+
+    while let Some(param) = params.next() {
+        match param {
+            0 => {
+                *color = Color::White;
+                *styles = Styles::empty();
+            }
+            1 => styles.insert(Styles::BOLD),
+            3 => styles.insert(Styles::ITALIC),
+            4 => styles.insert(Styles::UNDERLINED),
+            9 => styles.insert(Styles::STRIKETHROUGH),
+            22 => styles.remove(Styles::BOLD),
+            23 => styles.remove(Styles::ITALIC),
+            24 => styles.remove(Styles::UNDERLINED),
+            29 => styles.remove(Styles::STRIKETHROUGH),
+            30..=37 => *color = ANSI_COLORS[(param - 30) as usize],
+            90..=97 => *color = ANSI_COLORS[(param - 90 + 8) as usize],
+            38 => {
+                if let Some(c) = extended_color(&mut params) {
+                    *color = c;
+                }
+            }
+            39 => *color = Color::White,
+            // Background colors take the same arguments, which have to be
+            // skipped
+            48 => {
+                extended_color(&mut params);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse the arguments of an extended (`38` or `48`) color parameter
+fn extended_color(params: &mut impl Iterator<Item = u32>) -> Option<Color> {
+    match params.next()? {
+        5 => params.next().map(palette_color),
+        2 => {
+            let mut channel = || params.next().map(|v| v.min(255) as u8);
+            let (r, g, b) = (channel()?, channel()?, channel()?);
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// Map an entry of the 256-color palette to a [`Color`]
+fn palette_color(idx: u32) -> Color {
+    let rgb = match idx {
+        0..=15 => return ANSI_COLORS[idx as usize],
+        // A 6x6x6 color cube
+        16..=231 => {
+            let level = |v: u32| if v == 0 { 0 } else { (55 + 40 * v) as u8 };
+            let idx = idx - 16;
+            Color::Rgb(level(idx / 36), level(idx / 6 % 6), level(idx % 6))
+        }
+        // A grayscale ramp
+        _ => {
+            let v = (8 + 10 * (idx.min(255) - 232)) as u8;
+            Color::Rgb(v, v, v)
+        }
+    };
+
+    rgb.nearest_named()
+}
+
+/// The named color for each of the 16 basic ANSI colors, in order
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::Gold,
+    Color::DarkBlue,
+    Color::DarkPurple,
+    Color::DarkAqua,
+    Color::Gray,
+    Color::DarkGray,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::LightPurple,
+    Color::Aqua,
+    Color::White,
+];
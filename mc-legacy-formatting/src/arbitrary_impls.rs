@@ -0,0 +1,29 @@
+//! Implementations of [`Arbitrary`] for fuzzing
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Color, Styles};
+
+impl<'a> Arbitrary<'a> for Color {
+    /// Pick one of the named colors or a random [`Color::Rgb`]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=16)? {
+            16 => Color::Rgb(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?),
+            n => Color::from_char(char::from_digit(n, 16).unwrap()).unwrap(),
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(4))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Styles {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Styles::from_bits_truncate(u8::arbitrary(u)?.into()))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(1))
+    }
+}
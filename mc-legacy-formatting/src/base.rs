@@ -0,0 +1,77 @@
+use crate::{Color, Span, Styles};
+
+/// Layer `spans` on top of a base color and base styles.
+///
+/// Some text has formatting that applies before any codes do: item lore is
+/// dark purple and italic, and some plugins send chat with a gray base. Each
+/// span keeps its own color if it has one (if its color isn't
+/// [`Color::White`]) and otherwise gets `color`, and `styles` are added to the
+/// styles of every span. A [`Span::Plain`] therefore takes on the base
+/// formatting entirely.
+///
+/// Unlike [`SpanIter::with_initial_state`](crate::SpanIter::with_initial_state),
+/// the base styles survive color codes, and this works with spans from any
+/// source (such as flattened JSON chat components). Since spans don't record
+/// whether white text was made white by an explicit code, white text always
+/// takes on the base color.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{with_base, SpanExt, Span, Color, Styles};
+///
+/// let lore: Vec<Span> = with_base("Sharp §7(+5) §lwow".span_iter(), Color::DarkPurple, Styles::ITALIC).collect();
+///
+/// assert_eq!(
+///     lore,
+///     vec![
+///         Span::new_styled("Sharp ", Color::DarkPurple, Styles::ITALIC),
+///         Span::new_styled("(+5) ", Color::Gray, Styles::ITALIC),
+///         Span::new_styled("wow", Color::Gray, Styles::ITALIC | Styles::BOLD),
+///     ]
+/// );
+/// ```
+pub fn with_base<'a, I>(spans: I, color: Color, styles: Styles) -> WithBase<I::IntoIter>
+where
+    I: IntoIterator<Item = Span<'a>>,
+{
+    WithBase {
+        spans: spans.into_iter(),
+        color,
+        styles,
+    }
+}
+
+/// An iterator that layers spans on top of a base color and base styles.
+///
+/// Created by [`with_base`].
+#[derive(Debug, Clone)]
+pub struct WithBase<I> {
+    spans: I,
+    color: Color,
+    styles: Styles,
+}
+
+impl<'a, I: Iterator<Item = Span<'a>>> Iterator for WithBase<I> {
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        let color = match span.color() {
+            Color::White => self.color,
+            color => color,
+        };
+        let styles = span.styles() | self.styles;
+
+        Some(match span {
+            Span::Url { text, .. } => Span::new_url(text, color, styles),
+            _ => {
+                Span::from_formatting(span.text(), color, styles).with_background(span.background())
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
@@ -0,0 +1,102 @@
+use core::fmt;
+
+use heapless::Vec;
+
+use crate::{Span, SpanIter};
+
+/// The error returned when there are more [`Span`]s than fit in the
+/// [`heapless::Vec`] given to [`collect_spans`] or [`collect_spans_from`].
+///
+/// It holds everything that's needed to pick up where collecting left off:
+/// the spans that did fit, and a [`SpanIter`] that yields the rest of them.
+#[derive(Debug, Clone)]
+pub struct CapacityError<'a, const N: usize> {
+    /// The first `N` spans
+    pub spans: Vec<Span<'a>, N>,
+    /// The byte offset in the input string of the end of the text of the last
+    /// span in `spans` (or of where collecting started, if `N` is `0`)
+    pub consumed: usize,
+    /// An iterator over the spans that didn't fit, with the same settings and
+    /// formatting state as the one that was being collected
+    pub remainder: SpanIter<'a>,
+}
+
+impl<'a, const N: usize> fmt::Display for CapacityError<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "more than {} spans (stopped at byte {})",
+            N, self.consumed
+        )
+    }
+}
+
+/// Collect the [`Span`]s of `s` into a [`heapless::Vec`] that can hold up to
+/// `N` of them, without allocating.
+///
+/// If there are more than `N` spans, a [`CapacityError`] is returned with the
+/// `N` spans that fit and the means to carry on from there. See
+/// [`collect_spans_from`] to collect from a [`SpanIter`] with different
+/// settings.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{collect_spans, collect_spans_from, Span, Color, Styles};
+///
+/// let s = "§aOne §bTwo §cThree";
+///
+/// assert_eq!(collect_spans::<3>(s).unwrap().len(), 3);
+///
+/// let err = collect_spans::<2>(s).unwrap_err();
+/// assert_eq!(err.spans.len(), 2);
+/// assert_eq!(&s[err.consumed..], "§cThree");
+///
+/// let rest = collect_spans_from::<2>(err.remainder).unwrap();
+/// assert_eq!(rest, [Span::new_styled("Three", Color::Red, Styles::empty())]);
+/// ```
+// The error holds the spans that were collected, and there's no allocator to
+// box them with
+#[allow(clippy::result_large_err)]
+pub fn collect_spans<const N: usize>(s: &str) -> Result<Vec<Span<'_>, N>, CapacityError<'_, N>> {
+    collect_spans_from(SpanIter::new(s))
+}
+
+/// Collect the [`Span`]s from `span_iter` into a [`heapless::Vec`] that can
+/// hold up to `N` of them, without allocating.
+///
+/// See [`collect_spans`] for more details.
+#[allow(clippy::result_large_err)]
+pub fn collect_spans_from<const N: usize>(
+    mut span_iter: SpanIter<'_>,
+) -> Result<Vec<Span<'_>, N>, CapacityError<'_, N>> {
+    let offset = |buf: &str, text: &str| text.as_ptr() as usize - buf.as_ptr() as usize;
+
+    let mut spans = Vec::new();
+    // Nothing has been consumed yet, so this is where the next span starts
+    let mut consumed = match (span_iter.rest, span_iter.escaped_start_char) {
+        (Some(rest), _) => offset(span_iter.buf, rest.text()),
+        (None, Some(idx)) => idx,
+        (None, None) => span_iter.pos,
+    };
+
+    loop {
+        // Keep the iterator from before the span that doesn't fit, so that the
+        // span isn't lost
+        let before = span_iter.clone();
+        let span = match span_iter.next() {
+            Some(span) => span,
+            None => return Ok(spans),
+        };
+        let end = offset(span_iter.buf, span.text()) + span.text().len();
+
+        if spans.push(span).is_err() {
+            return Err(CapacityError {
+                spans,
+                consumed,
+                remainder: before,
+            });
+        }
+        consumed = end;
+    }
+}
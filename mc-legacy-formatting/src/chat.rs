@@ -0,0 +1,249 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde_json::{Map, Value};
+
+use crate::{Color, OwnedSpan, Span, SpanIter, Styles};
+
+/// Flatten a JSON chat component tree into a list of [`OwnedSpan`]s.
+///
+/// Color and styles are inherited down the tree (through `extra` and array
+/// components) the same way the vanilla client does it, and the text of every
+/// leaf component is then run through the legacy parser using the inherited
+/// formatting as its starting state. This means that legacy codes embedded in
+/// component text (which is very common in server list ping responses) are
+/// handled too.
+///
+/// A `§r` inside of component text resets back to [`Color::White`] and
+/// [`Styles::empty()`], just like it does for the legacy parser on its own.
+///
+/// Component fields other than `text`, `color`, `extra`, and the boolean style
+/// fields (`bold`, `italic`, `underlined`, `strikethrough`, `obfuscated`) are
+/// ignored.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{flatten_chat_component, OwnedSpan, Color, Styles};
+///
+/// let component = serde_json::json!({
+///     "text": "",
+///     "extra": [
+///         { "text": "Gold ", "color": "gold", "extra": [{ "text": "and bold", "bold": true }] },
+///         "§cred"
+///     ]
+/// });
+///
+/// assert_eq!(
+///     flatten_chat_component(&component),
+///     vec![
+///         OwnedSpan::new_styled("Gold ", Color::Gold, Styles::empty()),
+///         OwnedSpan::new_styled("and bold", Color::Gold, Styles::BOLD),
+///         OwnedSpan::new_styled("red", Color::Red, Styles::empty()),
+///     ]
+/// );
+/// ```
+pub fn flatten_chat_component(component: &Value) -> Vec<OwnedSpan> {
+    let mut spans = Vec::new();
+    flatten_into(&mut spans, component, Color::White, Styles::empty());
+    spans
+}
+
+/// Build a JSON chat component out of `spans`, in the shape the vanilla
+/// server uses for text translated from legacy codes.
+///
+/// The root component has empty text and every span becomes one of its
+/// `extra` components. Each of those has its `color` and all five style fields
+/// set explicitly, using `false` for the styles the span doesn't have, so that
+/// no formatting is inherited from the root or from whatever the component is
+/// inserted into. Named colors are written as their names (white included)
+/// and a [`Color::Rgb`] as a `#rrggbb` hex string, which needs a 1.16+ client
+/// to display. Spans without any text are skipped.
+///
+/// Start chars in the text of the spans are copied as they are, so
+/// [`flatten_chat_component`] only gives back the same spans if there aren't
+/// any.
+///
+/// `spans` can be [`Span`]s, [`CowSpan`](crate::CowSpan)s, or
+/// [`OwnedSpan`]s.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{to_chat_component, Span, Color, Styles};
+///
+/// let component = to_chat_component(&[Span::new_styled("Hi", Color::Gold, Styles::BOLD)]);
+///
+/// assert_eq!(
+///     component,
+///     serde_json::json!({
+///         "text": "",
+///         "extra": [{
+///             "text": "Hi",
+///             "color": "gold",
+///             "bold": true,
+///             "italic": false,
+///             "underlined": false,
+///             "strikethrough": false,
+///             "obfuscated": false
+///         }]
+///     })
+/// );
+/// ```
+pub fn to_chat_component<'a, S>(spans: &'a [S]) -> Value
+where
+    &'a S: Into<Span<'a>>,
+{
+    let extra = spans
+        .iter()
+        .map(Into::into)
+        .filter(|span: &Span| !span.is_empty())
+        .map(|span| {
+            let mut obj = Map::new();
+            obj.insert("text".to_string(), span.text().into());
+            obj.insert("color".to_string(), color_name(span.color()).into());
+
+            for (field, style) in STYLE_FIELDS {
+                obj.insert(field.to_string(), span.styles().contains(style).into());
+            }
+
+            Value::Object(obj)
+        })
+        .collect::<Vec<_>>();
+
+    let mut root = Map::new();
+    root.insert("text".to_string(), "".into());
+    root.insert("extra".to_string(), extra.into());
+    Value::Object(root)
+}
+
+/// Recursively flatten `component` into `spans`, using `color` and `styles` as
+/// the formatting inherited from the parent component
+fn flatten_into(spans: &mut Vec<OwnedSpan>, component: &Value, color: Color, styles: Styles) {
+    match component {
+        Value::String(text) => push_leaf(spans, text, color, styles),
+        // The first component in an array is the parent of all the rest
+        Value::Array(components) => {
+            if let Some((first, rest)) = components.split_first() {
+                let (color, styles) = match first {
+                    Value::Object(obj) => inherit_formatting(obj, color, styles),
+                    _ => (color, styles),
+                };
+
+                flatten_into(spans, first, color, styles);
+                rest.iter()
+                    .for_each(|c| flatten_into(spans, c, color, styles));
+            }
+        }
+        Value::Object(obj) => {
+            let (color, styles) = inherit_formatting(obj, color, styles);
+
+            if let Some(text) = obj.get("text") {
+                match text {
+                    Value::String(text) => push_leaf(spans, text, color, styles),
+                    Value::Number(_) | Value::Bool(_) => {
+                        push_leaf(spans, &text.to_string(), color, styles)
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(Value::Array(extra)) = obj.get("extra") {
+                extra
+                    .iter()
+                    .for_each(|c| flatten_into(spans, c, color, styles));
+            }
+        }
+        Value::Number(_) | Value::Bool(_) => {
+            push_leaf(spans, &component.to_string(), color, styles)
+        }
+        Value::Null => {}
+    }
+}
+
+/// Run the legacy parser over the text of a leaf component, starting from the
+/// given formatting
+fn push_leaf(spans: &mut Vec<OwnedSpan>, text: &str, color: Color, styles: Styles) {
+    let span_iter = SpanIter::new(text).with_initial_state(color, styles);
+    spans.extend(span_iter.map(OwnedSpan::from));
+}
+
+/// Apply the formatting fields of a component on top of the formatting it
+/// inherited from its parent
+fn inherit_formatting(obj: &Map<String, Value>, color: Color, styles: Styles) -> (Color, Styles) {
+    let color = obj
+        .get("color")
+        .and_then(Value::as_str)
+        .and_then(color_from_name)
+        .unwrap_or(color);
+    let mut styles = styles;
+
+    for (field, style) in STYLE_FIELDS {
+        if let Some(enabled) = obj.get(field).and_then(Value::as_bool) {
+            styles.set(style, enabled);
+        }
+    }
+
+    (color, styles)
+}
+
+/// The boolean style fields of a component and the style each one sets
+const STYLE_FIELDS: [(&str, Styles); 5] = [
+    ("obfuscated", Styles::RANDOM),
+    ("bold", Styles::BOLD),
+    ("strikethrough", Styles::STRIKETHROUGH),
+    ("underlined", Styles::UNDERLINED),
+    ("italic", Styles::ITALIC),
+];
+
+/// Map the name (or `#rrggbb` hex string) of a color used in JSON chat
+/// components to a [`Color`]
+fn color_from_name(name: &str) -> Option<Color> {
+    Some(match name {
+        hex if hex.starts_with('#') => return Color::from_hex_str(hex),
+        "black" => Color::Black,
+        "dark_blue" => Color::DarkBlue,
+        "dark_green" => Color::DarkGreen,
+        "dark_aqua" => Color::DarkAqua,
+        "dark_red" => Color::DarkRed,
+        "dark_purple" => Color::DarkPurple,
+        "gold" => Color::Gold,
+        "gray" => Color::Gray,
+        "dark_gray" => Color::DarkGray,
+        "blue" => Color::Blue,
+        "green" => Color::Green,
+        "aqua" => Color::Aqua,
+        "red" => Color::Red,
+        "light_purple" => Color::LightPurple,
+        "yellow" => Color::Yellow,
+        "white" | "reset" => Color::White,
+        _ => return None,
+    })
+}
+
+/// Get the name (or `#rrggbb` hex string) used for a [`Color`] in JSON chat
+/// components
+fn color_name(color: Color) -> String {
+    match color {
+        Color::Black => "black",
+        Color::DarkBlue => "dark_blue",
+        Color::DarkGreen => "dark_green",
+        Color::DarkAqua => "dark_aqua",
+        Color::DarkRed => "dark_red",
+        Color::DarkPurple => "dark_purple",
+        Color::Gold => "gold",
+        Color::Gray => "gray",
+        Color::DarkGray => "dark_gray",
+        Color::Blue => "blue",
+        Color::Green => "green",
+        Color::Aqua => "aqua",
+        Color::Red => "red",
+        Color::LightPurple => "light_purple",
+        Color::Yellow => "yellow",
+        Color::White => "white",
+        Color::Rgb(..) => return color.foreground_hex_str().to_string(),
+    }
+    .to_string()
+}
@@ -0,0 +1,129 @@
+use alloc::borrow::Cow;
+use core::iter::Peekable;
+
+use crate::{CowSpan, Span};
+
+/// Merge adjacent spans that have the same color, styles, and background.
+///
+/// Text often ends up split into more spans than it needs, such as when a
+/// code is repeated (`§6>§6>`) or after spans have been transformed. This
+/// yields one [`CowSpan`] for each run of spans with identical formatting, so
+/// the output renders the same way with fewer spans. [`Span::Url`]s are never
+/// merged with anything.
+///
+/// Spans that aren't merged keep borrowing their text. Merged text is
+/// allocated unless the pieces sit next to each other in the string given to
+/// [`Coalesce::with_buffer`]. The kind of each merged span is picked for its
+/// new text, so strikethrough whitespace followed by strikethrough text
+/// becomes a single [`CowSpan::Styled`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{coalesce, CowSpan, SpanExt, Color, Styles};
+///
+/// let spans: Vec<CowSpan> = coalesce("§6>§6§l>§6§l>§r!".span_iter()).collect();
+///
+/// assert_eq!(
+///     spans,
+///     vec![
+///         CowSpan::new_styled(">", Color::Gold, Styles::empty()),
+///         CowSpan::new_styled(">>", Color::Gold, Styles::BOLD),
+///         CowSpan::new_plain("!"),
+///     ]
+/// );
+/// ```
+pub fn coalesce<'a, I>(spans: I) -> Coalesce<'a, I::IntoIter>
+where
+    I: IntoIterator<Item = Span<'a>>,
+{
+    Coalesce {
+        spans: spans.into_iter().peekable(),
+        buf: None,
+    }
+}
+
+/// An iterator that merges adjacent spans with the same formatting.
+///
+/// Created by [`coalesce`].
+#[derive(Debug, Clone)]
+pub struct Coalesce<'a, I: Iterator<Item = Span<'a>>> {
+    spans: Peekable<I>,
+    buf: Option<&'a str>,
+}
+
+impl<'a, I: Iterator<Item = Span<'a>>> Coalesce<'a, I> {
+    /// Borrow merged text from `buf` instead of allocating when the text of
+    /// the merged spans is contiguous in it
+    ///
+    /// `buf` should be the string the spans were parsed from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use mc_legacy_formatting::{coalesce, CowSpan, SpanExt};
+    ///
+    /// let s = "first\nsecond";
+    /// let spans: Vec<CowSpan> = coalesce(s.span_iter().split_on_newline(true))
+    ///     .with_buffer(s)
+    ///     .collect();
+    ///
+    /// assert_eq!(spans, vec![CowSpan::new_plain("first\nsecond")]);
+    /// assert!(matches!(spans[0], CowSpan::Plain(Cow::Borrowed(_))));
+    /// ```
+    pub fn with_buffer(mut self, buf: &'a str) -> Self {
+        self.buf = Some(buf);
+        self
+    }
+
+    /// Append `next` to `text`, borrowing from `buf` if possible
+    fn join(&self, text: Cow<'a, str>, next: &'a str) -> Cow<'a, str> {
+        if let (Cow::Borrowed(prev), Some(buf)) = (&text, self.buf) {
+            let joined = offset_in(buf, prev)
+                .filter(|&start| offset_in(buf, next) == Some(start + prev.len()))
+                .and_then(|start| buf.get(start..start + prev.len() + next.len()));
+
+            if let Some(joined) = joined {
+                return Cow::Borrowed(joined);
+            }
+        }
+
+        let mut text = text.into_owned();
+        text.push_str(next);
+        Cow::Owned(text)
+    }
+}
+
+impl<'a, I: Iterator<Item = Span<'a>>> Iterator for Coalesce<'a, I> {
+    type Item = CowSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.spans.next()?;
+        let mut text = None;
+
+        while let Some(next) = self.spans.next_if(|next| can_merge(&first, next)) {
+            let joined = self.join(text.unwrap_or(Cow::Borrowed(first.text())), next.text());
+            text = Some(joined);
+        }
+
+        Some(match text {
+            Some(text) => first.map_text(|_| text),
+            None => first.into(),
+        })
+    }
+}
+
+fn can_merge(a: &Span, b: &Span) -> bool {
+    let is_url = |span: &Span| matches!(span, Span::Url { .. });
+
+    !is_url(a)
+        && !is_url(b)
+        && (a.color(), a.styles(), a.background()) == (b.color(), b.styles(), b.background())
+}
+
+/// Get the byte offset of `s` in `buf`, if `s` is a slice of `buf`
+fn offset_in(buf: &str, s: &str) -> Option<usize> {
+    let start = (s.as_ptr() as usize).checked_sub(buf.as_ptr() as usize)?;
+    Some(start).filter(|start| start + s.len() <= buf.len())
+}
@@ -0,0 +1,332 @@
+use core::{fmt, ops::Range, str::CharIndices};
+
+use crate::{Color, Styles, RESET_CODE};
+
+/// The kind of a formatting code.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CodeKind {
+    /// A code that sets the color (and resets the styles)
+    Color(Color),
+    /// A code that adds a style
+    Style(Styles),
+    /// The `RESET` code
+    Reset,
+    /// A start char followed by a character that isn't a valid code (or by
+    /// nothing at all)
+    ///
+    /// The parser treats these as plain text.
+    Invalid,
+}
+
+/// What a custom code recognized by the hook given to
+/// [`SpanIter::with_custom_code`](crate::SpanIter::with_custom_code) does.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CustomAction {
+    /// Set the color and reset the styles, like a color code
+    Color(Color),
+    /// Add styles, like a style code
+    Style(Styles),
+    /// Set both the color and the styles
+    Set(Color, Styles),
+    /// Reset the formatting, like the `RESET` code
+    Reset,
+}
+
+/// Classify the character following a start char.
+///
+/// This is the single place the meaning of a code char is decided, so it
+/// saves callers from combining [`Color::from_char`] and [`Styles::from_char`]
+/// and then special-casing the reset code (which neither of them accepts).
+/// Code chars are case-insensitive. Codes that take up more than one char
+/// (like hex colors) aren't recognized; the `x` that begins one is
+/// [`CodeKind::Invalid`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{classify_code, CodeKind, Color, Styles};
+///
+/// assert_eq!(classify_code('6'), CodeKind::Color(Color::Gold));
+/// assert_eq!(classify_code('L'), CodeKind::Style(Styles::BOLD));
+/// assert_eq!(classify_code('r'), CodeKind::Reset);
+/// assert_eq!(classify_code('z'), CodeKind::Invalid);
+/// ```
+pub fn classify_code(c: char) -> CodeKind {
+    if let Some(color) = Color::from_char(c) {
+        CodeKind::Color(color)
+    } else if let Some(style) = Styles::from_char(c) {
+        CodeKind::Style(style)
+    } else if c.eq_ignore_ascii_case(&RESET_CODE) {
+        CodeKind::Reset
+    } else {
+        CodeKind::Invalid
+    }
+}
+
+/// Find the byte range of every formatting code in `s`, including redundant
+/// and invalid ones.
+///
+/// Each range covers the start char and the character following it (or just
+/// the start char if it's the last character in `s`). Codes are paired up the
+/// same way [`SpanIter`](crate::SpanIter) pairs them, so the ranges of valid
+/// codes never overlap the ranges reported by
+/// [`SpanIter::with_ranges`](crate::SpanIter::with_ranges). [`CodeKind::Invalid`]
+/// ranges are displayed as text by the parser and so are part of those ranges.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{code_ranges, CodeKind, Color, Styles};
+///
+/// let mut codes = code_ranges("&4&lhi&z", '&');
+///
+/// assert_eq!(codes.next().unwrap(), (0..2, CodeKind::Color(Color::DarkRed)));
+/// assert_eq!(codes.next().unwrap(), (2..4, CodeKind::Style(Styles::BOLD)));
+/// assert_eq!(codes.next().unwrap(), (6..8, CodeKind::Invalid));
+/// assert!(codes.next().is_none());
+/// ```
+pub fn code_ranges(s: &str, start_char: char) -> CodeRanges<'_> {
+    CodeRanges {
+        chars: s.char_indices(),
+        len: s.len(),
+        start_char,
+    }
+}
+
+/// Check whether `s` contains at least one valid formatting code.
+///
+/// A start char that isn't followed by a valid code doesn't count, since the
+/// parser treats it as text. This is a quick scan that doesn't build any
+/// spans.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::contains_formatting;
+///
+/// assert!(contains_formatting("some §cred text", '§'));
+/// assert!(!contains_formatting("just a § sign", '§'));
+/// ```
+pub fn contains_formatting(s: &str, start_char: char) -> bool {
+    first_code_offset(s, start_char).is_some()
+}
+
+/// Find the byte offset of the start char of the first valid formatting code
+/// in `s`.
+///
+/// See [`contains_formatting`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::first_code_offset;
+///
+/// assert_eq!(first_code_offset("Tom & Jerry &6gold", '&'), Some(12));
+/// assert_eq!(first_code_offset("Tom & Jerry", '&'), None);
+/// ```
+pub fn first_code_offset(s: &str, start_char: char) -> Option<usize> {
+    code_ranges(s, start_char)
+        .find(|(_, kind)| *kind != CodeKind::Invalid)
+        .map(|(range, _)| range.start)
+}
+
+/// Check that every start char in `s` begins a valid formatting code.
+///
+/// Returns an error pointing at the first start char that isn't followed by a
+/// valid code, including a start char at the very end of `s`. This is a
+/// quick scan that doesn't build any spans, for things like underlining
+/// mistakes in an editor.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{validate, ParseError};
+///
+/// assert_eq!(validate("&6Gold &lbold", '&'), Ok(()));
+/// assert_eq!(
+///     validate("&6Gold &zbold", '&'),
+///     Err(ParseError { byte_offset: 7, code: Some('z') })
+/// );
+/// assert_eq!(
+///     validate("Gold&", '&'),
+///     Err(ParseError { byte_offset: 4, code: None })
+/// );
+/// ```
+pub fn validate(s: &str, start_char: char) -> Result<(), ParseError> {
+    match code_ranges(s, start_char).find(|(_, kind)| *kind == CodeKind::Invalid) {
+        Some((range, _)) => Err(ParseError {
+            byte_offset: range.start,
+            code: s[range].chars().nth(1),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// A start char that isn't followed by a valid formatting code.
+///
+/// Returned by [`validate`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ParseError {
+    /// The byte offset of the start char
+    pub byte_offset: usize,
+    /// The char following the start char, or `None` if the start char is the
+    /// last char in the string
+    pub code: Option<char>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Some(c) => write!(
+                f,
+                "invalid formatting code {:?} at byte {}",
+                c, self.byte_offset
+            ),
+            None => write!(f, "lone start char at byte {}", self.byte_offset),
+        }
+    }
+}
+
+/// An iterator over the formatting codes in a string.
+///
+/// Created by [`code_ranges`].
+#[derive(Debug, Clone)]
+pub struct CodeRanges<'a> {
+    chars: CharIndices<'a>,
+    len: usize,
+    start_char: char,
+}
+
+impl Iterator for CodeRanges<'_> {
+    type Item = (Range<usize>, CodeKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start_char = self.start_char;
+        let (start, _) = self.chars.find(|&(_, c)| c == start_char)?;
+
+        Some(match self.chars.next() {
+            Some((idx, c)) => (start..idx + c.len_utf8(), classify_code(c)),
+            None => (start..self.len, CodeKind::Invalid),
+        })
+    }
+}
+
+/// A piece of a string with legacy formatting codes, before any of the codes
+/// have been interpreted.
+///
+/// Produced by [`raw_tokens`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Token<'a> {
+    /// A run of text
+    Text(&'a str),
+    /// A color code
+    Color(Color),
+    /// A style code
+    Style(Styles),
+    /// The `RESET` code
+    Reset,
+    /// A start char that isn't followed by a valid code (or by anything at
+    /// all)
+    ///
+    /// The parser displays it as text. The character following it is always
+    /// the beginning of the next [`Token::Text`], even if it's another start
+    /// char.
+    LiteralStart,
+}
+
+/// Split `s` into text and formatting codes without resolving them into
+/// formatting state.
+///
+/// Codes are paired up with start chars the same way [`SpanIter`](crate::SpanIter)
+/// pairs them, but nothing else is decided for you: a color code doesn't clear
+/// the styles, a reset is just a [`Token::Reset`], and start chars that don't
+/// begin a valid code are reported as [`Token::LiteralStart`]. This is the
+/// building block for renderers that want to interpret codes differently
+/// from the vanilla client. Folding the tokens with
+/// [`apply_code`](crate::apply_code) gives the same formatting that
+/// [`SpanIter`](crate::SpanIter) does.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{raw_tokens, Color, Styles, Token};
+///
+/// let tokens: Vec<Token> = raw_tokens("&lBold &6gold &zhi", '&').collect();
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Token::Style(Styles::BOLD),
+///         Token::Text("Bold "),
+///         Token::Color(Color::Gold),
+///         Token::Text("gold "),
+///         Token::LiteralStart,
+///         Token::Text("zhi"),
+///     ]
+/// );
+/// ```
+pub fn raw_tokens(s: &str, start_char: char) -> RawTokens<'_> {
+    RawTokens {
+        rest: s,
+        start_char,
+        after_literal_start: false,
+    }
+}
+
+/// An iterator over the [`Token`]s in a string.
+///
+/// Created by [`raw_tokens`].
+#[derive(Debug, Clone)]
+pub struct RawTokens<'a> {
+    rest: &'a str,
+    start_char: char,
+    /// Whether the last token was a [`Token::LiteralStart`], in which case
+    /// the next char is text no matter what it is
+    after_literal_start: bool,
+}
+
+impl<'a> Iterator for RawTokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.rest.chars().next()?;
+        let text_start = if core::mem::take(&mut self.after_literal_start) {
+            first.len_utf8()
+        } else {
+            0
+        };
+        let text_len = self.rest[text_start..]
+            .find(self.start_char)
+            .map_or(self.rest.len(), |offset| text_start + offset);
+
+        if text_len > 0 {
+            let (text, rest) = self.rest.split_at(text_len);
+            self.rest = rest;
+            return Some(Token::Text(text));
+        }
+
+        // `rest` begins with a start char
+        let after_start = &self.rest[self.start_char.len_utf8()..];
+        let code = after_start.chars().next().and_then(|c| {
+            let token = match classify_code(c) {
+                CodeKind::Color(color) => Token::Color(color),
+                CodeKind::Style(style) => Token::Style(style),
+                CodeKind::Reset => Token::Reset,
+                CodeKind::Invalid => return None,
+            };
+            Some((token, c.len_utf8()))
+        });
+
+        match code {
+            Some((token, code_len)) => {
+                self.rest = &after_start[code_len..];
+                Some(token)
+            }
+            None => {
+                self.rest = after_start;
+                self.after_literal_start = true;
+                Some(Token::LiteralStart)
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use core::fmt::Display;
+use core::fmt::{self, Display};
 
-use crate::{Color, Span, Styles};
+use crate::{legacy_display::color_code, Color, DisplaySpans, Span, Styles};
 
 /// A wrapper around [`Span`] that provides colored pretty-printing
 ///
@@ -15,21 +15,298 @@ use crate::{Color, Span, Styles};
 ///
 /// // Output will look close to what you'd see in Minecraft (ignoring the font difference)
 /// ```
-pub struct PrintSpanColored<'a>(Span<'a>);
+pub struct PrintSpanColored<'a> {
+    span: Span<'a>,
+    strikethrough_whitespace: StrikethroughWhitespaceStyle,
+    palette: [colored::Color; 16],
+}
 
 impl<'a> From<Span<'a>> for PrintSpanColored<'a> {
     fn from(s: Span<'a>) -> Self {
-        Self(s)
+        Self {
+            span: s,
+            strikethrough_whitespace: StrikethroughWhitespaceStyle::default(),
+            palette: PrintSpanColored::DEFAULT_PALETTE,
+        }
+    }
+}
+
+impl PrintSpanColored<'_> {
+    /// The terminal colors that named [`Color`]s are printed as by default,
+    /// in the order of their codes (`0` through `f`)
+    ///
+    /// This is the same mapping as the conversion from [`Color`] into
+    /// `colored::Color`.
+    pub const DEFAULT_PALETTE: [colored::Color; 16] = [
+        colored::Color::Black,
+        colored::Color::Blue,
+        colored::Color::Green,
+        colored::Color::Cyan,
+        colored::Color::Red,
+        colored::Color::Magenta,
+        colored::Color::Yellow,
+        colored::Color::White,
+        colored::Color::BrightBlack,
+        colored::Color::BrightBlue,
+        colored::Color::BrightGreen,
+        colored::Color::BrightCyan,
+        colored::Color::BrightRed,
+        colored::Color::BrightMagenta,
+        colored::Color::BrightYellow,
+        colored::Color::BrightWhite,
+    ];
+}
+
+impl<'a> PrintSpanColored<'a> {
+    /// Set how [`Span::StrikethroughWhitespace`] is printed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, StrikethroughWhitespaceStyle};
+    ///
+    /// let s = "§m     §r text";
+    /// s.span_iter()
+    ///     .map(|span| {
+    ///         span.wrap_colored()
+    ///             .with_strikethrough_whitespace_style(StrikethroughWhitespaceStyle::SpacesWithLine)
+    ///     })
+    ///     .for_each(|s| print!("{}", s));
+    /// println!();
+    /// ```
+    pub fn with_strikethrough_whitespace_style(
+        mut self,
+        style: StrikethroughWhitespaceStyle,
+    ) -> Self {
+        self.strikethrough_whitespace = style;
+        self
+    }
+
+    /// Set the terminal colors that named [`Color`]s are printed as
+    ///
+    /// `palette` has an entry for each named color in the order of their
+    /// codes (`0` through `f`), and defaults to
+    /// [`PrintSpanColored::DEFAULT_PALETTE`]. Some terminal themes make the
+    /// default choices look washed out, or make colors like [`Color::Gray`]
+    /// and [`Color::White`] hard to tell apart. [`Color::Rgb`] is always
+    /// printed as truecolor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, PrintSpanColored};
+    ///
+    /// let mut palette = PrintSpanColored::DEFAULT_PALETTE;
+    /// // Print gray as a dimmer gray than the terminal's white
+    /// palette[7] = colored::Color::TrueColor { r: 170, g: 170, b: 170 };
+    ///
+    /// "§7gray §fwhite"
+    ///     .span_iter()
+    ///     .map(|span| span.wrap_colored().with_palette(palette))
+    ///     .for_each(|s| print!("{}", s));
+    /// println!();
+    /// ```
+    pub fn with_palette(mut self, palette: [colored::Color; 16]) -> Self {
+        self.palette = palette;
+        self
+    }
+}
+
+/// How [`PrintSpanColored`] prints a [`Span::StrikethroughWhitespace`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StrikethroughWhitespaceStyle {
+    /// Print a `-` for each char of whitespace
+    ///
+    /// This looks like a line on any terminal, even one that doesn't support
+    /// the strikethrough attribute. This is the default.
+    Dashes,
+    /// Print a space for each char of whitespace, with the strikethrough
+    /// attribute set
+    ///
+    /// This is closer to what the vanilla client draws, but the line only
+    /// shows up on terminals that support the attribute.
+    SpacesWithLine,
+    /// Print a `─` (box drawing light horizontal) for each char of whitespace
+    ///
+    /// This draws a solid line in most fonts, at the cost of not being ASCII.
+    BoxDrawing,
+    /// Print a `_` for each char of whitespace
+    Underscore,
+}
+
+impl StrikethroughWhitespaceStyle {
+    /// The text printed for each char of whitespace
+    fn replacement(&self) -> &'static str {
+        match self {
+            StrikethroughWhitespaceStyle::Dashes => "-",
+            StrikethroughWhitespaceStyle::SpacesWithLine => " ",
+            StrikethroughWhitespaceStyle::BoxDrawing => "─",
+            StrikethroughWhitespaceStyle::Underscore => "_",
+        }
+    }
+}
+
+impl Default for StrikethroughWhitespaceStyle {
+    fn default() -> Self {
+        StrikethroughWhitespaceStyle::Dashes
+    }
+}
+
+/// Colored pretty-printing for every span yielded by an iterator
+///
+/// Unlike printing each span with [`PrintSpanColored`] (which resets the
+/// terminal's styling after every span), only the escape sequences needed to
+/// change the formatting from one span to the next are written, and the
+/// styling is reset once at the very end. Text with the `RANDOM` style is
+/// printed with the hidden attribute, since terminals can't scramble text.
+///
+/// Colors are only written if the `colored` crate would write them (it checks
+/// environment variables such as `NO_COLOR`); otherwise this prints the same
+/// thing as [`DisplaySpans`]. The iterator is cloned every time it's
+/// displayed.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{ColoredSpans, SpanExt};
+///
+/// let s = "§4This will be dark red §oand italic";
+/// println!("{}", ColoredSpans::new(s.span_iter()));
+///
+/// // Output will look close to what you'd see in Minecraft (ignoring the font difference)
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColoredSpans<I> {
+    spans: I,
+    strikethrough_whitespace: StrikethroughWhitespaceStyle,
+    palette: [colored::Color; 16],
+}
+
+impl<I> ColoredSpans<I> {
+    /// Create a new [`ColoredSpans`] for the spans yielded by `spans`
+    pub fn new(spans: I) -> Self {
+        Self {
+            spans,
+            strikethrough_whitespace: StrikethroughWhitespaceStyle::default(),
+            palette: PrintSpanColored::DEFAULT_PALETTE,
+        }
+    }
+
+    /// Set how [`Span::StrikethroughWhitespace`] is printed
+    ///
+    /// See [`PrintSpanColored::with_strikethrough_whitespace_style`].
+    pub fn with_strikethrough_whitespace_style(
+        mut self,
+        style: StrikethroughWhitespaceStyle,
+    ) -> Self {
+        self.strikethrough_whitespace = style;
+        self
+    }
+
+    /// Set the terminal colors that named [`Color`]s are printed as
+    ///
+    /// See [`PrintSpanColored::with_palette`].
+    pub fn with_palette(mut self, palette: [colored::Color; 16]) -> Self {
+        self.palette = palette;
+        self
+    }
+}
+
+impl<'a, I: Iterator<Item = Span<'a>> + Clone> Display for ColoredSpans<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+            return Display::fmt(&DisplaySpans::new(self.spans.clone()), f);
+        }
+
+        // The formatting the terminal is currently using, where `None` is the
+        // terminal's default color
+        let mut current = (None, Styles::empty());
+        let mut styled = false;
+
+        for span in self.spans.clone().filter(|span| !span.is_empty()) {
+            let color = match span {
+                Span::Plain(_) => None,
+                _ => Some(span.color()),
+            };
+            let formatting = (color, span.styles());
+
+            if formatting != current {
+                write_sgr(f, &self.palette, current, formatting)?;
+                current = formatting;
+                styled = true;
+            }
+
+            match span {
+                Span::StrikethroughWhitespace { text, .. } => {
+                    let replacement = self.strikethrough_whitespace.replacement();
+                    (0..text.len()).try_for_each(|_| f.write_str(replacement))?;
+                }
+                _ => f.write_str(span.text())?,
+            }
+        }
+
+        if styled {
+            f.write_str("\x1b[0m")?;
+        }
+
+        Ok(())
     }
 }
 
+/// The SGR parameters that turn each style on and off
+const STYLE_SGR: [(Styles, &str, &str); 5] = [
+    (Styles::RANDOM, "8", "28"),
+    (Styles::BOLD, "1", "22"),
+    (Styles::STRIKETHROUGH, "9", "29"),
+    (Styles::UNDERLINED, "4", "24"),
+    (Styles::ITALIC, "3", "23"),
+];
+
+/// Write an SGR escape sequence that changes the terminal's formatting from
+/// `from` to `to`
+fn write_sgr(
+    f: &mut fmt::Formatter,
+    palette: &[colored::Color; 16],
+    from: (Option<Color>, Styles),
+    to: (Option<Color>, Styles),
+) -> fmt::Result {
+    let color = if to.0 == from.0 {
+        None
+    } else {
+        Some(match to.0 {
+            Some(color) => to_colored(color, palette).to_fg_str(),
+            None => "39".into(),
+        })
+    };
+    let styles = STYLE_SGR.iter().filter_map(|&(style, on, off)| {
+        match (from.1.contains(style), to.1.contains(style)) {
+            (false, true) => Some(on),
+            (true, false) => Some(off),
+            _ => None,
+        }
+    });
+
+    f.write_str("\x1b[")?;
+    for (i, param) in color.as_deref().into_iter().chain(styles).enumerate() {
+        if i > 0 {
+            f.write_str(";")?;
+        }
+        f.write_str(param)?;
+    }
+    f.write_str("m")
+}
+
 impl<'a> Display for PrintSpanColored<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        fn apply_color_and_styles(s: &str, color: Color, styles: Styles) -> colored::ColoredString {
+        let apply_color_and_styles = |s: &str, color: Color, styles: Styles| {
             use self::Styles as McStyles;
             use colored::*;
 
-            let mut text = s.color(color);
+            let mut text = s.color(to_colored(color, &self.palette));
+
+            if let Some(background) = self.span.background() {
+                text = text.on_color(to_colored(background, &self.palette));
+            }
 
             // TODO: handle random style
 
@@ -50,47 +327,90 @@ impl<'a> Display for PrintSpanColored<'a> {
             }
 
             text
-        }
+        };
 
-        match self.0 {
+        match self.span {
             Span::Styled {
                 text,
                 color,
                 styles,
+                ..
+            }
+            | Span::Url {
+                text,
+                color,
+                styles,
             } => {
                 let styled_text = apply_color_and_styles(text, color, styles);
                 Display::fmt(&styled_text, f)
             }
-            Span::Plain(_) => Display::fmt(&self.0, f),
+            Span::Plain(_) => Display::fmt(&self.span, f),
             Span::StrikethroughWhitespace {
                 text,
                 color,
                 styles,
-            } => (0..text.len())
-                .try_for_each(|_| Display::fmt(&apply_color_and_styles("-", color, styles), f)),
+            } => {
+                let replacement = self.strikethrough_whitespace.replacement();
+
+                (0..text.len()).try_for_each(|_| {
+                    Display::fmt(&apply_color_and_styles(replacement, color, styles), f)
+                })
+            }
         }
     }
 }
 
 impl From<Color> for colored::Color {
     fn from(c: Color) -> Self {
-        match c {
-            Color::Black => colored::Color::Black,
-            Color::DarkBlue => colored::Color::Blue,
-            Color::DarkGreen => colored::Color::Green,
-            Color::DarkAqua => colored::Color::Cyan,
-            Color::DarkRed => colored::Color::Red,
-            Color::DarkPurple => colored::Color::Magenta,
-            Color::Gold => colored::Color::Yellow,
-            Color::Gray => colored::Color::White,
-            Color::DarkGray => colored::Color::BrightBlack,
-            Color::Blue => colored::Color::BrightBlue,
-            Color::Green => colored::Color::BrightGreen,
-            Color::Aqua => colored::Color::BrightCyan,
-            Color::Red => colored::Color::BrightRed,
-            Color::LightPurple => colored::Color::BrightMagenta,
-            Color::Yellow => colored::Color::BrightYellow,
-            Color::White => colored::Color::BrightWhite,
-        }
+        to_colored(c, &PrintSpanColored::DEFAULT_PALETTE)
+    }
+}
+
+/// Get the terminal color `color` is printed as with the given palette
+fn to_colored(color: Color, palette: &[colored::Color; 16]) -> colored::Color {
+    match color {
+        Color::Rgb(r, g, b) => colored::Color::TrueColor { r, g, b },
+        // Named colors always have a hex digit code
+        named => palette[color_code(named).to_digit(16).unwrap_or(15) as usize],
+    }
+}
+
+/// Convert a [`colored::Color`] back to the closest [`Color`].
+///
+/// This is the inverse of the `From<Color>` conversion for `colored::Color`:
+/// the 16 basic terminal colors map back to the named colors they're printed
+/// as, and truecolor is mapped to the [nearest named color](Color::nearest_named).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{color_from_colored, Color};
+///
+/// assert_eq!(color_from_colored(colored::Color::Red), Color::DarkRed);
+/// assert_eq!(color_from_colored(colored::Color::BrightRed), Color::Red);
+/// assert_eq!(
+///     color_from_colored(colored::Color::TrueColor { r: 250, g: 160, b: 10 }),
+///     Color::Gold
+/// );
+/// ```
+pub fn color_from_colored(c: colored::Color) -> Color {
+    match c {
+        colored::Color::Black => Color::Black,
+        colored::Color::Blue => Color::DarkBlue,
+        colored::Color::Green => Color::DarkGreen,
+        colored::Color::Cyan => Color::DarkAqua,
+        colored::Color::Red => Color::DarkRed,
+        colored::Color::Magenta => Color::DarkPurple,
+        colored::Color::Yellow => Color::Gold,
+        colored::Color::White => Color::Gray,
+        colored::Color::BrightBlack => Color::DarkGray,
+        colored::Color::BrightBlue => Color::Blue,
+        colored::Color::BrightGreen => Color::Green,
+        colored::Color::BrightCyan => Color::Aqua,
+        colored::Color::BrightRed => Color::Red,
+        colored::Color::BrightMagenta => Color::LightPurple,
+        colored::Color::BrightYellow => Color::Yellow,
+        colored::Color::BrightWhite => Color::White,
+        colored::Color::TrueColor { r, g, b } => Color::Rgb(r, g, b).nearest_named(),
     }
 }
@@ -0,0 +1,146 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::Span;
+
+/// Split `spans` into cells at each occurrence of `delimiter` in their visible
+/// text, for layouts (such as scoreboards and tab list headers) that separate
+/// columns with something like `" | "`.
+///
+/// Each cell is yielded as a [`Vec`] of the spans in it. The delimiter is
+/// matched against the visible text, so it's found even if it's split across
+/// spans with different formatting, and it isn't included in any cell. Spans
+/// containing a delimiter are split into spans with the same formatting (a
+/// [`Span::Url`] stays a URL), so formatting carries from one cell into the
+/// next exactly like it does in the source.
+///
+/// Like [`str::split`], this always yields at least one cell, and cells can be
+/// empty (such as when the text starts or ends with the delimiter, or two
+/// delimiters are next to each other). An empty `delimiter` never matches.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{split_columns, SpanExt, Span, Color, Styles};
+///
+/// let cells: Vec<_> = split_columns("§6Kills: §a5 §7| §6Deaths: §c2".span_iter(), " | ").collect();
+///
+/// assert_eq!(
+///     cells,
+///     vec![
+///         vec![
+///             Span::new_styled("Kills: ", Color::Gold, Styles::empty()),
+///             Span::new_styled("5", Color::Green, Styles::empty()),
+///         ],
+///         vec![
+///             Span::new_styled("Deaths: ", Color::Gold, Styles::empty()),
+///             Span::new_styled("2", Color::Red, Styles::empty()),
+///         ],
+///     ]
+/// );
+/// ```
+pub fn split_columns<'a, 'd, I>(spans: I, delimiter: &'d str) -> SplitColumns<'a, 'd, I::IntoIter>
+where
+    I: IntoIterator<Item = Span<'a>>,
+{
+    SplitColumns {
+        spans: spans.into_iter(),
+        delimiter,
+        remainder: None,
+        finished: false,
+    }
+}
+
+/// An iterator over the cells of text separated by a delimiter.
+///
+/// Created by [`split_columns`].
+#[derive(Debug, Clone)]
+pub struct SplitColumns<'a, 'd, I: Iterator<Item = Span<'a>>> {
+    spans: I,
+    delimiter: &'d str,
+    /// The part of the last span from `spans` that's after a delimiter and
+    /// hasn't been added to a cell yet
+    remainder: Option<Span<'a>>,
+    finished: bool,
+}
+
+impl<'a, 'd, I: Iterator<Item = Span<'a>>> Iterator for SplitColumns<'a, 'd, I> {
+    type Item = Vec<Span<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut cell = Vec::new();
+        // The visible text of `cell`
+        let mut text = String::new();
+
+        loop {
+            let span = match self.remainder.take().or_else(|| self.spans.next()) {
+                Some(span) => span,
+                None => {
+                    self.finished = true;
+                    return Some(cell);
+                }
+            };
+
+            if self.delimiter.is_empty() {
+                cell.push(span);
+                continue;
+            }
+
+            // Only a delimiter that ends in this span can be new
+            let mut search_from = text.len().saturating_sub(self.delimiter.len() - 1);
+            while !text.is_char_boundary(search_from) {
+                search_from -= 1;
+            }
+            text.push_str(span.text());
+            cell.push(span);
+
+            if let Some(offset) = text[search_from..].find(self.delimiter) {
+                let start = search_from + offset;
+                let end = start + self.delimiter.len();
+
+                // The delimiter ends in the last span of the cell, so anything
+                // after it is the start of the next cell
+                let last_start = text.len() - span.text().len();
+                let after = &span.text()[end - last_start..];
+                if !after.is_empty() {
+                    self.remainder = Some(piece(span, after));
+                }
+
+                truncate_cell(&mut cell, start);
+                return Some(cell);
+            }
+        }
+    }
+}
+
+/// Cut `cell` down to the first `len` bytes of its visible text
+fn truncate_cell<'a>(cell: &mut Vec<Span<'a>>, len: usize) {
+    let mut pos = 0;
+    let mut keep = 0;
+
+    for span in cell.iter_mut() {
+        if pos >= len {
+            break;
+        }
+
+        let text = span.text();
+        if pos + text.len() > len {
+            *span = piece(*span, &text[..len - pos]);
+        }
+        pos += text.len();
+        keep += 1;
+    }
+
+    cell.truncate(keep);
+}
+
+/// Make a span with the formatting of `span` and a piece of its text
+fn piece<'a>(span: Span<'a>, text: &'a str) -> Span<'a> {
+    match span {
+        Span::Url { color, styles, .. } => Span::new_url(text, color, styles),
+        span => span.with_text(text),
+    }
+}
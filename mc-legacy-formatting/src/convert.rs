@@ -0,0 +1,21 @@
+//! Conversions from [`Color`] into the color types of other crates
+
+use crate::Color;
+
+#[cfg(feature = "rgb")]
+impl From<Color> for rgb::RGB8 {
+    /// Convert using [`Color::foreground_rgb`]
+    fn from(color: Color) -> Self {
+        let (r, g, b) = color.foreground_rgb();
+        rgb::RGB8::new(r, g, b)
+    }
+}
+
+#[cfg(feature = "palette")]
+impl From<Color> for palette::Srgb<u8> {
+    /// Convert using [`Color::foreground_rgb`]
+    fn from(color: Color) -> Self {
+        let (r, g, b) = color.foreground_rgb();
+        palette::Srgb::new(r, g, b)
+    }
+}
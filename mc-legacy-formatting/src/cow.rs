@@ -0,0 +1,285 @@
+use alloc::borrow::Cow;
+use core::fmt::{self, Display};
+
+use crate::{Color, OwnedSpan, Span, Styles};
+
+/// A version of [`Span`] whose text is either borrowed or owned.
+///
+/// This is the middle ground between [`Span`] and [`OwnedSpan`] for pipelines
+/// that edit the text of some spans but not others: the spans that aren't
+/// touched keep borrowing from the input, and only the edited ones allocate.
+/// Created by [`Span::map_text`].
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use mc_legacy_formatting::{to_legacy_string, CowSpan, SpanExt, Color, Styles};
+///
+/// let spans: Vec<CowSpan> = "§6Hello §lPLAYER§r!"
+///     .span_iter()
+///     .map(|span| {
+///         span.map_text(|text| match text {
+///             "PLAYER" => Cow::Owned("Steve".to_string()),
+///             text => Cow::Borrowed(text),
+///         })
+///     })
+///     .collect();
+///
+/// assert!(matches!(spans[0], CowSpan::Styled { text: Cow::Borrowed(_), .. }));
+/// assert_eq!(spans[1], CowSpan::new_styled("Steve", Color::Gold, Styles::BOLD));
+/// assert_eq!(to_legacy_string(&spans, '§'), "§6Hello §lSteve§r!");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CowSpan<'a> {
+    /// A styled string of text
+    Styled {
+        /// The styled text
+        text: Cow<'a, str>,
+        /// The color of the text
+        color: Color,
+        /// Styles that should be applied to the text
+        styles: Styles,
+        /// The color behind the text, if it was given one
+        ///
+        /// See [`Span::background`] for more details.
+        background: Option<Color>,
+    },
+    /// An unbroken sequence of whitespace that was given the
+    /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH) style.
+    ///
+    /// See [`Span::StrikethroughWhitespace`] for more details.
+    StrikethroughWhitespace {
+        /// The styled whitespace
+        text: Cow<'a, str>,
+        /// The color of the whitespace (and therefore the line over it)
+        color: Color,
+        /// Styles applied to the whitespace (will contain at least
+        /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH))
+        styles: Styles,
+    },
+    /// An unstyled string of text
+    ///
+    /// This should be given a default style. The vanilla client
+    /// would use [`Color::White`] and [`Styles::empty()`].
+    Plain(Cow<'a, str>),
+    /// A URL found in the text
+    ///
+    /// See [`Span::Url`] for more details.
+    Url {
+        /// The URL
+        text: Cow<'a, str>,
+        /// The color of the text the URL was found in
+        color: Color,
+        /// Styles applied to the text the URL was found in
+        styles: Styles,
+    },
+}
+
+impl<'a> CowSpan<'a> {
+    /// Create a new [`CowSpan::Plain`]
+    pub fn new_plain(s: impl Into<Cow<'a, str>>) -> Self {
+        CowSpan::Plain(s.into())
+    }
+
+    /// Create a new [`CowSpan::StrikethroughWhitespace`]
+    pub fn new_strikethrough_whitespace(
+        s: impl Into<Cow<'a, str>>,
+        color: Color,
+        styles: Styles,
+    ) -> Self {
+        CowSpan::StrikethroughWhitespace {
+            text: s.into(),
+            color,
+            styles,
+        }
+    }
+
+    /// Create a new [`CowSpan::Styled`] without a background
+    pub fn new_styled(s: impl Into<Cow<'a, str>>, color: Color, styles: Styles) -> Self {
+        CowSpan::Styled {
+            text: s.into(),
+            color,
+            styles,
+            background: None,
+        }
+    }
+
+    /// Create a new [`CowSpan::Url`]
+    pub fn new_url(s: impl Into<Cow<'a, str>>, color: Color, styles: Styles) -> Self {
+        CowSpan::Url {
+            text: s.into(),
+            color,
+            styles,
+        }
+    }
+
+    /// Borrow this [`CowSpan`] as a [`Span`]
+    pub fn as_span(&self) -> Span<'_> {
+        match self {
+            CowSpan::Styled {
+                text,
+                color,
+                styles,
+                background,
+            } => Span::Styled {
+                text,
+                color: *color,
+                styles: *styles,
+                background: *background,
+            },
+            CowSpan::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => Span::new_strikethrough_whitespace(text, *color, *styles),
+            CowSpan::Plain(text) => Span::new_plain(text),
+            CowSpan::Url {
+                text,
+                color,
+                styles,
+            } => Span::new_url(text, *color, *styles),
+        }
+    }
+
+    /// Copy the text of this [`CowSpan`] into an [`OwnedSpan`] (if it's not
+    /// already owned)
+    pub fn into_owned(self) -> OwnedSpan {
+        match self {
+            CowSpan::Styled {
+                text,
+                color,
+                styles,
+                background,
+            } => OwnedSpan::Styled {
+                text: text.into_owned(),
+                color,
+                styles,
+                background,
+            },
+            CowSpan::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => OwnedSpan::new_strikethrough_whitespace(text, color, styles),
+            CowSpan::Plain(text) => OwnedSpan::new_plain(text),
+            CowSpan::Url {
+                text,
+                color,
+                styles,
+            } => OwnedSpan::new_url(text, color, styles),
+        }
+    }
+}
+
+impl<'a> From<Span<'a>> for CowSpan<'a> {
+    fn from(s: Span<'a>) -> Self {
+        match s {
+            Span::Styled {
+                text,
+                color,
+                styles,
+                background,
+            } => CowSpan::Styled {
+                text: text.into(),
+                color,
+                styles,
+                background,
+            },
+            Span::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => CowSpan::new_strikethrough_whitespace(text, color, styles),
+            Span::Plain(text) => CowSpan::new_plain(text),
+            Span::Url {
+                text,
+                color,
+                styles,
+            } => CowSpan::new_url(text, color, styles),
+        }
+    }
+}
+
+impl<'a> From<&'a CowSpan<'_>> for Span<'a> {
+    fn from(s: &'a CowSpan<'_>) -> Self {
+        s.as_span()
+    }
+}
+
+impl Display for CowSpan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.as_span(), f)
+    }
+}
+
+/// Change the text of every span with `f`, keeping its formatting.
+///
+/// This calls [`Span::map_text`] on each span, so spans whose text `f` returns
+/// borrowed stay borrowed, and the kind of span is picked for the new text (a
+/// [`Span::StrikethroughWhitespace`] whose whitespace is mapped to more
+/// whitespace stays one). Useful for things like redacting words without
+/// losing their colors.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use mc_legacy_formatting::{map_spans, to_legacy_string, CowSpan, SpanExt};
+///
+/// let spans: Vec<CowSpan> = map_spans("§cdarn §lit".span_iter(), |text| {
+///     if text.contains("darn") {
+///         Cow::Owned(text.replace("darn", "****"))
+///     } else {
+///         Cow::Borrowed(text)
+///     }
+/// })
+/// .collect();
+///
+/// assert_eq!(to_legacy_string(&spans, '§'), "§c**** §lit");
+/// ```
+pub fn map_spans<'a, I, F>(spans: I, f: F) -> MapSpans<I::IntoIter, F>
+where
+    I: IntoIterator<Item = Span<'a>>,
+    F: FnMut(&'a str) -> Cow<'a, str>,
+{
+    MapSpans {
+        spans: spans.into_iter(),
+        f,
+    }
+}
+
+/// An iterator that changes the text of spans.
+///
+/// Created by [`map_spans`].
+#[derive(Clone)]
+pub struct MapSpans<I, F> {
+    spans: I,
+    f: F,
+}
+
+impl<I: fmt::Debug, F> fmt::Debug for MapSpans<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapSpans")
+            .field("spans", &self.spans)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, I, F> Iterator for MapSpans<I, F>
+where
+    I: Iterator<Item = Span<'a>>,
+    F: FnMut(&'a str) -> Cow<'a, str>,
+{
+    type Item = CowSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        Some(span.map_text(&mut self.f))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
@@ -0,0 +1,381 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{coalesce, Color, CowSpan, OwnedSpan, Span, SpanIter, Styles};
+
+/// A piece of the difference between two formatted strings.
+///
+/// Produced by [`diff`] and [`diff_spans`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum DiffSegment {
+    /// Text that's in both strings with the same formatting
+    Unchanged(OwnedSpan),
+    /// Text that's in both strings, but with different formatting
+    StyleChanged {
+        /// The text as it was in the old string
+        old: OwnedSpan,
+        /// The same text as it is in the new string
+        new: OwnedSpan,
+    },
+    /// Text that was replaced by different text with the same formatting
+    TextChanged {
+        /// The text in the old string
+        old: OwnedSpan,
+        /// The text that replaced it in the new string
+        new: OwnedSpan,
+    },
+    /// Text that's only in the new string
+    Inserted(OwnedSpan),
+    /// Text that's only in the old string
+    Removed(OwnedSpan),
+}
+
+/// A span in the difference between two formatted strings, compared span by
+/// span.
+///
+/// Produced by [`span_diff`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SpanChange {
+    /// A span that's in both strings, with the same text and formatting
+    Unchanged(OwnedSpan),
+    /// A span that's only in the new string
+    Added(OwnedSpan),
+    /// A span that's only in the old string
+    Removed(OwnedSpan),
+}
+
+/// Compare two strings with legacy formatting codes by what they display.
+///
+/// Both strings are parsed with the default [`SpanIter`] settings; see
+/// [`diff_spans`] for the details of how they're compared.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{diff, DiffSegment, OwnedSpan, Color, Styles};
+///
+/// assert_eq!(
+///     diff("§6Level §a5", "§6Level §c5"),
+///     vec![
+///         DiffSegment::Unchanged(OwnedSpan::new_styled("Level ", Color::Gold, Styles::empty())),
+///         DiffSegment::StyleChanged {
+///             old: OwnedSpan::new_styled("5", Color::Green, Styles::empty()),
+///             new: OwnedSpan::new_styled("5", Color::Red, Styles::empty()),
+///         },
+///     ]
+/// );
+/// ```
+pub fn diff(old: &str, new: &str) -> Vec<DiffSegment> {
+    diff_spans(SpanIter::new(old), SpanIter::new(new))
+}
+
+/// Compare two sequences of spans by what they display.
+///
+/// The visible text of the two is lined up char by char (using the longest
+/// common subsequence of chars), so differences in how the spans are split up
+/// or in which codes were used to format them don't show up; only the text
+/// and the formatting of each char matter. Chars that line up become
+/// [`DiffSegment::Unchanged`] or [`DiffSegment::StyleChanged`]. Between those,
+/// text that was replaced by text with the same formatting is a
+/// [`DiffSegment::TextChanged`], and anything else is reported as
+/// [`DiffSegment::Removed`] followed by [`DiffSegment::Inserted`].
+///
+/// Adjacent chars with the same kind of change and the same formatting are
+/// joined into a single segment, so the segments can be rendered directly
+/// (for example, coloring removed text red and inserted text green).
+/// [`Span::Url`]s are compared like any other text.
+///
+/// This takes time and memory proportional to the product of the lengths of
+/// the parts of the texts that differ (after any common prefix and suffix),
+/// which is fine for things like MOTDs and chat messages but not for very
+/// long texts.
+pub fn diff_spans<'a, 'b>(
+    old: impl IntoIterator<Item = Span<'a>>,
+    new: impl IntoIterator<Item = Span<'b>>,
+) -> Vec<DiffSegment> {
+    let (old, new) = (visible_chars(old), visible_chars(new));
+    let mut runs = Runs::default();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a.0 == b.0).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a.0 == b.0)
+        .count();
+
+    old[..prefix]
+        .iter()
+        .zip(&new[..prefix])
+        .for_each(|(a, b)| runs.matched(a, b));
+    align(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+        &mut runs,
+    );
+    old[old.len() - suffix..]
+        .iter()
+        .zip(&new[new.len() - suffix..])
+        .for_each(|(a, b)| runs.matched(a, b));
+
+    runs.into_segments()
+}
+
+/// Compare two strings with legacy formatting codes span by span, using
+/// `start_char` as the start char for both.
+///
+/// Both strings are parsed into spans, and adjacent spans with the same
+/// formatting are merged (see [`coalesce`]), so redundant codes don't show up
+/// as changes. The spans are then lined up along their longest common
+/// subsequence, where two spans match if they have the same text, color,
+/// and styles. Anything that doesn't line up is reported as
+/// [`SpanChange::Removed`] followed by [`SpanChange::Added`].
+///
+/// This is coarser than [`diff`], which compares char by char: any change to
+/// a span replaces the whole span. That makes it a good fit for reviewing
+/// edits to things like MOTDs, where changing the color of a word should show
+/// up as that word changing.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{span_diff, OwnedSpan, SpanChange, Color, Styles};
+///
+/// assert_eq!(
+///     span_diff("§7Status: §aonline", "§7Status: §conline", '§'),
+///     vec![
+///         SpanChange::Unchanged(OwnedSpan::new_styled("Status: ", Color::Gray, Styles::empty())),
+///         SpanChange::Removed(OwnedSpan::new_styled("online", Color::Green, Styles::empty())),
+///         SpanChange::Added(OwnedSpan::new_styled("online", Color::Red, Styles::empty())),
+///     ]
+/// );
+/// ```
+pub fn span_diff(old: &str, new: &str, start_char: char) -> Vec<SpanChange> {
+    let parse = |s: &str| -> Vec<OwnedSpan> {
+        coalesce(SpanIter::new(s).with_start_char(start_char))
+            .with_buffer(s)
+            .filter(|span| !span.as_span().is_empty())
+            .map(CowSpan::into_owned)
+            .collect()
+    };
+    let (old, new) = (parse(old), parse(new));
+    let width = new.len() + 1;
+    let lcs = lcs_table(&old, &new, |a, b| span_key(a) == span_key(b));
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    let mut added = Vec::new();
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && span_key(&old[i]) == span_key(&new[j]) {
+            changes.append(&mut added);
+            changes.push(SpanChange::Unchanged(old[i].clone()));
+            i += 1;
+            j += 1;
+        } else if j < new.len()
+            && (i == old.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            added.push(SpanChange::Added(new[j].clone()));
+            j += 1;
+        } else {
+            changes.push(SpanChange::Removed(old[i].clone()));
+            i += 1;
+        }
+    }
+
+    changes.append(&mut added);
+    changes
+}
+
+/// What two spans need to have in common to match in [`span_diff`]
+fn span_key(span: &OwnedSpan) -> (&str, Color, Styles) {
+    let span = span.as_span();
+    (span.text(), span.color(), span.styles())
+}
+
+/// Build the table of the lengths of the longest common subsequences of the
+/// suffixes of `old` and `new`
+///
+/// `table[i * (new.len() + 1) + j]` is the length of the longest common
+/// subsequence of `old[i..]` and `new[j..]`.
+fn lcs_table<T>(old: &[T], new: &[T], eq: impl Fn(&T, &T) -> bool) -> Vec<u32> {
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if eq(&old[i], &new[j]) {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    lcs
+}
+
+/// A visible char and its formatting
+type FormattedChar = (char, Color, Styles);
+
+fn visible_chars<'a>(spans: impl IntoIterator<Item = Span<'a>>) -> Vec<FormattedChar> {
+    spans
+        .into_iter()
+        .flat_map(|span| {
+            let (color, styles) = (span.color(), span.styles());
+            span.text().chars().map(move |c| (c, color, styles))
+        })
+        .collect()
+}
+
+/// Line up `old` and `new` along their longest common subsequence of chars,
+/// adding the result to `runs`
+fn align(old: &[FormattedChar], new: &[FormattedChar], runs: &mut Runs) {
+    let width = new.len() + 1;
+    let lcs = lcs_table(old, new, |a, b| a.0 == b.0);
+
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut inserted) = (Vec::new(), Vec::new());
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].0 == new[j].0 {
+            runs.gap(&removed, &inserted);
+            removed.clear();
+            inserted.clear();
+
+            runs.matched(&old[i], &new[j]);
+            i += 1;
+            j += 1;
+        } else if j < new.len()
+            && (i == old.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            inserted.push(new[j]);
+            j += 1;
+        } else {
+            removed.push(old[i]);
+            i += 1;
+        }
+    }
+
+    runs.gap(&removed, &inserted);
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum RunKind {
+    Unchanged,
+    StyleChanged,
+    TextChanged,
+    Inserted,
+    Removed,
+}
+
+/// A segment being built up a char at a time
+#[derive(Debug)]
+struct Run {
+    kind: RunKind,
+    old: (String, Color, Styles),
+    new: (String, Color, Styles),
+}
+
+/// Joins chars with the same kind of change and formatting into runs
+#[derive(Debug, Default)]
+struct Runs(Vec<Run>);
+
+impl Runs {
+    fn push(&mut self, kind: RunKind, old: Option<&FormattedChar>, new: Option<&FormattedChar>) {
+        let format = |c: Option<&FormattedChar>| c.map(|&(_, color, styles)| (color, styles));
+
+        let run = match self.0.last_mut() {
+            Some(run)
+                if run.kind == kind
+                    && old.map_or(true, |_| format(old) == Some((run.old.1, run.old.2)))
+                    && new.map_or(true, |_| format(new) == Some((run.new.1, run.new.2))) =>
+            {
+                run
+            }
+            _ => {
+                let empty = |c: Option<&FormattedChar>| {
+                    let (color, styles) = format(c).unwrap_or((Color::White, Styles::empty()));
+                    (String::new(), color, styles)
+                };
+                self.0.push(Run {
+                    kind,
+                    old: empty(old),
+                    new: empty(new),
+                });
+                self.0.last_mut().unwrap()
+            }
+        };
+
+        if let Some(&(c, _, _)) = old {
+            run.old.0.push(c);
+        }
+        if let Some(&(c, _, _)) = new {
+            run.new.0.push(c);
+        }
+    }
+
+    /// Add a char that's in both texts
+    fn matched(&mut self, old: &FormattedChar, new: &FormattedChar) {
+        let kind = if (old.1, old.2) == (new.1, new.2) {
+            RunKind::Unchanged
+        } else {
+            RunKind::StyleChanged
+        };
+        self.push(kind, Some(old), Some(new));
+    }
+
+    /// Add the chars between two matched chars
+    fn gap(&mut self, removed: &[FormattedChar], inserted: &[FormattedChar]) {
+        let uniform = |chars: &[FormattedChar]| {
+            let (_, color, styles) = *chars.first()?;
+            Some((color, styles)).filter(|&f| chars.iter().all(|c| (c.1, c.2) == f))
+        };
+
+        match (uniform(removed), uniform(inserted)) {
+            (Some(old), Some(new)) if old == new => {
+                // Start a new run rather than joining a `TextChanged` run that
+                // came before it
+                self.0.push(Run {
+                    kind: RunKind::TextChanged,
+                    old: (String::new(), old.0, old.1),
+                    new: (String::new(), new.0, new.1),
+                });
+                removed
+                    .iter()
+                    .for_each(|c| self.push(RunKind::TextChanged, Some(c), None));
+                inserted
+                    .iter()
+                    .for_each(|c| self.push(RunKind::TextChanged, None, Some(c)));
+            }
+            _ => {
+                removed
+                    .iter()
+                    .for_each(|c| self.push(RunKind::Removed, Some(c), None));
+                inserted
+                    .iter()
+                    .for_each(|c| self.push(RunKind::Inserted, None, Some(c)));
+            }
+        }
+    }
+
+    fn into_segments(self) -> Vec<DiffSegment> {
+        let span = |(text, color, styles): (String, Color, Styles)| {
+            Span::from_formatting(&text, color, styles).into_owned()
+        };
+
+        self.0
+            .into_iter()
+            .map(|run| match run.kind {
+                RunKind::Unchanged => DiffSegment::Unchanged(span(run.old)),
+                RunKind::StyleChanged => DiffSegment::StyleChanged {
+                    old: span(run.old),
+                    new: span(run.new),
+                },
+                RunKind::TextChanged => DiffSegment::TextChanged {
+                    old: span(run.old),
+                    new: span(run.new),
+                },
+                RunKind::Inserted => DiffSegment::Inserted(span(run.new)),
+                RunKind::Removed => DiffSegment::Removed(span(run.old)),
+            })
+            .collect()
+    }
+}
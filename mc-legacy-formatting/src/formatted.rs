@@ -0,0 +1,147 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{self, Display},
+    iter::FromIterator,
+    slice,
+};
+
+use crate::{legacy_display::Encoder, OwnedSpan, Span};
+
+/// An owned list of spans: formatted text that can be built up, measured, and
+/// turned back into a string with legacy formatting codes.
+///
+/// This ties the rest of the crate together for the common case of holding on
+/// to some formatted text. It can be collected from [`Span`]s or
+/// [`OwnedSpan`]s, and its [`Display`] impl writes the spans with legacy
+/// codes, using `§` as the start char (see
+/// [`to_legacy_string`](crate::to_legacy_string) for the details, including
+/// how start chars in the text are escaped).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{FormattedText, SpanExt, Span, Color, Styles};
+///
+/// let mut text: FormattedText = "§6Gold §lbold".span_iter().collect();
+/// text.push(Span::new_plain(" and plain"));
+///
+/// assert_eq!(text.plain_text(), "Gold bold and plain");
+/// assert_eq!(text.display_width(0), 19);
+/// assert_eq!(text.to_string(), "§6Gold §lbold§r and plain");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct FormattedText {
+    spans: Vec<OwnedSpan>,
+}
+
+impl FormattedText {
+    /// Create a new, empty [`FormattedText`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a span to the end of the text
+    pub fn push(&mut self, span: impl Into<OwnedSpan>) {
+        self.spans.push(span.into());
+    }
+
+    /// Get the spans that make up the text
+    pub fn spans(&self) -> &[OwnedSpan] {
+        &self.spans
+    }
+
+    /// Iterate over the spans, borrowed as [`Span`]s
+    ///
+    /// This makes the text usable with everything that works on [`Span`]s.
+    pub fn iter(&self) -> impl Iterator<Item = Span<'_>> + Clone {
+        self.spans.iter().map(OwnedSpan::as_span)
+    }
+
+    /// Get the number of spans
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Whether there aren't any spans
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Concatenate the text of the spans, dropping all formatting
+    pub fn plain_text(&self) -> String {
+        self.iter().map(|span| span.text()).collect()
+    }
+
+    /// Get the combined display width of the spans
+    ///
+    /// See [`Span::display_width`] for how the width of each span is counted.
+    pub fn display_width(&self, tab_width: u8) -> usize {
+        self.iter().map(|span| span.display_width(tab_width)).sum()
+    }
+
+    /// Turn the spans back into a string with legacy formatting codes, using
+    /// `start_char` as the start char
+    ///
+    /// This is the same as the [`Display`] output with `start_char` in place
+    /// of `§`.
+    pub fn to_legacy_string(&self, start_char: char) -> String {
+        crate::to_legacy_string(&self.spans, start_char)
+    }
+}
+
+impl Display for FormattedText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut encoder = Encoder::new('§');
+
+        for span in self.iter().filter(|span| !span.is_empty()) {
+            encoder.write_codes(f, span.color(), span.styles())?;
+            encoder.write_text(f, span.text())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<OwnedSpan>> for FormattedText {
+    fn from(spans: Vec<OwnedSpan>) -> Self {
+        Self { spans }
+    }
+}
+
+impl From<FormattedText> for Vec<OwnedSpan> {
+    fn from(text: FormattedText) -> Self {
+        text.spans
+    }
+}
+
+impl<S: Into<OwnedSpan>> FromIterator<S> for FormattedText {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        Self {
+            spans: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<S: Into<OwnedSpan>> Extend<S> for FormattedText {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        self.spans.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl IntoIterator for FormattedText {
+    type Item = OwnedSpan;
+    type IntoIter = vec::IntoIter<OwnedSpan>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FormattedText {
+    type Item = &'a OwnedSpan;
+    type IntoIter = slice::Iter<'a, OwnedSpan>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.iter()
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::string::String;
+
+use crate::{Color, LegacyStringBuilder, Span, SpanIter, Styles};
+
+/// Color `text` with a gradient from `from` to `to`, written with hex codes.
+///
+/// This is a shorthand for a two-stop [`Gradient`]; see it for the details.
+/// The output uses the `§x§r§r§g§g§b§b` format (with `start_char` in place of
+/// `§`), which can be parsed back with
+/// [`SpanIter::with_hex_colors`](crate::SpanIter::with_hex_colors).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::gradient;
+///
+/// assert_eq!(
+///     gradient("ab", (255, 0, 0), (0, 0, 255), '&'),
+///     "&x&f&f&0&0&0&0a&x&0&0&0&0&f&fb"
+/// );
+/// ```
+pub fn gradient(text: &str, from: (u8, u8, u8), to: (u8, u8, u8), start_char: char) -> String {
+    Gradient::new(&[from, to])
+        .with_start_char(start_char)
+        .apply(text)
+}
+
+/// Colors text with a gradient through any number of color stops.
+///
+/// The colors are interpolated (in RGB) per visible char: the first
+/// non-whitespace char gets the first stop, the last one gets the last stop,
+/// and the stops are spread evenly in between. Whitespace doesn't take up a
+/// step of the gradient and keeps the color of the char before it, so no codes
+/// are spent on it. With a single stop all of the text gets that color, and
+/// with no stops the text is left uncolored.
+///
+/// The text is parsed for codes with the start char. Color codes in it
+/// (including hex codes) are replaced by the gradient, and style codes are
+/// kept unless [`Gradient::with_styles`] is turned off. Literal start chars in
+/// the text are escaped by doubling them, as [`LegacyStringBuilder`] does.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::Gradient;
+///
+/// let stops = [(255, 0, 0), (0, 255, 0), (0, 0, 255)];
+///
+/// assert_eq!(
+///     Gradient::new(&stops).apply("r g§lb"),
+///     "§x§f§f§0§0§0§0r §x§0§0§f§f§0§0g§x§0§0§0§0§f§f§lb"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Gradient<'a> {
+    stops: &'a [(u8, u8, u8)],
+    start_char: char,
+    styles: bool,
+}
+
+impl<'a> Gradient<'a> {
+    /// Create a gradient through `stops`, in order
+    pub fn new(stops: &'a [(u8, u8, u8)]) -> Self {
+        Self {
+            stops,
+            start_char: '§',
+            styles: true,
+        }
+    }
+
+    /// Set the start char used to parse the text and to write the codes
+    /// (defaults to `§`)
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.start_char = c;
+        self
+    }
+
+    /// Set whether style codes in the text are kept (the default)
+    ///
+    /// With this turned off, all of the codes in the text are removed and the
+    /// output only has the gradient's colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Gradient;
+    ///
+    /// let gradient = Gradient::new(&[(255, 170, 0)]);
+    ///
+    /// assert_eq!(gradient.apply("§lbold"), "§x§f§f§a§a§0§0§lbold");
+    /// assert_eq!(gradient.with_styles(false).apply("§lbold"), "§x§f§f§a§a§0§0bold");
+    /// ```
+    pub fn with_styles(mut self, styles: bool) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Color `text` with the gradient
+    pub fn apply(&self, text: &str) -> String {
+        let spans = || {
+            SpanIter::new(text)
+                .with_start_char(self.start_char)
+                .with_hex_colors(true)
+        };
+        let steps = spans()
+            .flat_map(|span| span.text().chars())
+            .filter(|c| !c.is_whitespace())
+            .count();
+
+        let mut builder = LegacyStringBuilder::new().with_start_char(self.start_char);
+        let mut step: usize = 0;
+
+        for span in spans() {
+            let styles = if self.styles {
+                span.styles()
+            } else {
+                Styles::empty()
+            };
+
+            for (idx, c) in span.text().char_indices() {
+                let color = if c.is_whitespace() {
+                    self.color_at(step.saturating_sub(1), steps)
+                } else {
+                    step += 1;
+                    self.color_at(step - 1, steps)
+                };
+                let text = &span.text()[idx..idx + c.len_utf8()];
+
+                builder.push(
+                    Span::from_formatting(text, color, styles).with_background(span.background()),
+                );
+            }
+        }
+
+        builder.finish()
+    }
+
+    /// Get the color of the given step out of `steps`
+    fn color_at(&self, step: usize, steps: usize) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Color::White,
+        };
+        let segments = self.stops.len() - 1;
+
+        if segments == 0 || steps < 2 {
+            let (r, g, b) = first;
+            return Color::Rgb(r, g, b);
+        }
+        if step + 1 >= steps {
+            let (r, g, b) = last;
+            return Color::Rgb(r, g, b);
+        }
+
+        // Where the step falls between the stops, as `segment + rem / len`
+        let len = steps - 1;
+        let pos = step * segments;
+        let (segment, rem) = (pos / len, pos % len);
+        let (from, to) = (self.stops[segment], self.stops[segment + 1]);
+        let lerp =
+            |a: u8, b: u8| ((a as usize * (len - rem) + b as usize * rem + len / 2) / len) as u8;
+
+        Color::Rgb(lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+    }
+}
@@ -0,0 +1,100 @@
+use core::ops::Range;
+
+use alloc::string::String;
+
+use crate::{Color, LegacyStringBuilder, Span, SpanIter, Styles};
+
+/// Give the chars in `visible_range` of `s` a different color and styles,
+/// leaving the rest of the string formatted the way it was.
+///
+/// `visible_range` counts the chars the client displays (the same chars
+/// counted by [`visible_len`](crate::visible_len)), such as the range of a
+/// match from [`find_visible`](crate::find_visible). It's clamped to the
+/// visible text, so it can safely run past the end of it. If a bound falls
+/// between the two chars of an invalid code (which are both displayed), the
+/// range is widened to cover both, since splitting them up would turn them
+/// into a valid code.
+///
+/// The highlighted text gets exactly `color` and `styles`, replacing its own
+/// formatting. Spans are split at the ends of the range and only the codes
+/// needed to switch to the highlight are inserted. After the range the
+/// original codes are kept if they still give the text its original
+/// formatting, and otherwise the minimal codes to get back to it are used (so
+/// there's no blanket `§r`). Codes that don't affect any text are dropped,
+/// the same way [`minify`](crate::minify) drops them.
+///
+/// `s` is parsed with `§` as the start char.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{find_visible, highlight, Color, Styles};
+///
+/// let s = "§6Welcome back, §lSteve§6!";
+/// let found = find_visible(s, "back").unwrap();
+///
+/// assert_eq!(
+///     highlight(s, found.chars, Color::Yellow, Styles::UNDERLINED),
+///     "§6Welcome §e§nback§6, §lSteve§6!"
+/// );
+/// ```
+pub fn highlight(s: &str, visible_range: Range<usize>, color: Color, styles: Styles) -> String {
+    let mut builder = LegacyStringBuilder::new().with_escapes(false);
+    let mut pos = 0;
+
+    for (codes, span) in SpanIter::new(s).with_codes() {
+        let text = span.text();
+        let len = text.chars().count();
+        let start = visible_range.start.clamp(pos, pos + len) - pos;
+        let end = visible_range.end.clamp(pos, pos + len) - pos;
+        pos += len;
+
+        if start >= end {
+            builder.push_with_codes(codes, span);
+            continue;
+        }
+
+        let start = split_point(text, start, false);
+        let end = split_point(text, end, true);
+
+        builder.push_with_codes(codes, span.with_text(&text[..start]));
+        builder.push(
+            Span::from_formatting(&text[start..end], color, styles)
+                .with_background(span.background()),
+        );
+        builder.push(span.with_text(&text[end..]));
+    }
+
+    builder.finish()
+}
+
+/// Find the byte offset of the `n`th char of `text`
+///
+/// If that would split a start char from the char it pairs with, the offset
+/// is moved to the start of the pair, or to its end if `round_up` is set.
+fn split_point(text: &str, n: usize, round_up: bool) -> usize {
+    let mut chars = text.char_indices();
+    let mut count = 0;
+
+    while let Some((idx, c)) = chars.next() {
+        if count >= n {
+            return idx;
+        }
+        count += 1;
+
+        if c == '§' {
+            if let Some((code_idx, code)) = chars.next() {
+                count += 1;
+                if count - 1 == n {
+                    return if round_up {
+                        code_idx + code.len_utf8()
+                    } else {
+                        idx
+                    };
+                }
+            }
+        }
+    }
+
+    text.len()
+}
@@ -0,0 +1,96 @@
+use alloc::string::String;
+
+use crate::{Span, Styles};
+
+/// Render `spans` as HTML, with a `<span>` and an inline style for each one.
+///
+/// The color of each span is written as `color` and its background (see
+/// [`SpanIter::with_background_prefix`](crate::SpanIter::with_background_prefix))
+/// as `background-color`. Bold, italic, underlined, and strikethrough text get
+/// the matching `font-weight`, `font-style`, and `text-decoration`;
+/// [`Styles::RANDOM`] text is written as it is, since animating it is up to
+/// the page. The text is escaped, so the output is safe to insert into a
+/// page, but newlines are kept as they are (style the container with
+/// `white-space: pre-wrap` to show them). Spans without any text are left out.
+///
+/// Use [`Color::css_class`](crate::Color::css_class) to build HTML that's
+/// styled with a stylesheet instead.
+///
+/// `spans` can be [`Span`]s, [`CowSpan`](crate::CowSpan)s, or
+/// [`OwnedSpan`](crate::OwnedSpan)s.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{to_html, SpanExt, Span};
+///
+/// let spans: Vec<Span> = "§6§lGold§r <3".span_iter().collect();
+///
+/// assert_eq!(
+///     to_html(&spans),
+///     "<span style=\"color: #ffaa00; font-weight: bold\">Gold</span>\
+///      <span style=\"color: #ffffff\"> &lt;3</span>"
+/// );
+/// ```
+pub fn to_html<'a, S>(spans: &'a [S]) -> String
+where
+    &'a S: Into<Span<'a>>,
+{
+    let mut html = String::new();
+
+    for span in spans.iter().map(Into::into) {
+        if span.text().is_empty() {
+            continue;
+        }
+
+        html.push_str("<span style=\"color: ");
+        html.push_str(&span.color().foreground_hex_str());
+        if let Some(background) = span.background() {
+            html.push_str("; background-color: ");
+            html.push_str(&background.background_hex_str());
+        }
+        push_style_properties(&mut html, span.styles());
+        html.push_str("\">");
+        push_escaped(&mut html, span.text());
+        html.push_str("</span>");
+    }
+
+    html
+}
+
+/// Push the CSS properties for `styles` onto `html`
+fn push_style_properties(html: &mut String, styles: Styles) {
+    if styles.contains(Styles::BOLD) {
+        html.push_str("; font-weight: bold");
+    }
+    if styles.contains(Styles::ITALIC) {
+        html.push_str("; font-style: italic");
+    }
+
+    let decorations = [
+        (Styles::UNDERLINED, "underline"),
+        (Styles::STRIKETHROUGH, "line-through"),
+    ];
+    let mut first = true;
+    for (style, decoration) in decorations {
+        if styles.contains(style) {
+            html.push_str(if first { "; text-decoration: " } else { " " });
+            html.push_str(decoration);
+            first = false;
+        }
+    }
+}
+
+/// Push `text` onto `html` with the chars that are special in HTML escaped
+fn push_escaped(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use core::fmt::{self, Display, Write};
+
+use crate::{Color, Span, Styles, RESET_CODE};
+
+/// A wrapper around a slice of [`Span`]s that displays them as a string with
+/// legacy formatting codes.
+///
+/// The output is exactly the same as what
+/// [`to_legacy_string`](crate::to_legacy_string) returns, but it's written
+/// straight to the formatter, so spans can be used with `write!`, `format!`,
+/// and friends without building an intermediate string (and without an
+/// allocator).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{LegacyDisplay, Span, Color, Styles};
+///
+/// let spans = [
+///     Span::new_styled("Tom ", Color::Gold, Styles::BOLD),
+///     Span::new_styled("& Jerry", Color::Gold, Styles::empty()),
+/// ];
+///
+/// assert_eq!(LegacyDisplay::new(&spans).to_string(), "§6§lTom §6& Jerry");
+/// assert_eq!(
+///     format!("[{}]", LegacyDisplay::new(&spans).with_start_char('&')),
+///     "[&6&lTom &6&& Jerry]"
+/// );
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct LegacyDisplay<'a> {
+    spans: &'a [Span<'a>],
+    start_char: char,
+    code_case: CodeCase,
+}
+
+impl<'a> LegacyDisplay<'a> {
+    /// Wrap `spans` for displaying with `§` as the start char
+    pub fn new(spans: &'a [Span<'a>]) -> Self {
+        Self {
+            spans,
+            start_char: '§',
+            code_case: CodeCase::Lower,
+        }
+    }
+
+    /// Set the start char used for codes (and escaped in span text)
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.start_char = c;
+        self
+    }
+
+    /// Set whether codes are written with lowercase (the default) or
+    /// uppercase chars
+    pub fn with_code_case(mut self, case: CodeCase) -> Self {
+        self.code_case = case;
+        self
+    }
+}
+
+impl<'a> From<&'a [Span<'a>]> for LegacyDisplay<'a> {
+    fn from(spans: &'a [Span<'a>]) -> Self {
+        Self::new(spans)
+    }
+}
+
+impl Display for LegacyDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut encoder = Encoder::new(self.start_char);
+        encoder.case = self.code_case;
+
+        for span in self.spans.iter().filter(|span| !span.is_empty()) {
+            encoder.write_codes(f, span.color(), span.styles())?;
+            encoder.write_text(f, span.text())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Which case the letters in formatting codes are written in.
+///
+/// The vanilla client accepts codes in either case, so this only matters for
+/// consistency (or for matching other tooling). Only the code chars are
+/// affected, never the text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CodeCase {
+    /// `§6§lGold`
+    ///
+    /// This is the default.
+    Lower,
+    /// `§6§LGold`
+    Upper,
+}
+
+impl Default for CodeCase {
+    fn default() -> Self {
+        CodeCase::Lower
+    }
+}
+
+/// Writes minimal legacy codes for a sequence of formatting changes
+///
+/// This is shared by [`LegacyDisplay`] and
+/// [`LegacyStringBuilder`](crate::LegacyStringBuilder) so that they always
+/// produce the same output.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Encoder {
+    pub(crate) start_char: char,
+    /// Whether start chars in span text should be doubled
+    pub(crate) escape: bool,
+    /// The case that code chars are written in
+    pub(crate) case: CodeCase,
+    /// The formatting in effect at the end of what's been written
+    pub(crate) color: Color,
+    pub(crate) styles: Styles,
+}
+
+impl Encoder {
+    pub(crate) fn new(start_char: char) -> Self {
+        Self {
+            start_char,
+            escape: true,
+            case: CodeCase::Lower,
+            color: Color::White,
+            styles: Styles::empty(),
+        }
+    }
+
+    /// Write whatever codes are needed to switch from the current formatting
+    /// to `color` and `styles`
+    pub(crate) fn write_codes(
+        &mut self,
+        out: &mut impl Write,
+        color: Color,
+        styles: Styles,
+    ) -> fmt::Result {
+        if color != self.color || !styles.contains(self.styles) {
+            if color == Color::White && styles.is_empty() {
+                self.write_code(out, RESET_CODE)?;
+            } else {
+                self.write_color(out, color)?;
+            }
+
+            self.color = color;
+            self.styles = Styles::empty();
+        }
+
+        for (style, c) in STYLES_WITH_CODES {
+            if styles.contains(style) && !self.styles.contains(style) {
+                self.write_code(out, c)?;
+            }
+        }
+        self.styles = styles;
+
+        Ok(())
+    }
+
+    /// Write the color code for `color` followed by the code for each of
+    /// `styles`, whatever the current formatting is
+    #[cfg(feature = "alloc")]
+    pub(crate) fn write_all_codes(
+        &mut self,
+        out: &mut impl Write,
+        color: Color,
+        styles: Styles,
+    ) -> fmt::Result {
+        self.write_color(out, color)?;
+        self.color = color;
+        self.styles = Styles::empty();
+        self.write_codes(out, color, styles)
+    }
+
+    /// Write span text, escaping start chars in it if that's turned on
+    pub(crate) fn write_text(&self, out: &mut impl Write, text: &str) -> fmt::Result {
+        if !self.escape {
+            return out.write_str(text);
+        }
+
+        let mut rest = text;
+        while let Some(idx) = rest.find(self.start_char) {
+            let end = idx + self.start_char.len_utf8();
+            out.write_str(&rest[..end])?;
+            out.write_char(self.start_char)?;
+            rest = &rest[end..];
+        }
+
+        out.write_str(rest)
+    }
+
+    fn write_code(&self, out: &mut impl Write, c: char) -> fmt::Result {
+        out.write_char(self.start_char)?;
+        out.write_char(match self.case {
+            CodeCase::Lower => c,
+            CodeCase::Upper => c.to_ascii_uppercase(),
+        })
+    }
+
+    fn write_color(&self, out: &mut impl Write, color: Color) -> fmt::Result {
+        match color {
+            Color::Rgb(..) => {
+                self.write_code(out, 'x')?;
+                // Skip the leading `#`
+                color
+                    .foreground_hex_str()
+                    .chars()
+                    .skip(1)
+                    .try_for_each(|c| self.write_code(out, c))
+            }
+            _ => self.write_code(out, color_code(color)),
+        }
+    }
+}
+
+/// The code char for each style, in the order they're emitted
+const STYLES_WITH_CODES: [(Styles, char); 5] = [
+    (Styles::RANDOM, 'k'),
+    (Styles::BOLD, 'l'),
+    (Styles::STRIKETHROUGH, 'm'),
+    (Styles::UNDERLINED, 'n'),
+    (Styles::ITALIC, 'o'),
+];
+
+/// The code char for a named color
+pub(crate) fn color_code(color: Color) -> char {
+    match color {
+        Color::Black => '0',
+        Color::DarkBlue => '1',
+        Color::DarkGreen => '2',
+        Color::DarkAqua => '3',
+        Color::DarkRed => '4',
+        Color::DarkPurple => '5',
+        Color::Gold => '6',
+        Color::Gray => '7',
+        Color::DarkGray => '8',
+        Color::Blue => '9',
+        Color::Green => 'a',
+        Color::Aqua => 'b',
+        Color::Red => 'c',
+        Color::LightPurple => 'd',
+        Color::Yellow => 'e',
+        Color::White => 'f',
+        Color::Rgb(..) => unreachable!("rgb colors don't have a single code char"),
+    }
+}
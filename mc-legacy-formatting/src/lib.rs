@@ -10,6 +10,9 @@
 //! * Helpers for pretty-printing the parsed [`Span`]s to the terminal
 //! * Support for parsing any start character for the formatting codes (vanilla
 //!   uses `§` while many community tools use `&`)
+//! * Flattening of JSON chat components into spans (with the `json` feature)
+//! * Conversions from [`Color`] into the color types of the `rgb` and `palette`
+//!   crates (with the features of the same names)
 //!
 //! # Examples
 //!
@@ -47,15 +50,144 @@
 #![deny(missing_docs)]
 #![deny(unused_must_use)]
 
-use core::str::CharIndices;
+use core::ops::Range;
 
 use bitflags::bitflags;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, vec::Vec};
+
+#[cfg(feature = "alloc")]
+mod ansi;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod base;
+#[cfg(feature = "heapless")]
+mod bounded;
+#[cfg(feature = "json")]
+mod chat;
+#[cfg(feature = "alloc")]
+mod coalesce;
+mod codes;
 #[cfg(feature = "color-print")]
 mod color_print;
+#[cfg(feature = "alloc")]
+mod columns;
+#[cfg(any(feature = "rgb", feature = "palette"))]
+mod convert;
+#[cfg(feature = "alloc")]
+mod cow;
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+mod formatted;
+#[cfg(feature = "alloc")]
+mod gradient;
+#[cfg(feature = "alloc")]
+mod highlight;
+#[cfg(feature = "alloc")]
+mod html;
+mod legacy_display;
+mod lines;
+mod lint;
+mod map_style;
+mod multi;
+#[cfg(feature = "alloc")]
+mod normalize;
+#[cfg(feature = "alloc")]
+mod owned;
+mod player_count;
+#[cfg(feature = "alloc")]
+mod replace;
+#[cfg(feature = "alloc")]
+mod serialize;
+#[cfg(feature = "alloc")]
+mod span_buf;
+mod spans;
+mod stream;
+mod strip;
+mod styled;
+mod url;
+mod version;
+mod visible;
+mod width;
 
+#[cfg(feature = "alloc")]
+pub use ansi::from_ansi;
+pub use base::{with_base, WithBase};
+#[cfg(feature = "heapless")]
+pub use bounded::{collect_spans, collect_spans_from, CapacityError};
+#[cfg(feature = "json")]
+pub use chat::{flatten_chat_component, to_chat_component};
+#[cfg(feature = "alloc")]
+pub use coalesce::{coalesce, Coalesce};
+pub use codes::{
+    classify_code, code_ranges, contains_formatting, first_code_offset, raw_tokens, validate,
+    CodeKind, CodeRanges, CustomAction, ParseError, RawTokens, Token,
+};
 #[cfg(feature = "color-print")]
-pub use color_print::PrintSpanColored;
+pub use color_print::{
+    color_from_colored, ColoredSpans, PrintSpanColored, StrikethroughWhitespaceStyle,
+};
+#[cfg(feature = "alloc")]
+pub use columns::{split_columns, SplitColumns};
+#[cfg(feature = "alloc")]
+pub use cow::{map_spans, CowSpan, MapSpans};
+#[cfg(feature = "alloc")]
+pub use diff::{diff, diff_spans, span_diff, DiffSegment, SpanChange};
+#[cfg(feature = "alloc")]
+pub use formatted::FormattedText;
+#[cfg(feature = "alloc")]
+pub use gradient::{gradient, Gradient};
+#[cfg(feature = "alloc")]
+pub use highlight::highlight;
+#[cfg(feature = "alloc")]
+pub use html::to_html;
+pub use legacy_display::{CodeCase, LegacyDisplay};
+#[cfg(feature = "alloc")]
+pub use lines::collapse_blank_lines;
+pub use lines::LineSpanIter;
+pub use lint::{lint, lint_with_start_char, Diagnostic, DiagnosticKind, Lint, Severity};
+pub use map_style::{grayscale, map_style, strip_obfuscation, MapStyle};
+pub use multi::MultiSpanIter;
+#[cfg(feature = "alloc")]
+pub use normalize::normalize_section_escapes;
+#[cfg(feature = "alloc")]
+pub use owned::{span_iter_bytes, OwnedSpan, OwnedSpanIter};
+pub use player_count::{MotdSpan, PlayerCounts};
+#[cfg(feature = "alloc")]
+pub use replace::replace_visible;
+#[cfg(feature = "alloc")]
+pub use serialize::{
+    code_str, escape, minify, minify_with_code_case, to_legacy_string, translate_start_char,
+    translate_start_char_escaped, LegacyStringBuilder,
+};
+#[cfg(feature = "alloc")]
+pub use span_buf::{ParsedSpans, SpanBuf};
+pub use spans::{DisplaySpans, Spans};
+pub use stream::StreamParser;
+#[cfg(feature = "alloc")]
+pub use strip::strip_formatting_string;
+pub use strip::{strip_formatting, Stripped};
+pub use styled::{StyledIter, StyledSpan};
+pub use version::TargetVersion;
+pub use visible::{
+    byte_to_visible, byte_to_visible_with_start_char, find_all_visible, find_visible,
+    truncate_visible, truncate_visible_with_start_char, visible_byte_len,
+    visible_byte_len_with_start_char, visible_char_indices, visible_len,
+    visible_len_with_start_char, visible_to_byte, visible_to_byte_with_start_char, FindVisible,
+    VisibleCharIndices, VisibleMatch,
+};
+#[cfg(feature = "alloc")]
+pub use visible::{
+    map_visible_text, to_lowercase_visible, to_uppercase_visible, truncate_visible_owned,
+};
+#[cfg(feature = "alloc")]
+pub use width::scramble;
+pub use width::{fits_motd, fits_motd_with_width, glyph_width, WidthMode, MOTD_LINE_WIDTH};
 
 /// An extension trait that adds a method for creating a [`SpanIter`]
 pub trait SpanExt {
@@ -73,11 +205,29 @@ pub trait SpanExt {
     /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("and italic", Color::DarkRed, Styles::ITALIC));
     /// assert!(span_iter.next().is_none());
     /// ```
-    fn span_iter(&self) -> SpanIter;
+    fn span_iter(&self) -> SpanIter<'_>;
+
+    /// Produces a [`StyledIter`] from `&self`
+    ///
+    /// See [`StyledIter`] for more details.
+    fn styled_iter(&self) -> StyledIter<'_> {
+        self.span_iter().styled()
+    }
+
+    /// Produces an [`OwnedSpanIter`] that takes ownership of `self`
+    ///
+    /// See [`OwnedSpanIter`] for more details.
+    #[cfg(feature = "alloc")]
+    fn into_span_iter(self) -> OwnedSpanIter
+    where
+        Self: Into<alloc::string::String> + Sized,
+    {
+        OwnedSpanIter::new(self)
+    }
 }
 
 impl<T: AsRef<str>> SpanExt for T {
-    fn span_iter(&self) -> SpanIter {
+    fn span_iter(&self) -> SpanIter<'_> {
         SpanIter::new(self.as_ref())
     }
 }
@@ -99,14 +249,68 @@ impl<T: AsRef<str>> SpanExt for T {
 #[derive(Debug, Clone)]
 pub struct SpanIter<'a> {
     buf: &'a str,
-    chars: CharIndices<'a>,
+    /// The byte offset in `buf` of the next char to parse
+    pos: usize,
     /// The character that indicates the beginning of a fmt code
     ///
     /// The vanilla client uses `§` for this, but community tooling often uses
     /// `&`, so we allow it to be configured
     start_char: char,
+    /// The character that resets formatting when it follows the start char, if
+    /// any
+    ///
+    /// The vanilla client uses `r` for this.
+    reset_char: Option<char>,
+    /// Whether two start chars in a row should be treated as one literal start
+    /// char
+    doubled_escape: bool,
+    /// Whether a backslash directly before a start char makes it a literal
+    /// start char
+    backslash_escape: bool,
+    /// Whether `§x§r§r§g§g§b§b` and `§#rrggbb` hex color codes are recognized
+    hex_colors: bool,
+    /// Whether hex colors are mapped to the nearest named color
+    quantize_hex: bool,
+    /// Whether a color code clears the current styles
+    color_resets_styles: bool,
+    /// The index of the second start char of an escaped pair that ended the
+    /// last span; the next span begins with it
+    escaped_start_char: Option<usize>,
+    /// The number of columns a `\t` takes up when measuring display width
+    ///
+    /// `0` means a tab is treated like any other single glyph.
+    tab_width: u8,
+    /// How the display width of the parsed text is measured
+    width_mode: WidthMode,
+    /// Whether a [`Span::Plain`] with empty text should be yielded once the
+    /// input is exhausted
+    trailing_reset: bool,
+    /// Whether that trailing [`Span::Plain`] has been yielded already
+    trailing_reset_done: bool,
+    /// Whether [`Span`]s without any text should be skipped
+    skip_empty: bool,
+    /// Whether [`Span`]s should be ended after each `\n`
+    split_on_newline: bool,
+    /// Whether URLs should be split out into [`Span::Url`]s
+    url_detection: bool,
+    /// The char that turns a color code into a background code when it's
+    /// between the start char and the color code char, if any
+    background_prefix: Option<char>,
+    /// Recognizes two-char codes that aren't built in, if set
+    custom_code: Option<fn(char, char) -> Option<CustomAction>>,
+    /// The rest of a span that was split (after a `\n` or around a URL),
+    /// which still needs to be yielded
+    rest: Option<Span<'a>>,
+    /// Whether `color` is the default rather than one set by a color code
+    color_is_default: bool,
+    /// Whether the color of the last span that was made was the default
+    span_color_is_default: bool,
+    /// The formatting given to [`SpanIter::with_initial_state`], which
+    /// [`SpanIter::reset`] goes back to
+    initial_state: Option<(Color, Styles)>,
     color: Color,
     styles: Styles,
+    background: Option<Color>,
 }
 
 impl<'a> SpanIter<'a> {
@@ -114,10 +318,31 @@ impl<'a> SpanIter<'a> {
     pub fn new(s: &'a str) -> Self {
         Self {
             buf: s,
-            chars: s.char_indices(),
+            pos: 0,
             start_char: '§',
+            reset_char: Some(RESET_CODE),
+            doubled_escape: false,
+            backslash_escape: false,
+            hex_colors: false,
+            quantize_hex: false,
+            color_resets_styles: true,
+            escaped_start_char: None,
+            tab_width: 0,
+            width_mode: WidthMode::Columns,
+            trailing_reset: false,
+            trailing_reset_done: false,
+            skip_empty: false,
+            split_on_newline: false,
+            url_detection: false,
+            background_prefix: None,
+            custom_code: None,
+            rest: None,
+            color_is_default: true,
+            span_color_is_default: true,
+            initial_state: None,
             color: Color::White,
             styles: Styles::default(),
+            background: None,
         }
     }
 
@@ -147,250 +372,1800 @@ impl<'a> SpanIter<'a> {
         self.start_char = c;
     }
 
-    /// Update the currently stored color
-    fn update_color(&mut self, color: Color) {
-        self.color = color;
-        // According to https://wiki.vg/Chat, using a color code resets the current
-        // style
-        self.styles = Styles::empty();
+    /// Set the character that resets the color and styles when it follows the
+    /// start char, or disable resetting entirely with `None`
+    ///
+    /// The reset char is matched case-insensitively and takes priority over
+    /// any color or style code that uses the same char. When it isn't `r`,
+    /// the start char followed by `r` is treated as text. The default is
+    /// `Some('r')`.
+    ///
+    /// This only affects parsing with this iterator; [`apply_code`] and
+    /// [`code_ranges`] always use `r`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§aGreen §rstill green".span_iter().with_reset_char(None);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Green §rstill green", Color::Green, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_reset_char(mut self, reset_char: Option<char>) -> Self {
+        self.reset_char = reset_char;
+        self
     }
 
-    /// Insert `styles` into the currently stored styles
-    fn update_styles(&mut self, styles: Styles) {
-        self.styles.insert(styles);
+    /// Treat two start chars in a row as a single literal start char rather than
+    /// the beginning of a code
+    ///
+    /// This is how many community tools let text contain the start char (for
+    /// example, `&&` for a literal `&`), and is what [`to_legacy_string`] uses
+    /// to escape start chars.
+    ///
+    /// Since the span text is always a slice of the input string, the first
+    /// start char of the pair can't be left out of the middle of a span; the
+    /// span is ended before it instead, and the next span (with the same
+    /// formatting) begins with the second. Adjacent spans can therefore have
+    /// the same formatting in this mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "&6Tom && Jerry".span_iter().with_start_char('&').with_doubled_escape(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Tom ", Color::Gold, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("& Jerry", Color::Gold, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_doubled_escape(mut self, doubled_escape: bool) -> Self {
+        self.doubled_escape = doubled_escape;
+        self
     }
 
-    /// Should be called when encountering the `RESET` fmt code
-    fn reset_styles(&mut self) {
-        self.color = Color::White;
-        self.styles = Styles::empty();
+    /// Treat a start char with a backslash directly before it as a literal
+    /// start char, dropping the backslash
+    ///
+    /// Some config formats use this to let text contain the start char
+    /// (`\§a` for a literal `§a`). Two backslashes directly before a start
+    /// char are a literal backslash, leaving the start char to begin a code as
+    /// usual, and in general each pair of backslashes in a run directly before
+    /// a start char becomes one backslash. Backslashes anywhere else are
+    /// left alone, as are the ones before a start char that doesn't begin a
+    /// valid code.
+    ///
+    /// As with [`SpanIter::with_doubled_escape`], the span text is a slice of
+    /// the input, so a span is ended where a backslash is dropped and adjacent
+    /// spans can have the same formatting in this mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = r"§6Use \§a for green".span_iter().with_backslash_escape(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Use ", Color::Gold, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("§a for green", Color::Gold, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_backslash_escape(mut self, backslash_escape: bool) -> Self {
+        self.backslash_escape = backslash_escape;
+        self
     }
 
-    /// Make a [`Span`] based off the current state of the iterator
+    /// Recognize hex color codes, which set a [`Color::Rgb`] color
     ///
-    /// The span will be from `start..end`
-    fn make_span(&self, start: usize, end: usize) -> Span<'a> {
-        if self.color == Color::White && self.styles.is_empty() {
-            Span::Plain(&self.buf[start..end])
-        } else {
-            let text = &self.buf[start..end];
+    /// Two forms are supported: the one that Bukkit servers send to clients
+    /// (and [`to_legacy_string`] writes), where each hex digit is its own
+    /// code after an `x` (`§x§f§f§8§8§0§0`), and the shorter `§#ff8800` that
+    /// many plugins accept in their configs. Like any other color code, a
+    /// hex color resets the styles. A hex color that's exactly one of the
+    /// named colors is given as that [`Color`] (see [`Color::from_hex_str`]).
+    ///
+    /// An incomplete hex code is treated as text, as are hex codes when this
+    /// is turned off (the default). This only affects parsing with this
+    /// iterator; [`code_ranges`] and friends don't know about hex codes.
+    /// [`SpanIter::with_target_version`] also turns this on or off, so
+    /// whichever of the two is called last wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§x§f§f§8§8§0§0orange §#55ff55green"
+    ///     .span_iter()
+    ///     .with_hex_colors(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("orange ", Color::Rgb(255, 136, 0), Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("green", Color::Green, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_hex_colors(mut self, hex_colors: bool) -> Self {
+        self.hex_colors = hex_colors;
+        self
+    }
 
-            // The vanilla client renders whitespace with `Styles::STRIKETHROUGH`
-            // as a solid line. This replicates that behavior
-            //
-            // (Technically it does this by drawing a line over any text slice
-            // with the `STRIKETHROUGH` style.)
-            if text.chars().all(|c| c.is_ascii_whitespace())
-                && self.styles.contains(Styles::STRIKETHROUGH)
-            {
-                Span::StrikethroughWhitespace {
-                    text,
-                    color: self.color,
-                    styles: self.styles,
-                }
-            } else {
-                Span::Styled {
-                    text,
-                    color: self.color,
-                    styles: self.styles,
-                }
-            }
-        }
+    /// Map hex colors to the nearest named color as they're parsed
+    ///
+    /// This is for output that only supports the 16 named colors, such as
+    /// older clients. It only has an effect when hex colors are recognized
+    /// (see [`SpanIter::with_hex_colors`]), and uses
+    /// [`Color::nearest_named`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "&#ff5556almost red"
+    ///     .span_iter()
+    ///     .with_start_char('&')
+    ///     .with_hex_colors(true)
+    ///     .with_quantize_hex(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("almost red", Color::Red, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_quantize_hex(mut self, quantize_hex: bool) -> Self {
+        self.quantize_hex = quantize_hex;
+        self
     }
-}
 
-/// Keeps track of the state for each iteration
-#[derive(Debug, Copy, Clone)]
-enum SpanIterState {
-    GatheringStyles(GatheringStylesState),
-    GatheringText(GatheringTextState),
-}
+    /// Set the number of columns a tab (`\t`) takes up when measuring the
+    /// display width of the parsed text
+    ///
+    /// This doesn't change the text of any [`Span`]s. The default of `0` means
+    /// a tab is treated as a single glyph, like any other char.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::SpanExt;
+    ///
+    /// let s = "§6Name\t§7Value";
+    ///
+    /// assert_eq!(s.span_iter().display_width(), 10);
+    /// assert_eq!(s.span_iter().with_tab_width(4).display_width(), 13);
+    /// ```
+    pub fn with_tab_width(mut self, tab_width: u8) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
 
-/// In this state we are at the beginning of an iteration and we are looking to
-/// handle any initial formatting codes
-#[derive(Debug, Copy, Clone)]
-enum GatheringStylesState {
-    /// We're looking for our start char
-    ExpectingStartChar,
-    /// We've found our start char and are expecting a fmt code after it
-    ExpectingFmtCode,
-}
+    /// Get the tab width used when measuring display width
+    ///
+    /// See [`SpanIter::with_tab_width`].
+    pub fn tab_width(&self) -> u8 {
+        self.tab_width
+    }
 
-/// In this state we've encountered text unrelated to formatting, which means
-/// the next valid fmt code we encounter ends this iteration
-#[derive(Debug, Copy, Clone)]
-enum GatheringTextState {
-    /// We're waiting to find our start char
-    WaitingForStartChar,
-    /// We've found our start char and are expecting a fmt code after it
+    /// Set how [`SpanIter::display_width`] measures the parsed text
     ///
-    /// If we find a valid fmt code in this state, we need to make a span, apply
-    /// this last fmt code to our state, and end this iteration.
-    ExpectingEndChar,
-}
+    /// The default is [`WidthMode::Columns`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, WidthMode};
+    ///
+    /// let s = "§lHi §rthere";
+    ///
+    /// assert_eq!(s.span_iter().display_width(), 8);
+    /// assert_eq!(s.span_iter().with_width_mode(WidthMode::Pixels).display_width(), 44);
+    /// ```
+    pub fn with_width_mode(mut self, width_mode: WidthMode) -> Self {
+        self.width_mode = width_mode;
+        self
+    }
 
-impl<'a> Iterator for SpanIter<'a> {
-    type Item = Span<'a>;
+    /// Get how display width is measured
+    ///
+    /// See [`SpanIter::with_width_mode`].
+    pub fn width_mode(&self) -> WidthMode {
+        self.width_mode
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        use GatheringStylesState::*;
-        use GatheringTextState::*;
-        use SpanIterState::*;
+    /// Yield one extra, empty [`Span::Plain`] after all of the real content
+    ///
+    /// This serves as an explicit "back to the default formatting" marker for
+    /// renderers that draw each span independently. The extra span has no
+    /// text, so it doesn't take up any display width. It's yielded even if the
+    /// input string is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§4§lBig red text".span_iter().with_trailing_reset();
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Big red text", Color::DarkRed, Styles::BOLD));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_plain(""));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_trailing_reset(mut self) -> Self {
+        self.trailing_reset = true;
+        self
+    }
 
-        let mut state = GatheringStyles(ExpectingStartChar);
-        let mut span_start = None;
-        let mut span_end = None;
+    /// Never yield [`Span`]s without any text
+    ///
+    /// This includes the extra span added by
+    /// [`SpanIter::with_trailing_reset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span};
+    ///
+    /// let mut span_iter = "text".span_iter().with_trailing_reset().skip_empty();
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_plain("text"));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
+        self
+    }
 
-        while let Some((idx, c)) = self.chars.next() {
-            state = match state {
-                GatheringStyles(style_state) => match style_state {
-                    ExpectingStartChar => {
-                        span_start = Some(idx);
-                        match c {
-                            c if c == self.start_char => GatheringStyles(ExpectingFmtCode),
-                            _ => GatheringText(WaitingForStartChar),
-                        }
-                    }
-                    ExpectingFmtCode => {
-                        if let Some(color) = Color::from_char(c) {
-                            self.update_color(color);
-                            span_start = None;
-                            GatheringStyles(ExpectingStartChar)
-                        } else if let Some(style) = Styles::from_char(c) {
-                            self.update_styles(style);
-                            span_start = None;
-                            GatheringStyles(ExpectingStartChar)
-                        } else if c == 'r' || c == 'R' {
-                            // Handle the `RESET` fmt code
+    /// Set whether [`Span`]s are ended after each line break (off by default)
+    ///
+    /// When enabled, the text following a line break (`\n`, `\r\n`, or a
+    /// lone `\r`) starts a fresh span with the same formatting, so no span
+    /// contains a line break anywhere other than at the very end of its text.
+    /// This is a simpler alternative to [`SpanIter::lines`] for when
+    /// newline-free spans are all that's needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§6line one\nline two".span_iter().split_on_newline(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("line one\n", Color::Gold, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("line two", Color::Gold, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn split_on_newline(mut self, split_on_newline: bool) -> Self {
+        self.split_on_newline = split_on_newline;
+        self
+    }
 
-                            self.reset_styles();
-                            span_start = None;
-                            GatheringStyles(ExpectingStartChar)
-                        } else {
-                            GatheringText(WaitingForStartChar)
-                        }
-                    }
-                },
-                GatheringText(text_state) => match text_state {
-                    WaitingForStartChar => match c {
-                        c if c == self.start_char => {
-                            span_end = Some(idx);
-                            GatheringText(ExpectingEndChar)
-                        }
-                        _ => state,
-                    },
-                    ExpectingEndChar => {
-                        // Note that we only end this iteration if we find a valid fmt code
-                        //
-                        // If we do, we make sure to apply it to our state so that we can
-                        // pick up where we left off when the next iteration begins
+    /// Set whether URLs in the text are split out into [`Span::Url`]s (off by
+    /// default)
+    ///
+    /// Links starting with `http://` or `https://` are recognized, as are bare
+    /// domain names (like `www.example.com` or `discord.gg/invite`) whose
+    /// top-level domain is lowercase or a common one like `COM`. A URL is
+    /// only recognized if it's entirely within the text of one span, and
+    /// punctuation at the end of it (such as the period ending a sentence) is
+    /// left out. The [`Span::Url`] has the formatting of the text it was found
+    /// in, and the text around it is yielded as separate spans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§7Join at §bplay.example.com!".span_iter().with_url_detection(true);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Join at ", Color::Gray, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_url("play.example.com", Color::Aqua, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("!", Color::Aqua, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_url_detection(mut self, url_detection: bool) -> Self {
+        self.url_detection = url_detection;
+        self
+    }
+
+    /// Recognize background color codes that are a color code with `prefix`
+    /// between it and the start char, or turn them off with `None` (the
+    /// default)
+    ///
+    /// Legacy chat has no background codes, but some renderers (and Bedrock
+    /// Edition signs) associate a background color with text. With a prefix
+    /// of `^`, `§^4` sets the background to [`Color::DarkRed`] and `§^r`
+    /// removes it (using the reset char from
+    /// [`SpanIter::with_reset_char`]). Color and style codes leave the
+    /// background alone, while the reset code removes it along with
+    /// everything else. The prefix takes priority over any code that uses
+    /// the same char, and a prefix that isn't followed by a color code is
+    /// treated as text.
+    ///
+    /// Text with a background is always yielded as a [`Span::Styled`], since
+    /// that's the only kind of span that can carry one; it isn't split into
+    /// [`Span::Url`]s either. Serializers like [`to_legacy_string`] don't
+    /// write background codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§^1§eWarning§^r!".span_iter().with_background_prefix(Some('^'));
+    ///
+    /// assert_eq!(
+    ///     span_iter.next().unwrap(),
+    ///     Span::new_styled("Warning", Color::Yellow, Styles::empty()).with_background(Some(Color::DarkBlue))
+    /// );
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("!", Color::Yellow, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_background_prefix(mut self, prefix: Option<char>) -> Self {
+        self.background_prefix = prefix;
+        self
+    }
+
+    /// Match the quirks of the given version of the client
+    ///
+    /// This decides whether color codes clear the current styles (see
+    /// [`TargetVersion::color_resets_styles`]) and whether hex colors are
+    /// recognized (see [`TargetVersion::supports_hex`]). Without this, color
+    /// codes clear the styles and hex colors aren't recognized. Other
+    /// differences between versions (such as Bedrock Edition's material color
+    /// codes) aren't matched.
+    ///
+    /// This sets the same thing as [`SpanIter::with_hex_colors`], so
+    /// whichever of the two is called last wins: call
+    /// [`SpanIter::with_hex_colors`] afterwards to override the version's
+    /// hex color support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles, TargetVersion};
+    ///
+    /// let s = "§lBold §6gold";
+    /// let mut span_iter = s.span_iter().with_target_version(TargetVersion::Bedrock);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Bold ", Color::White, Styles::BOLD));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("gold", Color::Gold, Styles::BOLD));
+    /// assert!(span_iter.next().is_none());
+    ///
+    /// let mut span_iter = s.span_iter().with_target_version(TargetVersion::Java(8));
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Bold ", Color::White, Styles::BOLD));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("gold", Color::Gold, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_target_version(mut self, version: TargetVersion) -> Self {
+        self.color_resets_styles = version.color_resets_styles();
+        self.hex_colors = version.supports_hex();
+        self
+    }
+
+    /// Recognize custom two-char codes with `hook`
+    ///
+    /// Some servers extend the formatting system with codes that take up two
+    /// chars after the start char. When a start char is followed by a char
+    /// that isn't a code the parser already knows about, `hook` is given that
+    /// char and the one after it; if it returns a [`CustomAction`], both chars
+    /// make up a code that does what the action says. Otherwise the start
+    /// char is treated as text, like it would be without a hook. Built-in
+    /// codes always take priority, so the first char of a custom code can't
+    /// be a color or style code char.
+    ///
+    /// Only the parser knows about custom codes; functions like
+    /// [`validate`] and [`strip_formatting`] don't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, CustomAction, Span, Color, Styles};
+    ///
+    /// fn extra_styles(c: char, modifier: char) -> Option<CustomAction> {
+    ///     match (c, modifier) {
+    ///         ('x', 'a') => Some(CustomAction::Set(Color::Gold, Styles::BOLD)),
+    ///         _ => None,
+    ///     }
+    /// }
+    ///
+    /// let mut span_iter = "&xaGold &xzbold".span_iter().with_start_char('&').with_custom_code(Some(extra_styles));
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Gold &xzbold", Color::Gold, Styles::BOLD));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_custom_code(
+        mut self,
+        hook: Option<fn(char, char) -> Option<CustomAction>>,
+    ) -> Self {
+        self.custom_code = hook;
+        self
+    }
+
+    /// Start parsing with the given color and styles rather than
+    /// [`Color::White`] and [`Styles::empty()`]
+    ///
+    /// This is useful when the string being parsed continues on from some other
+    /// text whose formatting should carry over into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanIter, Span, Color, Styles};
+    ///
+    /// let mut span_iter = SpanIter::new("still gold §lnow bold").with_initial_state(Color::Gold, Styles::empty());
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("still gold ", Color::Gold, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("now bold", Color::Gold, Styles::BOLD));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_initial_state(mut self, color: Color, styles: Styles) -> Self {
+        self.initial_state = Some((color, styles));
+        self.color = color;
+        self.color_is_default = false;
+        self.styles = styles;
+        self
+    }
+
+    /// Get the color and styles in effect where parsing is currently at
+    ///
+    /// Once the iterator has been exhausted, this is the formatting at the
+    /// end of the input (including the effect of any codes after the last
+    /// [`Span`]). Passing it to [`SpanIter::with_initial_state`] for the
+    /// next piece of text continues parsing as if the two pieces had been
+    /// parsed together, as long as they aren't split in the middle of a code
+    /// (see [`StreamParser`] for that).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, SpanIter, Span, Color, Styles};
+    ///
+    /// let mut first = "§4ab§l".span_iter();
+    /// assert_eq!(first.next().unwrap(), Span::new_styled("ab", Color::DarkRed, Styles::empty()));
+    /// assert!(first.next().is_none());
+    /// assert_eq!(first.state(), (Color::DarkRed, Styles::BOLD));
+    ///
+    /// let (color, styles) = first.state();
+    /// let mut second = "cd".span_iter().with_initial_state(color, styles);
+    /// assert_eq!(second.next().unwrap(), Span::new_styled("cd", Color::DarkRed, Styles::BOLD));
+    /// ```
+    pub fn state(&self) -> (Color, Styles) {
+        (self.color, self.styles)
+    }
+
+    /// Restart parsing from the beginning of the input string
+    ///
+    /// The formatting goes back to what it was when parsing began (the state
+    /// given to [`SpanIter::with_initial_state`], or [`Color::White`] and
+    /// [`Styles::empty()`]), and every other option is kept. This is handy for
+    /// algorithms that make two passes over the same text, like measuring it
+    /// before rendering it, without having to keep the input string around
+    /// separately.
+    ///
+    /// Use [`Iterator::by_ref`] to consume spans without giving up ownership
+    /// of the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§6gold §lbold".span_iter();
+    ///
+    /// let width: usize = span_iter.by_ref().map(|span| span.display_width(0)).sum();
+    /// assert_eq!(width, 9);
+    ///
+    /// span_iter.reset();
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("gold ", Color::Gold, Styles::empty()));
+    /// ```
+    pub fn reset(&mut self) {
+        self.pos = 0;
+        self.escaped_start_char = None;
+        self.trailing_reset_done = false;
+        self.rest = None;
+        self.span_color_is_default = true;
+        self.background = None;
+
+        match self.initial_state {
+            Some((color, styles)) => {
+                self.color = color;
+                self.color_is_default = false;
+                self.styles = styles;
+            }
+            None => {
+                self.color = Color::White;
+                self.color_is_default = true;
+                self.styles = Styles::empty();
+            }
+        }
+    }
+
+    /// Yield the byte range of each [`Span`]'s text in the input string
+    /// alongside the [`Span`] itself
+    ///
+    /// The text between these ranges is made up entirely of formatting codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let s = "§4red §lbold";
+    /// let mut span_iter = s.span_iter().with_ranges();
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), (3..7, Span::new_styled("red ", Color::DarkRed, Styles::empty())));
+    /// assert_eq!(span_iter.next().unwrap(), (10..14, Span::new_styled("bold", Color::DarkRed, Styles::BOLD)));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_ranges(self) -> SpanRanges<'a> {
+        SpanRanges { span_iter: self }
+    }
+
+    /// Yield the formatting codes that precede each [`Span`] in the input
+    /// string alongside the [`Span`] itself
+    ///
+    /// The codes are a slice of the input, so they're exactly as they were
+    /// written (including the casing of each code char). Together with
+    /// [`LegacyStringBuilder::push_with_codes`] this allows re-serializing
+    /// parsed text without normalizing the codes that were used.
+    ///
+    /// Codes after the last [`Span`] aren't yielded, since they don't affect
+    /// any text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let s = "§D§Lpink §Rwhite";
+    /// let mut span_iter = s.span_iter().with_codes();
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), ("§D§L", Span::new_styled("pink ", Color::LightPurple, Styles::BOLD)));
+    /// assert_eq!(span_iter.next().unwrap(), ("§R", Span::new_plain("white")));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_codes(self) -> SpanCodes<'a> {
+        SpanCodes {
+            buf: self.buf,
+            span_ranges: self.with_ranges(),
+            codes_start: 0,
+        }
+    }
+
+    /// Yield whether the color of each [`Span`] was defaulted alongside it
+    ///
+    /// The flag is `true` when no color code has set the color of the span
+    /// (either because there hasn't been one yet or because the formatting was
+    /// reset since), in which case the color is [`Color::White`]. This tells an
+    /// explicit `§f` apart from text that's only white because of the default,
+    /// which is useful for linting (for example, to find text that would be
+    /// unreadable if it were shown on a light background). A color given to
+    /// [`SpanIter::with_initial_state`] counts as explicitly set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§lbold §fwhite".span_iter().with_color_defaults();
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), (true, Span::new_styled("bold ", Color::White, Styles::BOLD)));
+    /// assert_eq!(span_iter.next().unwrap(), (false, Span::new_plain("white")));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn with_color_defaults(self) -> SpanColorDefaults<'a> {
+        SpanColorDefaults { span_iter: self }
+    }
+
+    /// Stop once `n` visible chars have been yielded, cutting off the text of
+    /// the span that goes over the limit
+    ///
+    /// Chars are counted rather than columns, the same way [`visible_len`]
+    /// counts them, which makes this useful for enforcing a limit on the
+    /// length of a message while streaming it. The cut is always made on a
+    /// char boundary. Once the limit has been reached no more spans are
+    /// yielded, not even empty ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut span_iter = "§6Golden §capplé pie".span_iter().take_visible(11);
+    ///
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("Golden ", Color::Gold, Styles::empty()));
+    /// assert_eq!(span_iter.next().unwrap(), Span::new_styled("appl", Color::Red, Styles::empty()));
+    /// assert!(span_iter.next().is_none());
+    /// ```
+    pub fn take_visible(self, n: usize) -> TakeVisible<'a> {
+        TakeVisible {
+            span_iter: self,
+            remaining: n,
+        }
+    }
+
+    /// Split [`Span`]s at newlines, yielding the index of the line each one
+    /// is on alongside it
+    ///
+    /// See [`LineSpanIter`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let s = "§6§lfirst\nsecond §rthird";
+    /// let mut lines = s.span_iter().lines();
+    ///
+    /// assert_eq!(lines.next().unwrap(), (0, Span::new_styled("first", Color::Gold, Styles::BOLD)));
+    /// assert_eq!(lines.next().unwrap(), (1, Span::new_styled("second ", Color::Gold, Styles::BOLD)));
+    /// assert_eq!(lines.next().unwrap(), (1, Span::new_plain("third")));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn lines(self) -> LineSpanIter<'a> {
+        LineSpanIter::new(self)
+    }
+
+    /// Recognize player counts like `[116 / 1000]` in the text of the
+    /// [`Span`]s
+    ///
+    /// See [`PlayerCounts`] for more details.
+    pub fn player_counts(self) -> PlayerCounts<'a> {
+        PlayerCounts::new(self)
+    }
+
+    /// Yield [`StyledSpan`]s rather than [`Span`]s
+    ///
+    /// See [`StyledIter`] for more details.
+    pub fn styled(self) -> StyledIter<'a> {
+        StyledIter::new(self)
+    }
+
+    /// Display the remaining text with all of the formatting codes removed
+    ///
+    /// See [`Stripped`] for more details.
+    pub fn stripped(self) -> Stripped<'a> {
+        Stripped::new(self)
+    }
+
+    /// Sum the display widths of all the remaining [`Span`]s, using this
+    /// iterator's tab width and width mode
+    ///
+    /// See [`Span::display_width`] and [`Span::pixel_width`] for how the width
+    /// of each span is measured.
+    pub fn display_width(self) -> usize {
+        let tab_width = self.tab_width;
+
+        match self.width_mode {
+            WidthMode::Columns => self.map(|span| span.display_width(tab_width)).sum(),
+            WidthMode::Pixels => self.map(|span| span.pixel_width()).sum(),
+        }
+    }
+
+    /// Clear `out` and fill it with the [`Span`]s from this iterator
+    ///
+    /// This allows a single buffer to be reused across many parses rather than
+    /// allocating a new [`Vec`] every time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanExt, Span, Color, Styles};
+    ///
+    /// let mut buf = Vec::new();
+    ///
+    /// "§4dark red".span_iter().collect_into(&mut buf);
+    /// assert_eq!(buf, vec![Span::new_styled("dark red", Color::DarkRed, Styles::empty())]);
+    ///
+    /// "§bNew §cMOTD".span_iter().collect_into(&mut buf);
+    /// assert_eq!(
+    ///     buf,
+    ///     vec![
+    ///         Span::new_styled("New ", Color::Aqua, Styles::empty()),
+    ///         Span::new_styled("MOTD", Color::Red, Styles::empty()),
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn collect_into(self, out: &mut Vec<Span<'a>>) {
+        out.clear();
+        out.extend(self);
+    }
+
+    /// Make a [`Span`] based off the current state of the iterator
+    ///
+    /// The span will be from `start..end`
+    ///
+    /// `whitespace_only` must say whether the text is all ASCII whitespace.
+    fn make_span(&mut self, start: usize, end: usize, whitespace_only: bool) -> Span<'a> {
+        self.span_color_is_default = self.color_is_default;
+        Span::from_parts(
+            &self.buf[start..end],
+            self.color,
+            self.styles,
+            whitespace_only,
+        )
+        .with_background(self.background)
+    }
+
+    /// Apply the fmt code `c` to the current state of the iterator
+    fn apply_code(&mut self, c: char) -> bool {
+        let reset_char = self.reset_char;
+
+        match reset_char {
+            _ if Some(c) == self.background_prefix => self.apply_background_code(),
+            Some(reset_char) if c.eq_ignore_ascii_case(&reset_char) => {
+                self.color = Color::White;
+                self.color_is_default = true;
+                self.styles = Styles::empty();
+                self.background = None;
+                true
+            }
+            _ if self.hex_colors && self.apply_hex_code(c) => true,
+            _ => match classify_code(c) {
+                CodeKind::Color(color) => {
+                    self.set_color(color);
+                    true
+                }
+                CodeKind::Style(style) => {
+                    self.styles.insert(style);
+                    true
+                }
+                // `r` is only a reset code if it's been configured as one
+                CodeKind::Reset | CodeKind::Invalid => self.apply_custom_code(c),
+            },
+        }
+    }
+
+    /// Apply the custom code that begins with `c` (the char following the
+    /// start char), consuming the char after it, if there is one
+    fn apply_custom_code(&mut self, c: char) -> bool {
+        let hook = match self.custom_code {
+            Some(hook) => hook,
+            None => return false,
+        };
+        let modifier = match self.buf[self.pos..].chars().next() {
+            Some(modifier) => modifier,
+            None => return false,
+        };
+        let action = match hook(c, modifier) {
+            Some(action) => action,
+            None => return false,
+        };
+
+        match action {
+            CustomAction::Color(color) => self.set_color(color),
+            CustomAction::Style(styles) => self.styles.insert(styles),
+            CustomAction::Set(color, styles) => {
+                self.color = color;
+                self.color_is_default = false;
+                self.styles = styles;
+            }
+            CustomAction::Reset => {
+                self.color = Color::White;
+                self.color_is_default = true;
+                self.styles = Styles::empty();
+                self.background = None;
+            }
+        }
+        self.pos += modifier.len_utf8();
+        true
+    }
+
+    /// Apply the background code whose prefix was just parsed, consuming the
+    /// color code char after it, if there is one
+    fn apply_background_code(&mut self) -> bool {
+        let c = match self.buf[self.pos..].chars().next() {
+            Some(c) => c,
+            None => return false,
+        };
+
+        self.background = match self.reset_char {
+            Some(reset_char) if c.eq_ignore_ascii_case(&reset_char) => None,
+            _ => match Color::from_char(c) {
+                Some(color) => Some(color),
+                None => return false,
+            },
+        };
+        self.pos += c.len_utf8();
+        true
+    }
+
+    /// Apply the hex color code that begins with `c` (the char following the
+    /// start char), consuming the rest of it, if there is one
+    fn apply_hex_code(&mut self, c: char) -> bool {
+        let (color, len) = match parse_hex_code(c, &self.buf[self.pos..], self.start_char) {
+            Some(hex) => hex,
+            None => return false,
+        };
+
+        self.pos += len;
+        self.set_color(if self.quantize_hex {
+            color.nearest_named()
+        } else {
+            color
+        });
+        true
+    }
+
+    /// Apply a color code, clearing the styles if that's what the client
+    /// being matched does
+    fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.color_is_default = false;
+        if self.color_resets_styles {
+            self.styles = Styles::empty();
+        }
+    }
+}
+
+/// Parse the hex color code that begins with `c` (the char following the
+/// start char), if there is one
+///
+/// `rest` is the input following `c`. Returns the color and the length in
+/// bytes of the rest of the code in `rest`.
+pub(crate) fn parse_hex_code(c: char, rest: &str, start_char: char) -> Option<(Color, usize)> {
+    let mut hex = [0; 6];
+
+    let len = match c {
+        // `§x§r§r§g§g§b§b`
+        'x' | 'X' => {
+            let mut chars = rest.chars();
+            for digit in &mut hex {
+                match (chars.next(), chars.next()) {
+                    (Some(c), Some(d)) if c == start_char && d.is_ascii_hexdigit() => {
+                        *digit = d as u8
+                    }
+                    _ => return None,
+                }
+            }
+
+            rest.len() - chars.as_str().len()
+        }
+        // `§#rrggbb`
+        '#' => match rest.as_bytes().get(..6) {
+            Some(digits) if digits.iter().all(u8::is_ascii_hexdigit) => {
+                hex.copy_from_slice(digits);
+                6
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    // The digits were all checked to be ASCII
+    let color = core::str::from_utf8(&hex)
+        .ok()
+        .and_then(Color::from_hex_str)?;
+
+    Some((color, len))
+}
+
+/// Apply the formatting code `c` (the char following the start char) to the
+/// given `color` and `styles`, following the same rules as the vanilla client.
+///
+/// * A color code sets the color and clears all styles
+/// * A style code adds that style to the current styles
+/// * `r` resets the color to [`Color::White`] and clears all styles
+///
+/// Codes are case-insensitive. Returns `false` (leaving `color` and `styles`
+/// untouched) if `c` isn't a recognized code.
+///
+/// This is exactly what [`SpanIter`] uses internally, so it's useful for
+/// renderers that want to match the parser's behavior.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{apply_code, Color, Styles};
+///
+/// let mut color = Color::White;
+/// let mut styles = Styles::empty();
+///
+/// assert!(apply_code('l', &mut color, &mut styles));
+/// assert!(apply_code('6', &mut color, &mut styles));
+/// assert_eq!((color, styles), (Color::Gold, Styles::empty()));
+///
+/// assert!(apply_code('O', &mut color, &mut styles));
+/// assert_eq!((color, styles), (Color::Gold, Styles::ITALIC));
+///
+/// assert!(!apply_code('z', &mut color, &mut styles));
+/// assert_eq!((color, styles), (Color::Gold, Styles::ITALIC));
+///
+/// assert!(apply_code('r', &mut color, &mut styles));
+/// assert_eq!((color, styles), (Color::White, Styles::empty()));
+/// ```
+pub fn apply_code(c: char, color: &mut Color, styles: &mut Styles) -> bool {
+    match classify_code(c) {
+        CodeKind::Color(new_color) => {
+            *color = new_color;
+            // According to https://wiki.vg/Chat, using a color code resets the current
+            // style
+            *styles = Styles::empty();
+        }
+        CodeKind::Style(style) => styles.insert(style),
+        CodeKind::Reset => {
+            *color = Color::White;
+            *styles = Styles::empty();
+        }
+        CodeKind::Invalid => return false,
+    }
+
+    true
+}
+
+/// Keeps track of the state for each iteration
+#[derive(Debug, Copy, Clone)]
+enum SpanIterState {
+    GatheringStyles(GatheringStylesState),
+    GatheringText(GatheringTextState),
+}
+
+/// In this state we are at the beginning of an iteration and we are looking to
+/// handle any initial formatting codes
+#[derive(Debug, Copy, Clone)]
+enum GatheringStylesState {
+    /// We're looking for our start char
+    ExpectingStartChar,
+    /// We've found our start char and are expecting a fmt code after it
+    ExpectingFmtCode,
+}
+
+/// In this state we've encountered text unrelated to formatting, which means
+/// the next valid fmt code we encounter ends this iteration
+#[derive(Debug, Copy, Clone)]
+enum GatheringTextState {
+    /// We're waiting to find our start char
+    WaitingForStartChar,
+    /// We've found our start char and are expecting a fmt code after it
+    ///
+    /// If we find a valid fmt code in this state, we need to make a span, apply
+    /// this last fmt code to our state, and end this iteration.
+    ExpectingEndChar,
+}
+
+impl<'a> SpanIter<'a> {
+    /// Skip over text up to the next start char (or the end of the input),
+    /// keeping track of whether the text is all whitespace
+    ///
+    /// Nothing but a start char can end the text, so there's no need to look at
+    /// the text a char at a time. Finding a char in a `str` is done with a fast
+    /// byte scan, and a run of whitespace is only ever looked at once.
+    fn skip_text(&mut self, whitespace_only: &mut bool) {
+        let rest = &self.buf[self.pos..];
+        let whitespace_len = if *whitespace_only {
+            rest.bytes()
+                .take_while(|&b| b.is_ascii_whitespace() && char::from(b) != self.start_char)
+                .count()
+        } else {
+            0
+        };
+        let text_len = rest[whitespace_len..]
+            .find(self.start_char)
+            .map_or(rest.len(), |offset| whitespace_len + offset);
+
+        *whitespace_only &= text_len == whitespace_len;
+        self.pos += text_len;
+    }
+
+    /// Count the backslashes in the text directly before the start char at
+    /// `idx`, and whether they escape it
+    ///
+    /// Always `(0, false)` unless [`SpanIter::with_backslash_escape`] is
+    /// enabled.
+    fn backslashes_before(&self, span_start: usize, idx: usize) -> (usize, bool) {
+        if !self.backslash_escape {
+            return (0, false);
+        }
+
+        let text = &self.buf[span_start..idx];
+        let count = text.len() - text.trim_end_matches('\\').len();
+
+        // Like an unescaped start char, an escaped one only takes the
+        // backslashes with it if it would have begun a valid code
+        (count, count % 2 == 1 && self.code_follows())
+    }
+
+    /// Check whether the start char that was just parsed is followed by a
+    /// valid code, without applying it
+    fn code_follows(&self) -> bool {
+        let mut peek = self.clone();
+        peek.next_char().map_or(false, |(_, c)| peek.apply_code(c))
+    }
+
+    /// Check whether `c` (the char following a start char that was just
+    /// parsed) makes up a valid code, without applying it
+    fn is_code(&self, c: char) -> bool {
+        self.clone().apply_code(c)
+    }
+
+    /// Get the next char of the input along with its byte offset in `buf`
+    fn next_char(&mut self) -> Option<(usize, char)> {
+        let idx = self.pos;
+        let c = self.buf[idx..].chars().next()?;
+        self.pos += c.len_utf8();
+
+        Some((idx, c))
+    }
+
+    /// Parse the next [`Span`] out of the input
+    fn next_span(&mut self) -> Option<Span<'a>> {
+        use GatheringStylesState::*;
+        use GatheringTextState::*;
+        use SpanIterState::*;
+
+        let mut state = GatheringStyles(ExpectingStartChar);
+        let mut span_start = None;
+        let mut span_end = None;
+        // Whether all of the text gathered so far is whitespace, which is kept
+        // up to date as the text is consumed so that it doesn't have to be
+        // scanned again to make the span
+        let mut whitespace_only = true;
+
+        if let Some(idx) = self.escaped_start_char.take() {
+            span_start = Some(idx);
+            whitespace_only = self.start_char.is_ascii_whitespace();
+            state = GatheringText(WaitingForStartChar);
+        }
+
+        while let Some((idx, c)) = self.next_char() {
+            state = match state {
+                GatheringStyles(style_state) => match style_state {
+                    ExpectingStartChar => {
+                        span_start = Some(idx);
+                        match c {
+                            c if c == self.start_char => GatheringStyles(ExpectingFmtCode),
+                            _ => {
+                                whitespace_only = c.is_ascii_whitespace();
+                                GatheringText(WaitingForStartChar)
+                            }
+                        }
+                    }
+                    ExpectingFmtCode => {
+                        if self.doubled_escape && c == self.start_char {
+                            // Drop the first start char and treat the second as text
+                            span_start = Some(idx);
+                            whitespace_only = c.is_ascii_whitespace();
+                            GatheringText(WaitingForStartChar)
+                        } else if self.apply_code(c) {
+                            span_start = None;
+                            GatheringStyles(ExpectingStartChar)
+                        } else {
+                            whitespace_only =
+                                self.start_char.is_ascii_whitespace() && c.is_ascii_whitespace();
+                            GatheringText(WaitingForStartChar)
+                        }
+                    }
+                },
+                GatheringText(text_state) => match text_state {
+                    WaitingForStartChar => match c {
+                        c if c == self.start_char => {
+                            let start = span_start.unwrap();
+                            let (backslashes, escaped) = self.backslashes_before(start, idx);
+                            // Only half of the backslashes are kept
+                            let end = idx - backslashes + backslashes / 2;
+
+                            if !escaped {
+                                span_end = Some(end);
+                                GatheringText(ExpectingEndChar)
+                            } else if end == start {
+                                // Nothing to end a span with, so the text just
+                                // begins with the start char
+                                span_start = Some(idx);
+                                whitespace_only = c.is_ascii_whitespace();
+                                state
+                            } else {
+                                self.escaped_start_char = Some(idx);
+                                return Some(self.make_span(start, end, whitespace_only));
+                            }
+                        }
+                        _ => {
+                            whitespace_only &= c.is_ascii_whitespace();
+                            self.skip_text(&mut whitespace_only);
+                            state
+                        }
+                    },
+                    ExpectingEndChar => {
+                        // Note that we only end this iteration if we find a valid fmt code
+                        //
+                        // If we do, we make sure to apply it to our state so that we can
+                        // pick up where we left off when the next iteration begins
+                        //
+                        // The span has to be made before the fmt code is applied, since
+                        // it uses the formatting that was in effect up until now
+
+                        if self.doubled_escape && c == self.start_char {
+                            // An escaped start char; this span ends before the first start
+                            // char and the next one begins with the second
+                            self.escaped_start_char = Some(idx);
+                            return Some(self.make_span(
+                                span_start.unwrap(),
+                                span_end.unwrap(),
+                                whitespace_only,
+                            ));
+                        }
+
+                        if self.is_code(c) {
+                            let span = self.make_span(
+                                span_start.unwrap(),
+                                span_end.unwrap(),
+                                whitespace_only,
+                            );
+                            self.apply_code(c);
+                            return Some(span);
+                        } else {
+                            span_end = None;
+                            whitespace_only &=
+                                self.start_char.is_ascii_whitespace() && c.is_ascii_whitespace();
+                            GatheringText(WaitingForStartChar)
+                        }
+                    }
+                },
+            }
+        }
+
+        match span_start {
+            Some(start) => {
+                // A start char at the very end of the input is part of the text
+                if matches!(state, GatheringStyles(ExpectingFmtCode)) || span_end.is_some() {
+                    whitespace_only &= self.start_char.is_ascii_whitespace();
+                }
+
+                Some(self.make_span(start, self.buf.len(), whitespace_only))
+            }
+            None if self.trailing_reset && !self.trailing_reset_done => {
+                self.trailing_reset_done = true;
+                self.span_color_is_default = true;
+                // An empty slice at the very end of the input, so that the
+                // span's text is still part of `buf`
+                Some(Span::new_plain(&self.buf[self.buf.len()..]))
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'a> Iterator for SpanIter<'a> {
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut span = match self.rest.take() {
+                Some(span) => span,
+                None => self.next_span()?,
+            };
+            let text = span.text();
+            // Where to split the span, if it needs to be split
+            let mut split = None;
+            let mut is_url = false;
+
+            if self.split_on_newline {
+                split = lines::find_line_break(text)
+                    .map(|(_, end)| end)
+                    .filter(|&end| end < text.len());
+            }
+
+            // Only `Span::Styled` can carry a background
+            if self.url_detection && span.background().is_none() {
+                match url::find_url(text) {
+                    // The text before the URL comes first (unless there's a
+                    // newline to split at before that)
+                    Some(url) if url.start > 0 => {
+                        split = Some(split.map_or(url.start, |end| end.min(url.start)));
+                    }
+                    // URLs never contain newlines, so there's nothing else to
+                    // split at
+                    Some(url) => {
+                        is_url = true;
+                        split = Some(url.end).filter(|&end| end < text.len());
+                    }
+                    None => {}
+                }
+            }
+
+            if let Some(split) = split {
+                self.rest = Some(span.with_text(&text[split..]));
+                span = span.with_text(&text[..split]);
+            }
+
+            if is_url {
+                span = Span::new_url(span.text(), span.color(), span.styles());
+            }
+
+            if !(self.skip_empty && span.is_empty()) {
+                return Some(span);
+            }
+        }
+    }
+}
+
+/// An iterator that yields [`Span`]s along with the byte range of their text
+/// in the input string.
+///
+/// Created by [`SpanIter::with_ranges`].
+#[derive(Debug, Clone)]
+pub struct SpanRanges<'a> {
+    span_iter: SpanIter<'a>,
+}
+
+impl<'a> Iterator for SpanRanges<'a> {
+    type Item = (Range<usize>, Span<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.span_iter.next()?;
+        // Every span's text is a slice of the input string, so its offset can
+        // be worked out from where it starts in memory
+        let start = span.text().as_ptr() as usize - self.span_iter.buf.as_ptr() as usize;
+
+        Some((start..start + span.text().len(), span))
+    }
+}
+
+/// An iterator that yields [`Span`]s along with the formatting codes that
+/// precede them in the input string.
+///
+/// Created by [`SpanIter::with_codes`].
+#[derive(Debug, Clone)]
+pub struct SpanCodes<'a> {
+    buf: &'a str,
+    span_ranges: SpanRanges<'a>,
+    /// Where the codes before the next span begin (the end of the last span)
+    codes_start: usize,
+}
+
+impl<'a> Iterator for SpanCodes<'a> {
+    type Item = (&'a str, Span<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (range, span) = self.span_ranges.next()?;
+        let codes = &self.buf[self.codes_start..range.start];
+        self.codes_start = range.end;
+
+        Some((codes, span))
+    }
+}
+
+/// An iterator that stops after a number of visible chars.
+///
+/// Created by [`SpanIter::take_visible`].
+#[derive(Debug, Clone)]
+pub struct TakeVisible<'a> {
+    span_iter: SpanIter<'a>,
+    /// The number of chars that can still be yielded
+    remaining: usize,
+}
+
+impl<'a> Iterator for TakeVisible<'a> {
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let span = self.span_iter.next()?;
+        let text = span.text();
+
+        match text.char_indices().nth(self.remaining) {
+            Some((end, _)) => {
+                self.remaining = 0;
+                Some(match span {
+                    Span::Url { color, styles, .. } => Span::new_url(&text[..end], color, styles),
+                    _ => span.with_text(&text[..end]),
+                })
+            }
+            None => {
+                self.remaining -= text.chars().count();
+                Some(span)
+            }
+        }
+    }
+}
+
+/// An iterator that yields [`Span`]s along with whether their color was
+/// defaulted rather than set by a color code.
+///
+/// Created by [`SpanIter::with_color_defaults`].
+#[derive(Debug, Clone)]
+pub struct SpanColorDefaults<'a> {
+    span_iter: SpanIter<'a>,
+}
+
+impl<'a> Iterator for SpanColorDefaults<'a> {
+    type Item = (bool, Span<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.span_iter.next()?;
+
+        Some((self.span_iter.span_color_is_default, span))
+    }
+}
+
+/// Text with an associated color and associated styles.
+///
+/// [`Span`] implements [`Display`](core::fmt::Display) and can be neatly printed.
+///
+/// More variants may be added in the future (as [`Span::Url`] was), so matches
+/// on a [`Span`] need a wildcard arm.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Span<'a> {
+    /// A styled slice of text
+    Styled {
+        /// The styled text slice
+        text: &'a str,
+        /// The color of the text
+        color: Color,
+        /// Styles that should be applied to the text
+        styles: Styles,
+        /// The color behind the text, if it was given one
+        ///
+        /// Legacy chat has no background codes, so this is always `None`
+        /// unless [`SpanIter::with_background_prefix`] is used.
+        background: Option<Color>,
+    },
+    /// An unbroken sequence of whitespace that was given the
+    /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH) style.
+    ///
+    /// The vanilla client renders whitespace with the `STRIKETHROUGH` style
+    /// as a solid line; this variant allows for replicating that behavior.
+    StrikethroughWhitespace {
+        /// The styled whitespace slice
+        text: &'a str,
+        /// The color of the whitespace (and therefore the line over it)
+        color: Color,
+        /// Styles applied to the whitespace (will contain at least
+        /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH))
+        styles: Styles,
+    },
+    /// An unstyled slice of text
+    ///
+    /// This should be given a default style. The vanilla client
+    /// would use [`Color::White`] and [`Styles::empty()`].
+    Plain(&'a str),
+    /// A URL found in the text
+    ///
+    /// Only yielded when [`SpanIter::with_url_detection`] is enabled, so that
+    /// renderers can make the URL clickable.
+    Url {
+        /// The URL
+        text: &'a str,
+        /// The color of the text the URL was found in
+        color: Color,
+        /// Styles applied to the text the URL was found in
+        styles: Styles,
+    },
+}
+
+impl core::fmt::Display for Span<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            // TODO: handle random style
+            Span::Styled { text, .. } => f.write_str(text),
+            Span::StrikethroughWhitespace { text, .. } => {
+                (0..text.len()).try_for_each(|_| f.write_str("-"))
+            }
+            Span::Plain(text) => f.write_str(text),
+            Span::Url { text, .. } => f.write_str(text),
+        }
+    }
+}
+
+impl<'a> Span<'a> {
+    /// Create a new [`Span::Plain`]
+    pub fn new_plain(s: &'a str) -> Self {
+        Span::Plain(s)
+    }
+
+    /// Create a new [`Span::StrikethroughWhitespace`]
+    pub fn new_strikethrough_whitespace(s: &'a str, color: Color, styles: Styles) -> Self {
+        Span::StrikethroughWhitespace {
+            text: s,
+            color,
+            styles,
+        }
+    }
+
+    /// Create a new [`Span::Styled`] without a background
+    pub fn new_styled(s: &'a str, color: Color, styles: Styles) -> Self {
+        Span::Styled {
+            text: s,
+            color,
+            styles,
+            background: None,
+        }
+    }
+
+    /// Create a new [`Span::Url`]
+    pub fn new_url(s: &'a str, color: Color, styles: Styles) -> Self {
+        Span::Url {
+            text: s,
+            color,
+            styles,
+        }
+    }
+
+    /// Get the text slice this [`Span`] refers to
+    ///
+    /// For [`Span::StrikethroughWhitespace`] this is the original whitespace,
+    /// not the line it gets rendered as.
+    pub fn text(&self) -> &'a str {
+        match self {
+            Span::Styled { text, .. } => text,
+            Span::StrikethroughWhitespace { text, .. } => text,
+            Span::Plain(text) => text,
+            Span::Url { text, .. } => text,
+        }
+    }
+
+    /// Check whether the text of this [`Span`] is empty
+    ///
+    /// This is the case for every kind of span without any text, including
+    /// [`Span::StrikethroughWhitespace`] (which has nothing to draw a line
+    /// over). Use [`SpanIter::skip_empty`] to never get empty spans from the
+    /// parser in the first place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{Span, Color, Styles};
+    ///
+    /// assert!(Span::new_styled("", Color::Green, Styles::empty()).is_empty());
+    /// assert!(!Span::new_plain(" ").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.text().is_empty()
+    }
+
+    /// Get the color of this [`Span`]
+    ///
+    /// [`Span::Plain`] has the default color, [`Color::White`].
+    pub fn color(&self) -> Color {
+        match self {
+            Span::Styled { color, .. } => *color,
+            Span::StrikethroughWhitespace { color, .. } => *color,
+            Span::Plain(_) => Color::White,
+            Span::Url { color, .. } => *color,
+        }
+    }
+
+    /// Get the styles of this [`Span`]
+    ///
+    /// [`Span::Plain`] has no styles.
+    pub fn styles(&self) -> Styles {
+        match self {
+            Span::Styled { styles, .. } => *styles,
+            Span::StrikethroughWhitespace { styles, .. } => *styles,
+            Span::Plain(_) => Styles::empty(),
+            Span::Url { styles, .. } => *styles,
+        }
+    }
+
+    /// Get the background color of this [`Span`]
+    ///
+    /// Only a [`Span::Styled`] can have one. Renderers that honor it would
+    /// usually draw [`Color::background_hex_str`] behind the text, which is
+    /// the darker shade the client uses for the text's shadow.
+    pub fn background(&self) -> Option<Color> {
+        match self {
+            Span::Styled { background, .. } => *background,
+            _ => None,
+        }
+    }
+
+    /// Give this [`Span`] a background color, or remove it with `None`
+    ///
+    /// Since only [`Span::Styled`] can have a background, any other kind of
+    /// span (including a [`Span::Url`]) becomes a [`Span::Styled`] with the
+    /// same text and formatting when it's given one. Removing the background
+    /// picks the kind of span for its text and formatting the same way the
+    /// parser does it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{Span, Color, Styles};
+    ///
+    /// let span = Span::new_plain("hi").with_background(Some(Color::Blue));
+    ///
+    /// assert_eq!(
+    ///     span,
+    ///     Span::Styled {
+    ///         text: "hi",
+    ///         color: Color::White,
+    ///         styles: Styles::empty(),
+    ///         background: Some(Color::Blue),
+    ///     }
+    /// );
+    /// assert_eq!(span.with_background(None), Span::new_plain("hi"));
+    /// ```
+    pub fn with_background(self, background: Option<Color>) -> Self {
+        match (self, background) {
+            (_, Some(_)) => Span::Styled {
+                text: self.text(),
+                color: self.color(),
+                styles: self.styles(),
+                background,
+            },
+            (
+                Span::Styled {
+                    background: Some(_),
+                    ..
+                },
+                None,
+            ) => Span::from_formatting(self.text(), self.color(), self.styles()),
+            (span, None) => span,
+        }
+    }
+
+    /// Iterate over the grapheme clusters in the text of this [`Span`]
+    ///
+    /// A grapheme cluster is what a reader sees as a single character, like an
+    /// emoji or a letter followed by combining accents, even when it's made up
+    /// of several chars. Moving a cursor by graphemes never breaks one apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Span;
+    ///
+    /// let span = Span::new_plain("cafe\u{301}");
+    ///
+    /// assert_eq!(span.text().chars().count(), 5);
+    /// assert_eq!(span.graphemes().collect::<Vec<_>>(), ["c", "a", "f", "e\u{301}"]);
+    /// ```
+    #[cfg(feature = "unicode")]
+    pub fn graphemes(&self) -> impl Iterator<Item = &'a str> {
+        unicode_segmentation::UnicodeSegmentation::graphemes(self.text(), true)
+    }
+
+    /// Get the number of columns the text of this [`Span`] takes up when
+    /// displayed
+    ///
+    /// Every char counts as one column, except for `\t`, which counts as
+    /// `tab_width` columns (or one column if `tab_width` is `0`), and `\n` and
+    /// `\r`, which don't take up any space (so text with `\r\n` line endings
+    /// is as wide as the same text with `\n`). To measure each line of
+    /// multi-line text on its own, use [`SpanIter::lines`].
+    ///
+    /// With the `unicode` feature, every [grapheme](Span::graphemes) counts as
+    /// one column instead, so emoji and chars with combining marks aren't
+    /// counted more than once.
+    ///
+    /// Pass [`SpanIter::tab_width`] to honor the setting of the iterator the
+    /// span came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Span;
+    ///
+    /// let span = Span::new_plain("a\tb");
+    ///
+    /// assert_eq!(span.display_width(0), 3);
+    /// assert_eq!(span.display_width(4), 6);
+    /// ```
+    pub fn display_width(&self, tab_width: u8) -> usize {
+        let width = |c: char| match c {
+            '\t' if tab_width > 0 => tab_width as usize,
+            '\n' | '\r' => 0,
+            _ => 1,
+        };
+
+        // `\r\n` is a single grapheme, so it's the last char of each one that
+        // decides its width
+        #[cfg(feature = "unicode")]
+        let width = self
+            .graphemes()
+            .map(|g| g.chars().next_back().map_or(0, width))
+            .sum();
+        #[cfg(not(feature = "unicode"))]
+        let width = self.text().chars().map(width).sum();
+
+        width
+    }
+
+    /// Get the number of pixels the text of this [`Span`] takes up when the
+    /// vanilla client draws it with the default font (at a GUI scale of 1)
+    ///
+    /// Each char is as wide as its [`glyph_width`], plus one more pixel if the
+    /// span is [`BOLD`](Styles::BOLD) (since the client draws bold glyphs
+    /// twice, one pixel apart). Chars without any width, such as `\n`, don't
+    /// get the extra pixel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{Span, Color, Styles};
+    ///
+    /// assert_eq!(Span::new_plain("Hi!").pixel_width(), 10);
+    /// assert_eq!(Span::new_styled("Hi!", Color::Gold, Styles::BOLD).pixel_width(), 13);
+    /// ```
+    pub fn pixel_width(&self) -> usize {
+        let bold = usize::from(self.styles().contains(Styles::BOLD));
 
-                        if let Some(color) = Color::from_char(c) {
-                            let span = self.make_span(span_start.unwrap(), span_end.unwrap());
-                            self.update_color(color);
-                            return Some(span);
-                        } else if let Some(style) = Styles::from_char(c) {
-                            let span = self.make_span(span_start.unwrap(), span_end.unwrap());
-                            self.update_styles(style);
-                            return Some(span);
-                        } else if c == 'r' || c == 'R' {
-                            // Handle the `RESET` fmt code
+        self.text()
+            .chars()
+            .map(|c| match glyph_width(c) {
+                0 => 0,
+                width => usize::from(width) + bold,
+            })
+            .sum()
+    }
 
-                            let span = self.make_span(span_start.unwrap(), span_end.unwrap());
-                            self.reset_styles();
-                            return Some(span);
-                        } else {
-                            span_end = None;
-                            GatheringText(WaitingForStartChar)
-                        }
-                    }
-                },
+    /// Make the right kind of [`Span`] for `text` with the given formatting,
+    /// the same way the parser does
+    pub(crate) fn from_formatting(text: &'a str, color: Color, styles: Styles) -> Self {
+        let whitespace_only =
+            styles.contains(Styles::STRIKETHROUGH) && text.bytes().all(|b| b.is_ascii_whitespace());
+        Span::from_parts(text, color, styles, whitespace_only)
+    }
+
+    /// Make the right kind of [`Span`] for `text` with the given formatting,
+    /// when it's already known whether `text` is all ASCII whitespace
+    fn from_parts(text: &'a str, color: Color, styles: Styles, whitespace_only: bool) -> Self {
+        if color == Color::White && styles.is_empty() {
+            Span::Plain(text)
+        } else {
+            // The vanilla client renders whitespace with `Styles::STRIKETHROUGH`
+            // as a solid line. This replicates that behavior
+            //
+            // (Technically it does this by drawing a line over any text slice
+            // with the `STRIKETHROUGH` style.)
+            if whitespace_only && styles.contains(Styles::STRIKETHROUGH) {
+                Span::StrikethroughWhitespace {
+                    text,
+                    color,
+                    styles,
+                }
+            } else {
+                Span::Styled {
+                    text,
+                    color,
+                    styles,
+                    background: None,
+                }
             }
         }
+    }
 
-        span_start.map(|start| self.make_span(start, self.buf.len()))
+    /// Make a [`Span`] with the same formatting as this one but with different
+    /// text
+    ///
+    /// The kind of [`Span`] is picked for the new text the same way the parser
+    /// does it (so a piece of whitespace split off of a [`Span::Styled`] with
+    /// the `STRIKETHROUGH` style becomes a [`Span::StrikethroughWhitespace`]),
+    /// except that a span with a background stays a [`Span::Styled`].
+    pub(crate) fn with_text(self, text: &'a str) -> Self {
+        Span::from_formatting(text, self.color(), self.styles()).with_background(self.background())
     }
-}
 
-/// Text with an associated color and associated styles.
-///
-/// [`Span`] implements [`Display`](core::fmt::Display) and can be neatly printed.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub enum Span<'a> {
-    /// A styled slice of text
-    Styled {
-        /// The styled text slice
-        text: &'a str,
-        /// The color of the text
-        color: Color,
-        /// Styles that should be applied to the text
-        styles: Styles,
-    },
-    /// An unbroken sequence of whitespace that was given the
-    /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH) style.
+    /// Convert this [`Span`] into a single canonical form for how it renders
     ///
-    /// The vanilla client renders whitespace with the `STRIKETHROUGH` style
-    /// as a solid line; this variant allows for replicating that behavior.
-    StrikethroughWhitespace {
-        /// The styled whitespace slice
-        text: &'a str,
-        /// The color of the whitespace (and therefore the line over it)
-        color: Color,
-        /// Styles applied to the whitespace (will contain at least
-        /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH))
-        styles: Styles,
-    },
-    /// An unstyled slice of text
+    /// The derived [`PartialEq`] and [`Hash`] implementations compare spans
+    /// exactly, so two spans that look the same can still be unequal, which
+    /// gets in the way of deduplicating them (such as in a `HashSet<Span>`).
+    /// Spans that render the same are equal once they've been canonicalized.
+    /// These normalizations are made:
     ///
-    /// This should be given a default style. The vanilla client
-    /// would use [`Color::White`] and [`Styles::empty()`].
-    Plain(&'a str),
-}
+    /// * A [`Color::Rgb`] that's exactly the same as one of the named colors
+    ///   becomes that named color (as [`Color::from_hex_str`] does)
+    /// * A span with [`Color::White`] and no styles becomes a [`Span::Plain`]
+    /// * Whether a span is a [`Span::Styled`] or a
+    ///   [`Span::StrikethroughWhitespace`] is picked for its text and styles,
+    ///   the same way the parser does it
+    /// * A span without any text becomes `Span::Plain("")`
+    ///
+    /// A background color is kept (and snapped to a named color the same way),
+    /// so a span with one stays a [`Span::Styled`]. Other than losing its
+    /// formatting when it has no text, a [`Span::Url`] stays a URL with the
+    /// same styles, since being a URL changes how a span is handled rather
+    /// than how it looks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{Span, Color, Styles};
+    ///
+    /// assert_eq!(
+    ///     Span::new_styled("hi", Color::White, Styles::empty()).canonicalize(),
+    ///     Span::new_plain("hi")
+    /// );
+    /// assert_eq!(
+    ///     Span::new_styled("hi", Color::Rgb(255, 85, 85), Styles::BOLD).canonicalize(),
+    ///     Span::new_styled("hi", Color::Red, Styles::BOLD)
+    /// );
+    /// ```
+    pub fn canonicalize(self) -> Self {
+        let (text, color, styles) = (self.text(), self.color().exact_named(), self.styles());
 
-impl core::fmt::Display for Span<'_> {
-    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
-            // TODO: handle random style
-            Span::Styled { text, .. } => f.write_str(text),
-            Span::StrikethroughWhitespace { text, .. } => {
-                (0..text.len()).try_for_each(|_| f.write_str("-"))
-            }
-            Span::Plain(text) => f.write_str(text),
+            _ if text.is_empty() => Span::Plain(""),
+            Span::Url { .. } => Span::new_url(text, color, styles),
+            _ => Span::from_formatting(text, color, styles)
+                .with_background(self.background().map(Color::exact_named)),
         }
     }
-}
 
-impl<'a> Span<'a> {
-    /// Create a new [`Span::Plain`]
-    pub fn new_plain(s: &'a str) -> Self {
-        Span::Plain(s)
+    /// Wraps this [`Span`] in a type that enables colored printing
+    #[cfg(feature = "color-print")]
+    pub fn wrap_colored(self) -> PrintSpanColored<'a> {
+        PrintSpanColored::from(self)
     }
 
-    /// Create a new [`Span::StrikethroughWhitespace`]
-    pub fn new_strikethrough_whitespace(s: &'a str, color: Color, styles: Styles) -> Self {
-        Span::StrikethroughWhitespace {
-            text: s,
-            color,
-            styles,
-        }
+    /// Copies the text of this [`Span`] into an [`OwnedSpan`]
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> OwnedSpan {
+        OwnedSpan::from(self)
     }
 
-    /// Create a new [`Span::Styled`]
-    pub fn new_styled(s: &'a str, color: Color, styles: Styles) -> Self {
-        Span::Styled {
-            text: s,
-            color,
-            styles,
+    /// Replace the text of this [`Span`] with the result of `f`, keeping its
+    /// formatting
+    ///
+    /// `f` can return the text it's given (or any other borrowed string)
+    /// without allocating. The kind of span is picked for the new text the
+    /// same way the parser does it, except that a [`Span::Url`] stays a URL
+    /// and a span with a background stays a [`Span::Styled`]. See [`CowSpan`]
+    /// for an example.
+    #[cfg(feature = "alloc")]
+    pub fn map_text(self, f: impl FnOnce(&'a str) -> Cow<'a, str>) -> CowSpan<'a> {
+        let (color, styles) = (self.color(), self.styles());
+        let text = f(self.text());
+
+        if let Some(background) = self.background() {
+            return CowSpan::Styled {
+                text,
+                color,
+                styles,
+                background: Some(background),
+            };
         }
+
+        let make: fn(Cow<'a, str>, Color, Styles) -> CowSpan<'a> = match self {
+            Span::Url { .. } => CowSpan::new_url,
+            _ => match Span::from_formatting(&text, color, styles) {
+                Span::Styled { .. } | Span::Url { .. } => CowSpan::new_styled,
+                Span::StrikethroughWhitespace { .. } => CowSpan::new_strikethrough_whitespace,
+                Span::Plain(_) => |text, _, _| CowSpan::new_plain(text),
+            },
+        };
+
+        make(text, color, styles)
     }
+}
 
-    /// Wraps this [`Span`] in a type that enables colored printing
-    #[cfg(feature = "color-print")]
-    pub fn wrap_colored(self) -> PrintSpanColored<'a> {
-        PrintSpanColored::from(self)
+impl<'a> From<&'a Span<'_>> for Span<'a> {
+    fn from(s: &'a Span<'_>) -> Self {
+        *s
     }
 }
 
+/// Every color code char, in order from [`Color::Black`] (`0`) to
+/// [`Color::White`] (`f`).
+///
+/// The vanilla client also accepts the uppercase versions of the letters.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{Color, COLOR_CODES};
+///
+/// assert_eq!(Color::from_char(COLOR_CODES[9]), Some(Color::Blue));
+/// ```
+pub const COLOR_CODES: [char; 16] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+];
+
+/// Every style code char, in the same order as the bits of [`Styles`].
+///
+/// The vanilla client also accepts the uppercase versions of these.
+pub const STYLE_CODES: [char; 5] = ['k', 'l', 'm', 'n', 'o'];
+
+/// The code char that resets the color and styles.
+///
+/// The vanilla client also accepts `R`.
+pub const RESET_CODE: char = 'r';
+
 /// Various colors that a [`Span`] can have.
 ///
 /// See [the wiki.vg docs][colors] for specific information.
@@ -415,6 +2190,13 @@ pub enum Color {
     LightPurple,
     Yellow,
     White,
+    /// A truecolor color given as (red, green, blue)
+    ///
+    /// Newer versions of Minecraft (1.16+) support arbitrary colors in chat
+    /// components. Note that derived equality is exact, so a truecolor color
+    /// is never equal to a named one; see [`Color::approx_eq`] for comparing
+    /// by rendered color instead.
+    Rgb(u8, u8, u8),
 }
 
 impl Default for Color {
@@ -438,7 +2220,7 @@ impl Color {
             '6' => Color::Gold,
             '7' => Color::Gray,
             '8' => Color::DarkGray,
-            '9' => Color::DarkBlue,
+            '9' => Color::Blue,
             // The vanilla client accepts lower or uppercase interchangeably
             'a' | 'A' => Color::Green,
             'b' | 'B' => Color::Aqua,
@@ -450,6 +2232,100 @@ impl Color {
         })
     }
 
+    /// Map a four-bit value to a [`Color`], for binary formats that pack a
+    /// color into a nibble
+    ///
+    /// Values are in the same order as the color codes, so `n` is the index of
+    /// the color's code in [`COLOR_CODES`] (`0x0` is [`Color::Black`] and `0xf`
+    /// is [`Color::White`]). Returns [`None`] if `n` is larger than `0xf`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::from_nibble(0x6), Some(Color::Gold));
+    /// assert_eq!(Color::from_nibble(0x10), None);
+    /// ```
+    pub fn from_nibble(n: u8) -> Option<Self> {
+        COLOR_CODES
+            .get(usize::from(n))
+            .and_then(|&c| Color::from_char(c))
+    }
+
+    /// Get the four-bit value for this color; the reverse of
+    /// [`Color::from_nibble`]
+    ///
+    /// [`Color::Rgb`] doesn't fit in a nibble and returns [`None`]; use
+    /// [`Color::nearest_named`] first to pack it as the closest named color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::Gold.to_nibble(), Some(0x6));
+    /// assert_eq!(Color::Rgb(255, 136, 0).to_nibble(), None);
+    /// ```
+    pub const fn to_nibble(&self) -> Option<u8> {
+        Some(match self {
+            Color::Black => 0x0,
+            Color::DarkBlue => 0x1,
+            Color::DarkGreen => 0x2,
+            Color::DarkAqua => 0x3,
+            Color::DarkRed => 0x4,
+            Color::DarkPurple => 0x5,
+            Color::Gold => 0x6,
+            Color::Gray => 0x7,
+            Color::DarkGray => 0x8,
+            Color::Blue => 0x9,
+            Color::Green => 0xa,
+            Color::Aqua => 0xb,
+            Color::Red => 0xc,
+            Color::LightPurple => 0xd,
+            Color::Yellow => 0xe,
+            Color::White => 0xf,
+            Color::Rgb(..) => return None,
+        })
+    }
+
+    /// Parse a `#rrggbb` (or bare `rrggbb`) hex color string
+    ///
+    /// The hex digits are case-insensitive. A color that's exactly the same as
+    /// one of the named colors is returned as that named color; any other
+    /// color is returned as [`Color::Rgb`]. Returns `None` if `s` isn't six hex
+    /// digits long (not counting the `#`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::from_hex_str("#ff8800"), Some(Color::Rgb(255, 136, 0)));
+    /// assert_eq!(Color::from_hex_str("55FFFF"), Some(Color::Aqua));
+    /// assert_eq!(Color::from_hex_str("#f80"), None);
+    /// ```
+    pub fn from_hex_str(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        // `from_str_radix` would also accept a leading `+`
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+        Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?).exact_named())
+    }
+
+    /// Get the named color that's exactly the same as this one, or this color
+    /// if there isn't one
+    pub(crate) fn exact_named(self) -> Self {
+        COLOR_CODES
+            .iter()
+            .filter_map(|&c| Color::from_char(c))
+            .find(|named| named.approx_eq(&self))
+            .unwrap_or(self)
+    }
+
     /// Get the correct foreground hex color string for a given color
     ///
     /// # Examples
@@ -457,26 +2333,10 @@ impl Color {
     /// ```
     /// use mc_legacy_formatting::Color;
     /// assert_eq!(Color::Aqua.foreground_hex_str(), "#55ffff");
+    /// assert_eq!(Color::Rgb(255, 136, 0).foreground_hex_str(), "#ff8800");
     /// ```
-    pub const fn foreground_hex_str(&self) -> &'static str {
-        match self {
-            Color::Black => "#000000",
-            Color::DarkBlue => "#0000aa",
-            Color::DarkGreen => "#00aa00",
-            Color::DarkAqua => "#00aaaa",
-            Color::DarkRed => "#aa0000",
-            Color::DarkPurple => "#aa00aa",
-            Color::Gold => "#ffaa00",
-            Color::Gray => "#aaaaaa",
-            Color::DarkGray => "#555555",
-            Color::Blue => "#5555ff",
-            Color::Green => "#55ff55",
-            Color::Aqua => "#55ffff",
-            Color::Red => "#ff5555",
-            Color::LightPurple => "#ff55ff",
-            Color::Yellow => "#ffff55",
-            Color::White => "#ffffff",
-        }
+    pub const fn foreground_hex_str(&self) -> HexStr {
+        HexStr::from_rgb(self.foreground_rgb())
     }
 
     /// Get the correct background hex color string for a given color
@@ -487,25 +2347,8 @@ impl Color {
     /// use mc_legacy_formatting::Color;
     /// assert_eq!(Color::Aqua.background_hex_str(), "#153f3f");
     /// ```
-    pub const fn background_hex_str(&self) -> &'static str {
-        match self {
-            Color::Black => "#000000",
-            Color::DarkBlue => "#00002a",
-            Color::DarkGreen => "#002a00",
-            Color::DarkAqua => "#002a2a",
-            Color::DarkRed => "#2a0000",
-            Color::DarkPurple => "#2a002a",
-            Color::Gold => "#2a2a00",
-            Color::Gray => "#2a2a2a",
-            Color::DarkGray => "#151515",
-            Color::Blue => "#15153f",
-            Color::Green => "#153f15",
-            Color::Aqua => "#153f3f",
-            Color::Red => "#3f1515",
-            Color::LightPurple => "#3f153f",
-            Color::Yellow => "#3f3f15",
-            Color::White => "#3f3f3f",
-        }
+    pub const fn background_hex_str(&self) -> HexStr {
+        HexStr::from_rgb(self.background_rgb())
     }
 
     /// Get the correct foreground RGB color values for a given color
@@ -536,6 +2379,7 @@ impl Color {
             Color::LightPurple => (255, 85, 255),
             Color::Yellow => (255, 255, 85),
             Color::White => (255, 255, 255),
+            Color::Rgb(r, g, b) => (*r, *g, *b),
         }
     }
 
@@ -567,8 +2411,247 @@ impl Color {
             Color::LightPurple => (63, 21, 63),
             Color::Yellow => (63, 63, 21),
             Color::White => (63, 63, 63),
+            // The vanilla client darkens the color to a quarter of its
+            // brightness for the background
+            Color::Rgb(r, g, b) => (*r / 4, *g / 4, *b / 4),
+        }
+    }
+
+    /// Whether or not this color is perceptually light
+    ///
+    /// This is useful for picking a contrasting color to go along with it (for
+    /// example, black text or outlines for light colors and white ones for dark
+    /// colors).
+    ///
+    /// The perceived luminance of the [foreground color](Color::foreground_rgb)
+    /// is computed with the ITU-R BT.601 weighting
+    /// (`0.299 * red + 0.587 * green + 0.114 * blue`), and colors with a
+    /// luminance of at least half of the maximum (128 out of 255) are
+    /// considered light.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert!(Color::Yellow.is_light());
+    /// assert!(!Color::DarkBlue.is_light());
+    /// ```
+    pub const fn is_light(&self) -> bool {
+        let (r, g, b) = self.foreground_rgb();
+        // Scaled up by 1000 to stay in integer math
+        let luminance = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+
+        luminance >= 128 * 1000
+    }
+
+    /// Compare two colors by the foreground color they render as, rather than
+    /// by variant
+    ///
+    /// The derived [`PartialEq`] implementation is exact: a truecolor
+    /// [`Color::Rgb`] is never equal to a named color, even if they look the
+    /// same. That's what you want for hashing and for keeping track of what a
+    /// string actually contained, but when asking "will these look the same?"
+    /// use this method instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert!(Color::Rgb(255, 85, 85).approx_eq(&Color::Red));
+    /// assert_ne!(Color::Rgb(255, 85, 85), Color::Red);
+    /// ```
+    pub const fn approx_eq(&self, other: &Color) -> bool {
+        let (r1, g1, b1) = self.foreground_rgb();
+        let (r2, g2, b2) = other.foreground_rgb();
+
+        r1 == r2 && g1 == g2 && b1 == b2
+    }
+
+    /// Get the named color whose foreground color is closest to this one
+    ///
+    /// Named colors are returned as they are. For [`Color::Rgb`], the named
+    /// color with the smallest (squared) euclidean distance in RGB space is
+    /// picked, which is useful when targeting clients that only support the 16
+    /// named colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::Rgb(250, 160, 10).nearest_named(), Color::Gold);
+    /// assert_eq!(Color::Rgb(20, 20, 20).nearest_named(), Color::Black);
+    /// assert_eq!(Color::Aqua.nearest_named(), Color::Aqua);
+    /// ```
+    pub fn nearest_named(&self) -> Color {
+        let (r, g, b) = self.foreground_rgb();
+        let distance = |color: &Color| {
+            let (r2, g2, b2) = color.foreground_rgb();
+            let (dr, dg, db) = (
+                r as i32 - r2 as i32,
+                g as i32 - g2 as i32,
+                b as i32 - b2 as i32,
+            );
+            dr * dr + dg * dg + db * db
+        };
+
+        match self {
+            Color::Rgb(..) => COLOR_CODES
+                .iter()
+                .filter_map(|&c| Color::from_char(c))
+                .min_by_key(distance)
+                .unwrap_or(Color::White),
+            named => *named,
+        }
+    }
+
+    /// Get the name of the xterm color closest to this color
+    ///
+    /// Names are the conventional (lowercase) names of the entries in the
+    /// xterm 256-color palette, as used by tools like vim and tmux, and are
+    /// picked by hand so that each named color gets a distinct name. The
+    /// closest palette entries are used rather than the 16 basic colors, whose
+    /// actual look varies between terminals. [`Color::Rgb`] gets the name of
+    /// its [nearest named color](Color::nearest_named).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::Gold.xterm_name(), "orange1");
+    /// assert_eq!(Color::DarkGray.xterm_name(), "grey35");
+    /// assert_eq!(Color::Rgb(250, 160, 10).xterm_name(), "orange1");
+    /// ```
+    pub fn xterm_name(&self) -> &'static str {
+        match self.nearest_named() {
+            Color::Black => "black",
+            Color::DarkBlue => "blue3",
+            Color::DarkGreen => "green3",
+            Color::DarkAqua => "lightseagreen",
+            Color::DarkRed => "red3",
+            Color::DarkPurple => "magenta3",
+            Color::Gold => "orange1",
+            Color::Gray => "grey66",
+            Color::DarkGray => "grey35",
+            Color::Blue => "royalblue1",
+            Color::Green => "seagreen2",
+            Color::Aqua => "darkslategray2",
+            Color::Red => "indianred1",
+            Color::LightPurple => "mediumorchid1",
+            Color::Yellow => "lightgoldenrod1",
+            Color::White | Color::Rgb(..) => "white",
         }
     }
+
+    /// Get a CSS class name for this color, for styling HTML output with a
+    /// stylesheet rather than inline colors
+    ///
+    /// Class names are the kebab-case names of the named colors with an `mc-`
+    /// prefix (`mc-dark-red`, `mc-gold`, ...), so a site can theme each color
+    /// independently of the colors the client uses. [`Color::Rgb`] has no class
+    /// and returns `None`; renderers should fall back to an inline style with
+    /// [`Color::foreground_hex_str`] for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Color;
+    ///
+    /// assert_eq!(Color::DarkRed.css_class(), Some("mc-dark-red"));
+    /// assert_eq!(Color::LightPurple.css_class(), Some("mc-light-purple"));
+    /// assert_eq!(Color::Rgb(255, 136, 0).css_class(), None);
+    /// ```
+    pub const fn css_class(&self) -> Option<&'static str> {
+        Some(match self {
+            Color::Black => "mc-black",
+            Color::DarkBlue => "mc-dark-blue",
+            Color::DarkGreen => "mc-dark-green",
+            Color::DarkAqua => "mc-dark-aqua",
+            Color::DarkRed => "mc-dark-red",
+            Color::DarkPurple => "mc-dark-purple",
+            Color::Gold => "mc-gold",
+            Color::Gray => "mc-gray",
+            Color::DarkGray => "mc-dark-gray",
+            Color::Blue => "mc-blue",
+            Color::Green => "mc-green",
+            Color::Aqua => "mc-aqua",
+            Color::Red => "mc-red",
+            Color::LightPurple => "mc-light-purple",
+            Color::Yellow => "mc-yellow",
+            Color::White => "mc-white",
+            Color::Rgb(..) => return None,
+        })
+    }
+}
+
+/// A `#rrggbb` hex color string.
+///
+/// Returned by [`Color::foreground_hex_str`] and [`Color::background_hex_str`].
+/// Derefs to a [`str`] and can be compared directly with string slices.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct HexStr([u8; 7]);
+
+impl HexStr {
+    /// Format (red, green, blue) color values as a hex string
+    const fn from_rgb(rgb: (u8, u8, u8)) -> Self {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        HexStr([
+            b'#',
+            DIGITS[(rgb.0 >> 4) as usize],
+            DIGITS[(rgb.0 & 0xf) as usize],
+            DIGITS[(rgb.1 >> 4) as usize],
+            DIGITS[(rgb.1 & 0xf) as usize],
+            DIGITS[(rgb.2 >> 4) as usize],
+            DIGITS[(rgb.2 & 0xf) as usize],
+        ])
+    }
+
+    /// Get the hex string as a `&str`
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.0).expect("hex strings are always ASCII")
+    }
+}
+
+impl core::ops::Deref for HexStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for HexStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::fmt::Debug for HexStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Display for HexStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for HexStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for HexStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
 }
 
 bitflags! {
@@ -621,4 +2704,23 @@ impl Styles {
             _ => return None,
         })
     }
+
+    /// Get these styles without [`Styles::RANDOM`]
+    ///
+    /// Obfuscated text is animated, so it can't be drawn by anything that
+    /// produces a still image (such as SVG or HTML rendered to an image).
+    /// Output targets like that can call this before applying styles instead
+    /// of special-casing obfuscation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::Styles;
+    ///
+    /// assert_eq!((Styles::BOLD | Styles::RANDOM).renderable(), Styles::BOLD);
+    /// assert_eq!(Styles::ITALIC.renderable(), Styles::ITALIC);
+    /// ```
+    pub const fn renderable(&self) -> Styles {
+        self.difference(Styles::RANDOM)
+    }
 }
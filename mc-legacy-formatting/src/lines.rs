@@ -0,0 +1,172 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{Span, SpanIter};
+
+/// An iterator that splits [`Span`]s at newlines and yields them alongside the
+/// index of the line they're on.
+///
+/// Spans containing a line break are split into multiple spans with the same
+/// formatting, and the line break itself is never part of a yielded span's
+/// text. `\n`, `\r\n` (even if the `\r` and `\n` end up in different spans),
+/// and a lone `\r` are each a single line break.
+/// Formatting carries over from one line to the next exactly like it does for
+/// [`SpanIter`] (the vanilla client doesn't reset anything at a newline).
+///
+/// Lines without any text (such as the middle line of `"a\n\nb"`) don't yield
+/// any spans, so the line index can skip ahead by more than one.
+///
+/// Created by [`SpanIter::lines`].
+#[derive(Debug, Clone)]
+pub struct LineSpanIter<'a> {
+    span_iter: SpanIter<'a>,
+    /// The part of the last span from the inner iterator that's after a
+    /// newline and hasn't been yielded yet
+    remainder: Option<Span<'a>>,
+    line: usize,
+    /// Whether the last span ended with a `\r`, in which case a `\n` at the
+    /// start of the next one is part of the same line break
+    after_cr: bool,
+}
+
+impl<'a> LineSpanIter<'a> {
+    pub(crate) fn new(span_iter: SpanIter<'a>) -> Self {
+        Self {
+            span_iter,
+            remainder: None,
+            line: 0,
+            after_cr: false,
+        }
+    }
+}
+
+impl<'a> Iterator for LineSpanIter<'a> {
+    type Item = (usize, Span<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut span = match self.remainder.take() {
+                Some(span) => span,
+                None => self.span_iter.next()?,
+            };
+
+            if core::mem::take(&mut self.after_cr) && span.text().starts_with('\n') {
+                if span.text().len() == 1 {
+                    continue;
+                }
+                span = span.with_text(&span.text()[1..]);
+            }
+            let text = span.text();
+
+            match find_line_break(text) {
+                Some((start, end)) => {
+                    let line = self.line;
+                    self.line += 1;
+
+                    if end < text.len() {
+                        self.remainder = Some(span.with_text(&text[end..]));
+                    } else {
+                        self.after_cr = text.ends_with('\r');
+                    }
+
+                    if start > 0 {
+                        return Some((line, span.with_text(&text[..start])));
+                    }
+                }
+                None => return Some((self.line, span)),
+            }
+        }
+    }
+}
+
+/// Find the first line break in `text` (`\n`, `\r\n`, or a lone `\r`),
+/// returning the byte range it takes up
+pub(crate) fn find_line_break(text: &str) -> Option<(usize, usize)> {
+    let start = text.find(|c| c == '\n' || c == '\r')?;
+    let len = if text[start..].starts_with("\r\n") {
+        2
+    } else {
+        1
+    };
+
+    Some((start, start + len))
+}
+
+/// Reduce runs of consecutive line breaks in `spans` down to at most
+/// `max_consecutive` line breaks.
+///
+/// Like [`SpanIter::lines`], `\n`, `\r\n` (even if the `\r` and `\n` are in
+/// different spans), and a lone `\r` each count as a single line break, and a
+/// `\r\n` is always either kept or removed as a whole.
+///
+/// Runs are counted across span boundaries, and line breaks can be removed
+/// from the middle of a span; when that happens the span is split into
+/// multiple spans with the same formatting (so no text is copied). The
+/// formatting of the spans is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{collapse_blank_lines, SpanExt, Span, Color, Styles};
+///
+/// let s = "§bfirst line\n\n\n§6second line";
+/// let spans = collapse_blank_lines(s.span_iter().collect(), 1);
+///
+/// assert_eq!(
+///     spans,
+///     vec![
+///         Span::new_styled("first line\n", Color::Aqua, Styles::empty()),
+///         Span::new_styled("second line", Color::Gold, Styles::empty()),
+///     ]
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn collapse_blank_lines<'a>(spans: Vec<Span<'a>>, max_consecutive: usize) -> Vec<Span<'a>> {
+    let mut collapsed = Vec::with_capacity(spans.len());
+    let mut consecutive = 0;
+    // Whether the last byte was a `\r`, and if so, whether it was removed
+    let mut after_cr = None;
+
+    for span in spans {
+        let text = span.text();
+        let mut piece_start = 0;
+        let mut split = false;
+
+        // `\n` and `\r` are single bytes in UTF-8 and can never be part of a
+        // multi-byte character, so walking the bytes is fine here
+        for (idx, b) in text.bytes().enumerate() {
+            let remove = match b {
+                // The `\n` of a `\r\n` goes wherever its `\r` went
+                b'\n' if after_cr.is_some() => after_cr.take() == Some(true),
+                b'\n' | b'\r' => {
+                    consecutive += 1;
+                    let remove = consecutive > max_consecutive;
+                    after_cr = if b == b'\r' { Some(remove) } else { None };
+                    remove
+                }
+                _ => {
+                    consecutive = 0;
+                    after_cr = None;
+                    continue;
+                }
+            };
+
+            if remove {
+                if piece_start < idx {
+                    collapsed.push(span.with_text(&text[piece_start..idx]));
+                }
+
+                piece_start = idx + 1;
+                split = true;
+            }
+        }
+
+        if !split {
+            collapsed.push(span);
+        } else if piece_start < text.len() {
+            collapsed.push(span.with_text(&text[piece_start..]));
+        }
+    }
+
+    collapsed
+}
@@ -0,0 +1,473 @@
+use core::{fmt, ops::Range};
+
+use crate::{parse_hex_code, raw_tokens, RawTokens, Styles, TargetVersion, Token};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// Something that doesn't change how the text looks, but could be
+    /// cleaned up
+    Info,
+    /// Something that probably doesn't look the way it was meant to
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// What a [`Diagnostic`] is about.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DiagnosticKind {
+    /// Formatting codes at the end of the text, which don't apply to anything
+    TrailingCodes,
+    /// A color code that's replaced by another color code (or a reset)
+    /// before any text
+    RedundantColor,
+    /// A style code that's cleared by a color code (or a reset) before any
+    /// text, so it never shows up
+    WipedStyle,
+    /// A code written with an uppercase char, which works but is unusual
+    UppercaseCode(char),
+    /// A start char that isn't followed by a valid code (or by anything at
+    /// all), which is displayed as text
+    UnknownCode(Option<char>),
+    /// Visible text past the limit given to [`Lint::with_max_visible_len`]
+    TooLong,
+    /// A hex color, which is only supported by Java Edition 1.16 and later
+    ///
+    /// Only reported for the version given to [`Lint::with_target_version`].
+    /// Both the `§x§r§r§g§g§b§b` form and the `§#rrggbb` form that plugins
+    /// accept are recognized.
+    HexColor,
+    /// A material color code (such as `§g` for minecoin gold) that only
+    /// Bedrock Edition supports
+    ///
+    /// Only reported when [`Lint::with_target_version`] is given a Java
+    /// Edition version, which displays these as text.
+    BedrockColor(char),
+    /// A style code that Bedrock Edition doesn't support
+    ///
+    /// Only reported when [`Lint::with_target_version`] is given
+    /// [`TargetVersion::Bedrock`], which uses `§m` and `§n` for material
+    /// colors rather than [`Styles::STRIKETHROUGH`] and
+    /// [`Styles::UNDERLINED`].
+    UnsupportedStyle(Styles),
+}
+
+impl DiagnosticKind {
+    /// How serious this kind of diagnostic is
+    pub const fn severity(&self) -> Severity {
+        match self {
+            DiagnosticKind::TrailingCodes
+            | DiagnosticKind::RedundantColor
+            | DiagnosticKind::UppercaseCode(_) => Severity::Info,
+            DiagnosticKind::WipedStyle
+            | DiagnosticKind::UnknownCode(_)
+            | DiagnosticKind::TooLong
+            | DiagnosticKind::HexColor
+            | DiagnosticKind::BedrockColor(_)
+            | DiagnosticKind::UnsupportedStyle(_) => Severity::Warning,
+        }
+    }
+
+    /// A short description of this kind of diagnostic
+    pub const fn message(&self) -> &'static str {
+        match self {
+            DiagnosticKind::TrailingCodes => "formatting codes with no text after them",
+            DiagnosticKind::RedundantColor => "color code is replaced before any text",
+            DiagnosticKind::WipedStyle => "style code is cleared before any text",
+            DiagnosticKind::UppercaseCode(_) => "formatting code is uppercase",
+            DiagnosticKind::UnknownCode(_) => "unknown formatting code is displayed as text",
+            DiagnosticKind::TooLong => "text is longer than the limit",
+            DiagnosticKind::HexColor => "hex color isn't supported by the target version",
+            DiagnosticKind::BedrockColor(_) => "color code is only supported by Bedrock Edition",
+            DiagnosticKind::UnsupportedStyle(_) => "style code isn't supported by Bedrock Edition",
+        }
+    }
+}
+
+/// Something worth pointing out about a string with legacy formatting codes.
+///
+/// Produced by [`lint`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Diagnostic {
+    /// The byte range that the diagnostic is about
+    pub range: Range<usize>,
+    /// What the diagnostic is about
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    /// How serious the diagnostic is
+    pub const fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    /// A short description of the diagnostic
+    pub const fn message(&self) -> &'static str {
+        self.kind.message()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} at bytes {}..{}",
+            self.severity(),
+            self.message(),
+            self.range.start,
+            self.range.end
+        )
+    }
+}
+
+/// Find things worth pointing out in a string with legacy formatting codes,
+/// such as codes that don't do anything.
+///
+/// This is meant for tools that check text written by people (like a
+/// server's MOTD in its config) and want to explain what could be improved.
+/// See [`DiagnosticKind`] for everything that's reported and
+/// [`Lint::with_max_visible_len`] to also check the length of the text.
+/// Diagnostics are yielded in the order they appear in `s`. Uses `§` as the
+/// start char; see [`lint_with_start_char`] to use a different one.
+///
+/// Codes that a particular version of Minecraft doesn't support are only
+/// checked for if the version is given to [`Lint::with_target_version`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{lint, Diagnostic, DiagnosticKind, Severity};
+///
+/// let diagnostics: Vec<_> = lint("§l§aHello§r").collect();
+///
+/// assert_eq!(
+///     diagnostics,
+///     vec![
+///         Diagnostic { range: 0..3, kind: DiagnosticKind::WipedStyle },
+///         Diagnostic { range: 11..14, kind: DiagnosticKind::TrailingCodes },
+///     ]
+/// );
+/// assert_eq!(diagnostics[0].severity(), Severity::Warning);
+/// assert_eq!(
+///     diagnostics[0].to_string(),
+///     "warning: style code is cleared before any text at bytes 0..3"
+/// );
+/// ```
+pub fn lint(s: &str) -> Lint<'_> {
+    lint_with_start_char(s, '§')
+}
+
+/// Find things worth pointing out in a string with legacy formatting codes
+/// that use `start_char` as the start char.
+///
+/// See [`lint`] for more details.
+pub fn lint_with_start_char(s: &str, start_char: char) -> Lint<'_> {
+    Lint {
+        s,
+        tokens: raw_tokens(s, start_char),
+        start_char,
+        pos: 0,
+        visible_len: 0,
+        max_visible_len: None,
+        target_version: None,
+        queued: None,
+        done: false,
+    }
+}
+
+/// An iterator over the [`Diagnostic`]s for a string.
+///
+/// Created by [`lint`].
+#[derive(Debug, Clone)]
+pub struct Lint<'a> {
+    s: &'a str,
+    tokens: RawTokens<'a>,
+    start_char: char,
+    /// The byte offset in `s` of the next token
+    pos: usize,
+    /// The number of visible chars before `pos`
+    visible_len: usize,
+    max_visible_len: Option<usize>,
+    target_version: Option<TargetVersion>,
+    /// A second diagnostic for the last token, which is yielded next
+    queued: Option<Diagnostic>,
+    /// Whether the rest of `s` has been reported as trailing codes
+    done: bool,
+}
+
+impl<'a> Lint<'a> {
+    /// Report the visible text after the first `max` chars as
+    /// [`DiagnosticKind::TooLong`]
+    ///
+    /// `None` (the default) doesn't limit the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{lint, Diagnostic, DiagnosticKind};
+    ///
+    /// let s = "§6Welcome to the server!";
+    ///
+    /// assert_eq!(
+    ///     lint(s).with_max_visible_len(Some(7)).collect::<Vec<_>>(),
+    ///     vec![Diagnostic { range: 10..25, kind: DiagnosticKind::TooLong }]
+    /// );
+    /// assert_eq!(lint(s).with_max_visible_len(Some(22)).count(), 0);
+    /// ```
+    pub fn with_max_visible_len(mut self, max: Option<usize>) -> Self {
+        self.max_visible_len = max;
+        self
+    }
+
+    /// Also report the codes that `version` doesn't support, or don't check
+    /// for them with `None` (the default)
+    ///
+    /// This is meant for authoring tools that target a specific version, so
+    /// they can point out codes that will show up as text or do something
+    /// unexpected:
+    ///
+    /// * [`DiagnosticKind::HexColor`] for hex colors on anything other than
+    ///   Java Edition 1.16 and later
+    /// * [`DiagnosticKind::BedrockColor`] for Bedrock Edition's material
+    ///   colors (`§g`, `§h`, and so on) on Java Edition
+    /// * [`DiagnosticKind::UnsupportedStyle`] for strikethrough and underline
+    ///   on Bedrock Edition, which uses those codes for material colors
+    ///   instead
+    ///
+    /// Hex colors and material colors that `version` does support are
+    /// checked like any other color code, rather than being reported as
+    /// [`DiagnosticKind::UnknownCode`]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{lint, Diagnostic, DiagnosticKind, TargetVersion};
+    ///
+    /// let s = "§x§f§f§8§8§0§0Orange §gcoin";
+    ///
+    /// assert_eq!(
+    ///     lint(s).with_target_version(Some(TargetVersion::Java(16))).collect::<Vec<_>>(),
+    ///     vec![Diagnostic { range: 28..31, kind: DiagnosticKind::BedrockColor('g') }]
+    /// );
+    /// assert_eq!(
+    ///     lint(s).with_target_version(Some(TargetVersion::Java(8))).collect::<Vec<_>>(),
+    ///     vec![
+    ///         Diagnostic { range: 0..21, kind: DiagnosticKind::HexColor },
+    ///         Diagnostic { range: 28..31, kind: DiagnosticKind::BedrockColor('g') },
+    ///     ]
+    /// );
+    /// ```
+    pub fn with_target_version(mut self, version: Option<TargetVersion>) -> Self {
+        self.target_version = version;
+        self
+    }
+
+    /// Count the visible `text` that starts at `start`, reporting it if it
+    /// goes over the limit
+    fn count_visible(&mut self, start: usize, text: &str) -> Option<Diagnostic> {
+        let before = self.visible_len;
+        self.visible_len += text.chars().count();
+
+        let max = self.max_visible_len?;
+        if before > max || self.visible_len <= max {
+            return None;
+        }
+
+        let (offset, _) = text.char_indices().nth(max - before)?;
+        Some(Diagnostic {
+            range: start + offset..self.s.len(),
+            kind: DiagnosticKind::TooLong,
+        })
+    }
+
+    /// Check the code that starts at `start` and ends at `end`
+    ///
+    /// `overridden` is reported if the code's effect is replaced before any
+    /// text.
+    fn check_code(
+        &mut self,
+        overridden: Option<DiagnosticKind>,
+        start: usize,
+        end: usize,
+    ) -> (Option<Diagnostic>, Option<Diagnostic>) {
+        let code_start = start + self.start_char.len_utf8();
+        // Valid codes are always followed by their code char
+        let c = self.s[code_start..].chars().next().unwrap();
+        self.pos = end;
+
+        let diagnostic = |kind| Diagnostic {
+            range: start..end,
+            kind,
+        };
+        let overridden = match next_effect(self.tokens.clone()) {
+            NextEffect::Text => None,
+            NextEffect::Overridden => overridden.map(diagnostic),
+            NextEffect::End => {
+                self.done = true;
+                let trailing = Diagnostic {
+                    range: start..self.s.len(),
+                    kind: DiagnosticKind::TrailingCodes,
+                };
+                return (Some(trailing), None);
+            }
+        };
+        let uppercase =
+            Some(diagnostic(DiagnosticKind::UppercaseCode(c))).filter(|_| c.is_ascii_uppercase());
+
+        match overridden {
+            Some(overridden) => (Some(overridden), uppercase),
+            None => (uppercase, None),
+        }
+    }
+
+    /// Check the start char at `start` that isn't followed by a valid code
+    fn literal_start(&mut self, start: usize) -> (Option<Diagnostic>, Option<Diagnostic>) {
+        self.pos += self.start_char.len_utf8();
+        // The char after the start char is part of the next text
+        let code = self.s[self.pos..].chars().next();
+        let unknown = Diagnostic {
+            range: start..self.pos + code.map_or(0, char::len_utf8),
+            kind: DiagnosticKind::UnknownCode(code),
+        };
+
+        let too_long = self.count_visible(start, &self.s[start..self.pos]);
+        (Some(unknown), too_long)
+    }
+
+    /// Check the start char at `start` that isn't followed by a legacy code
+    /// against the target version, in case it begins a hex color or a
+    /// material color
+    fn check_version_code(
+        &mut self,
+        start: usize,
+    ) -> Option<(Option<Diagnostic>, Option<Diagnostic>)> {
+        let version = self.target_version?;
+        let code_start = start + self.start_char.len_utf8();
+        let c = self.s[code_start..].chars().next()?;
+        let after = code_start + c.len_utf8();
+
+        let (end, supported, kind) = match parse_hex_code(c, &self.s[after..], self.start_char) {
+            Some((_, len)) => (
+                after + len,
+                version.supports_hex(),
+                DiagnosticKind::HexColor,
+            ),
+            None if BEDROCK_COLOR_CODES.contains(&c.to_ascii_lowercase()) => (
+                after,
+                version == TargetVersion::Bedrock,
+                DiagnosticKind::BedrockColor(c),
+            ),
+            None => return None,
+        };
+        // Pick up after the whole code
+        self.tokens = raw_tokens(&self.s[end..], self.start_char);
+
+        if supported {
+            Some(self.check_code(Some(DiagnosticKind::RedundantColor), start, end))
+        } else {
+            self.pos = end;
+            Some((
+                Some(Diagnostic {
+                    range: start..end,
+                    kind,
+                }),
+                None,
+            ))
+        }
+    }
+}
+
+impl Iterator for Lint<'_> {
+    type Item = Diagnostic;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(diagnostic) = self.queued.take() {
+            return Some(diagnostic);
+        }
+
+        while !self.done {
+            let start = self.pos;
+            let (first, second) = match self.tokens.next()? {
+                Token::Text(text) => {
+                    self.pos += text.len();
+                    (self.count_visible(start, text), None)
+                }
+                Token::LiteralStart => {
+                    if let Some(diagnostics) = self.check_version_code(start) {
+                        diagnostics
+                    } else {
+                        self.literal_start(start)
+                    }
+                }
+                Token::Style(style)
+                    if self.target_version == Some(TargetVersion::Bedrock)
+                        && style.intersects(Styles::STRIKETHROUGH | Styles::UNDERLINED) =>
+                {
+                    self.pos = start + self.start_char.len_utf8() + 1;
+                    let unsupported = Diagnostic {
+                        range: start..self.pos,
+                        kind: DiagnosticKind::UnsupportedStyle(style),
+                    };
+                    (Some(unsupported), None)
+                }
+                token => {
+                    let overridden = match token {
+                        Token::Color(_) => Some(DiagnosticKind::RedundantColor),
+                        Token::Style(_) => Some(DiagnosticKind::WipedStyle),
+                        _ => None,
+                    };
+                    let end = start + self.start_char.len_utf8() + 1;
+                    self.check_code(overridden, start, end)
+                }
+            };
+
+            if let Some(first) = first {
+                self.queued = second;
+                return Some(first);
+            }
+        }
+
+        None
+    }
+}
+
+/// The codes for Bedrock Edition's material colors that aren't also Java
+/// Edition codes
+const BEDROCK_COLOR_CODES: &[char] = &['g', 'h', 'i', 'j', 'p', 'q', 's', 't', 'u', 'v'];
+
+/// What happens after a code, before the next text is displayed
+enum NextEffect {
+    /// Nothing but style codes
+    Text,
+    /// A color code or a reset clears the formatting
+    Overridden,
+    /// The string ends without any more text
+    End,
+}
+
+fn next_effect(tokens: RawTokens<'_>) -> NextEffect {
+    let mut effect = NextEffect::End;
+
+    for token in tokens {
+        match token {
+            Token::Style(_) => {}
+            Token::Color(_) | Token::Reset => effect = NextEffect::Overridden,
+            Token::Text(_) | Token::LiteralStart => {
+                return match effect {
+                    NextEffect::End => NextEffect::Text,
+                    effect => effect,
+                }
+            }
+        }
+    }
+
+    NextEffect::End
+}
@@ -0,0 +1,134 @@
+use core::fmt;
+
+use crate::{Color, Span, Styles};
+
+/// Change the color and styles of every span with `f`.
+///
+/// `f` is called once for each span with its color and styles, and returns
+/// the formatting the span should have instead. This is useful for theming
+/// (such as swapping [`Color::Gold`] for a brand color) or for dropping
+/// formatting a renderer can't handle; see [`grayscale`] and
+/// [`strip_obfuscation`] for a couple of ready-made mappers.
+///
+/// A [`Span::Plain`] is passed in as [`Color::White`] with no styles, the
+/// same formatting [`Span::color`] and [`Span::styles`] report for it. The
+/// text of each span is kept as it is, and the kind of span is picked for the
+/// new formatting, so adding or removing [`Styles::STRIKETHROUGH`] turns
+/// whitespace into a [`Span::StrikethroughWhitespace`] or back. [`Span::Url`]s
+/// stay URLs.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{map_style, SpanExt, Span, Color, Styles};
+///
+/// let themed: Vec<Span> = map_style("§6Gold §lbold".span_iter(), |color, styles| match color {
+///     Color::Gold => (Color::Rgb(255, 120, 0), styles),
+///     color => (color, styles),
+/// })
+/// .collect();
+///
+/// assert_eq!(
+///     themed,
+///     vec![
+///         Span::new_styled("Gold ", Color::Rgb(255, 120, 0), Styles::empty()),
+///         Span::new_styled("bold", Color::Rgb(255, 120, 0), Styles::BOLD),
+///     ]
+/// );
+/// ```
+pub fn map_style<'a, I, F>(spans: I, f: F) -> MapStyle<I::IntoIter, F>
+where
+    I: IntoIterator<Item = Span<'a>>,
+    F: FnMut(Color, Styles) -> (Color, Styles),
+{
+    MapStyle {
+        spans: spans.into_iter(),
+        f,
+    }
+}
+
+/// An iterator that changes the color and styles of spans.
+///
+/// Created by [`map_style`].
+#[derive(Clone)]
+pub struct MapStyle<I, F> {
+    spans: I,
+    f: F,
+}
+
+impl<I: fmt::Debug, F> fmt::Debug for MapStyle<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapStyle")
+            .field("spans", &self.spans)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, I, F> Iterator for MapStyle<I, F>
+where
+    I: Iterator<Item = Span<'a>>,
+    F: FnMut(Color, Styles) -> (Color, Styles),
+{
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.spans.next()?;
+        let (color, styles) = (self.f)(span.color(), span.styles());
+
+        Some(match span {
+            Span::Url { text, .. } => Span::new_url(text, color, styles),
+            _ => {
+                Span::from_formatting(span.text(), color, styles).with_background(span.background())
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+/// A [`map_style`] mapper that replaces every color with the closest of the
+/// four gray named colors.
+///
+/// The closest gray is picked by luminance, so [`Color::Black`],
+/// [`Color::DarkGray`], [`Color::Gray`] and [`Color::White`] stay the same.
+/// Styles are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{grayscale, Color, Styles};
+///
+/// assert_eq!(grayscale(Color::DarkBlue, Styles::BOLD), (Color::Black, Styles::BOLD));
+/// assert_eq!(grayscale(Color::Gold, Styles::empty()), (Color::Gray, Styles::empty()));
+/// assert_eq!(grayscale(Color::Yellow, Styles::empty()), (Color::White, Styles::empty()));
+/// ```
+pub fn grayscale(color: Color, styles: Styles) -> (Color, Styles) {
+    const GRAYS: [Color; 4] = [Color::Black, Color::DarkGray, Color::Gray, Color::White];
+
+    let (r, g, b) = color.foreground_rgb();
+    // Same weights as `Color::is_light`, scaled up by 1000
+    let luminance = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+    // The grays are evenly spaced 85 apart, so round to the nearest step
+    let idx = (luminance * 3 + 127_500) / 255_000;
+
+    (GRAYS[idx as usize], styles)
+}
+
+/// A [`map_style`] mapper that removes [`Styles::RANDOM`], for renderers that
+/// can't animate obfuscated text.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{strip_obfuscation, Color, Styles};
+///
+/// assert_eq!(
+///     strip_obfuscation(Color::Red, Styles::RANDOM | Styles::BOLD),
+///     (Color::Red, Styles::BOLD)
+/// );
+/// ```
+pub fn strip_obfuscation(color: Color, styles: Styles) -> (Color, Styles) {
+    (color, styles.renderable())
+}
@@ -0,0 +1,86 @@
+use core::iter::Enumerate;
+
+use crate::{Color, Span, SpanIter, Styles};
+
+/// An iterator that parses several strings in sequence as if they were one,
+/// carrying formatting over from the end of each string into the next.
+///
+/// Each [`Span`] is yielded alongside the index of the string its text came
+/// from. This is useful for text that's sent as multiple strings but
+/// displayed as a unit, like the four lines of a sign or the lines of a
+/// scoreboard, without having to join the strings together first.
+///
+/// Spans never cross string boundaries, and a formatting code must be fully
+/// contained within one string to be recognized (a start char at the very end
+/// of a string is treated as text).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{MultiSpanIter, Span, Color, Styles};
+///
+/// let sign = ["§1§lWelcome", "to the", "§cserver", ""];
+/// let mut span_iter = MultiSpanIter::new(sign);
+///
+/// assert_eq!(span_iter.next().unwrap(), (0, Span::new_styled("Welcome", Color::DarkBlue, Styles::BOLD)));
+/// assert_eq!(span_iter.next().unwrap(), (1, Span::new_styled("to the", Color::DarkBlue, Styles::BOLD)));
+/// assert_eq!(span_iter.next().unwrap(), (2, Span::new_styled("server", Color::Red, Styles::empty())));
+/// assert!(span_iter.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiSpanIter<'a, I> {
+    strs: Enumerate<I>,
+    /// The string currently being parsed along with its index
+    current: Option<(usize, SpanIter<'a>)>,
+    start_char: char,
+}
+
+impl<'a, I> MultiSpanIter<'a, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    /// Create a new [`MultiSpanIter`] to parse the given strings
+    pub fn new<S>(strs: S) -> Self
+    where
+        S: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            strs: strs.into_iter().enumerate(),
+            current: None,
+            start_char: '§',
+        }
+    }
+
+    /// Set the start character used while parsing
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.start_char = c;
+        self
+    }
+}
+
+impl<'a, I> Iterator for MultiSpanIter<'a, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    type Item = (usize, Span<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (color, styles) = match &mut self.current {
+                Some((idx, span_iter)) => match span_iter.next() {
+                    Some(span) => return Some((*idx, span)),
+                    None => (span_iter.color, span_iter.styles),
+                },
+                None => (Color::White, Styles::empty()),
+            };
+
+            let (idx, s) = self.strs.next()?;
+            self.current = Some((
+                idx,
+                SpanIter::new(s)
+                    .with_start_char(self.start_char)
+                    .with_initial_state(color, styles),
+            ));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use alloc::{borrow::Cow, string::String};
+
+/// Rewrite literal `\u00a7` and `\xa7` escape sequences in `s` into actual `§`
+/// chars.
+///
+/// Text that went through JSON or some other escaping layer sometimes still
+/// contains the section sign as an escape sequence rather than the char
+/// itself, which the parser would display as text. The hex digits are matched
+/// case-insensitively, and an escaped backslash (`\\`) is skipped over so
+/// that `\\u00a7` is left alone.
+///
+/// `s` is returned as it is if it doesn't contain any escape sequences.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{normalize_section_escapes, SpanExt, Span, Color, Styles};
+///
+/// let s = normalize_section_escapes(r"\u00a74red \xA7lbold");
+///
+/// assert_eq!(s, "§4red §lbold");
+/// assert_eq!(
+///     s.span_iter().next().unwrap(),
+///     Span::new_styled("red ", Color::DarkRed, Styles::empty())
+/// );
+/// ```
+pub fn normalize_section_escapes(s: &str) -> Cow<'_, str> {
+    let mut out: Option<String> = None;
+    // The end of the last escape sequence that was rewritten
+    let mut copied_up_to = 0;
+    let mut idx = 0;
+
+    while let Some(offset) = s[idx..].find('\\') {
+        let start = idx + offset;
+        let rest = &s[start + 1..];
+
+        if rest.starts_with('\\') {
+            idx = start + 2;
+            continue;
+        }
+
+        let len = match section_escape_len(rest) {
+            Some(len) => len,
+            None => {
+                idx = start + 1;
+                continue;
+            }
+        };
+
+        let out = out.get_or_insert_with(|| String::with_capacity(s.len()));
+        out.push_str(&s[copied_up_to..start]);
+        out.push('§');
+
+        copied_up_to = start + 1 + len;
+        idx = copied_up_to;
+    }
+
+    match out {
+        Some(mut out) => {
+            out.push_str(&s[copied_up_to..]);
+            Cow::Owned(out)
+        }
+        None => Cow::Borrowed(s),
+    }
+}
+
+/// Get the length of the section sign escape sequence at the start of `s`
+/// (which follows a backslash), if there is one
+fn section_escape_len(s: &str) -> Option<usize> {
+    let (digits, len) = match s.chars().next()? {
+        'u' => ("00a7", 5),
+        'x' => ("a7", 3),
+        _ => return None,
+    };
+
+    s.get(1..len)
+        .filter(|d| d.eq_ignore_ascii_case(digits))
+        .map(|_| len)
+}
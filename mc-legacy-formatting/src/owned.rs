@@ -0,0 +1,289 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use crate::{Color, Span, SpanIter, Styles};
+
+/// Parse spans out of raw bytes, decoding them as UTF-8 lossily.
+///
+/// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`
+/// (the same way [`String::from_utf8_lossy`] does it) rather than causing an
+/// error, which is useful for text read straight off the network. Since the
+/// decoded text may not be borrowed from `bytes`, [`OwnedSpan`]s are yielded.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{span_iter_bytes, OwnedSpan, Color, Styles};
+///
+/// let bytes = b"\xc2\xa7aGreen \xff\xc2\xa7cred";
+///
+/// assert_eq!(
+///     span_iter_bytes(bytes).collect::<Vec<_>>(),
+///     vec![
+///         OwnedSpan::new_styled("Green \u{FFFD}", Color::Green, Styles::empty()),
+///         OwnedSpan::new_styled("red", Color::Red, Styles::empty()),
+///     ]
+/// );
+/// ```
+pub fn span_iter_bytes(bytes: &[u8]) -> impl Iterator<Item = OwnedSpan> {
+    let s = String::from_utf8_lossy(bytes);
+    // The decoded text might be owned by `s`, so the spans have to be copied
+    // out before it's dropped
+    SpanIter::new(&s)
+        .map(OwnedSpan::from)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// An owned version of [`Span`].
+///
+/// Useful when the text a span refers to doesn't outlive the span itself (for
+/// example, text that was pulled out of a JSON chat component).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanExt, OwnedSpan, Span, Color, Styles};
+///
+/// let owned: OwnedSpan = "§4dark red".span_iter().next().unwrap().into();
+///
+/// assert_eq!(owned, OwnedSpan::new_styled("dark red", Color::DarkRed, Styles::empty()));
+/// assert_eq!(owned.as_span(), Span::new_styled("dark red", Color::DarkRed, Styles::empty()));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum OwnedSpan {
+    /// A styled string of text
+    Styled {
+        /// The styled text
+        text: String,
+        /// The color of the text
+        color: Color,
+        /// Styles that should be applied to the text
+        styles: Styles,
+        /// The color behind the text, if it was given one
+        ///
+        /// See [`Span::background`] for more details.
+        background: Option<Color>,
+    },
+    /// An unbroken sequence of whitespace that was given the
+    /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH) style.
+    ///
+    /// See [`Span::StrikethroughWhitespace`] for more details.
+    StrikethroughWhitespace {
+        /// The styled whitespace
+        text: String,
+        /// The color of the whitespace (and therefore the line over it)
+        color: Color,
+        /// Styles applied to the whitespace (will contain at least
+        /// [`STRIKETHROUGH`](Styles::STRIKETHROUGH))
+        styles: Styles,
+    },
+    /// An unstyled string of text
+    ///
+    /// This should be given a default style. The vanilla client
+    /// would use [`Color::White`] and [`Styles::empty()`].
+    Plain(String),
+    /// A URL found in the text
+    ///
+    /// See [`Span::Url`] for more details.
+    Url {
+        /// The URL
+        text: String,
+        /// The color of the text the URL was found in
+        color: Color,
+        /// Styles applied to the text the URL was found in
+        styles: Styles,
+    },
+}
+
+impl OwnedSpan {
+    /// Create a new [`OwnedSpan::Plain`]
+    pub fn new_plain(s: impl Into<String>) -> Self {
+        OwnedSpan::Plain(s.into())
+    }
+
+    /// Create a new [`OwnedSpan::StrikethroughWhitespace`]
+    pub fn new_strikethrough_whitespace(
+        s: impl Into<String>,
+        color: Color,
+        styles: Styles,
+    ) -> Self {
+        OwnedSpan::StrikethroughWhitespace {
+            text: s.into(),
+            color,
+            styles,
+        }
+    }
+
+    /// Create a new [`OwnedSpan::Styled`] without a background
+    pub fn new_styled(s: impl Into<String>, color: Color, styles: Styles) -> Self {
+        OwnedSpan::Styled {
+            text: s.into(),
+            color,
+            styles,
+            background: None,
+        }
+    }
+
+    /// Create a new [`OwnedSpan::Url`]
+    pub fn new_url(s: impl Into<String>, color: Color, styles: Styles) -> Self {
+        OwnedSpan::Url {
+            text: s.into(),
+            color,
+            styles,
+        }
+    }
+
+    /// Borrow this [`OwnedSpan`] as a [`Span`]
+    pub fn as_span(&self) -> Span<'_> {
+        match self {
+            OwnedSpan::Styled {
+                text,
+                color,
+                styles,
+                background,
+            } => Span::Styled {
+                text,
+                color: *color,
+                styles: *styles,
+                background: *background,
+            },
+            OwnedSpan::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => Span::new_strikethrough_whitespace(text, *color, *styles),
+            OwnedSpan::Plain(text) => Span::new_plain(text),
+            OwnedSpan::Url {
+                text,
+                color,
+                styles,
+            } => Span::new_url(text, *color, *styles),
+        }
+    }
+}
+
+impl From<Span<'_>> for OwnedSpan {
+    fn from(s: Span<'_>) -> Self {
+        match s {
+            Span::Styled {
+                text,
+                color,
+                styles,
+                background,
+            } => OwnedSpan::Styled {
+                text: text.into(),
+                color,
+                styles,
+                background,
+            },
+            Span::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => OwnedSpan::new_strikethrough_whitespace(text, color, styles),
+            Span::Plain(text) => OwnedSpan::new_plain(text),
+            Span::Url {
+                text,
+                color,
+                styles,
+            } => OwnedSpan::new_url(text, color, styles),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedSpan> for Span<'a> {
+    fn from(s: &'a OwnedSpan) -> Self {
+        s.as_span()
+    }
+}
+
+impl Display for OwnedSpan {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        Display::fmt(&self.as_span(), f)
+    }
+}
+
+/// An iterator that owns its input string and yields [`OwnedSpan`]s from it.
+///
+/// Unlike [`SpanIter`], this doesn't borrow from anything, so it can be
+/// returned from a function that builds the input string itself (for example,
+/// out of a network response). Spans are parsed lazily, one at a time, the
+/// same way [`SpanIter`] parses them.
+///
+/// Created by [`SpanExt::into_span_iter`](crate::SpanExt::into_span_iter) or
+/// [`OwnedSpanIter::new`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanExt, OwnedSpan, OwnedSpanIter, Color, Styles};
+///
+/// fn greeting(name: &str) -> OwnedSpanIter {
+///     format!("§6Hello, §l{}", name).into_span_iter()
+/// }
+///
+/// assert_eq!(
+///     greeting("Steve").collect::<Vec<_>>(),
+///     vec![
+///         OwnedSpan::new_styled("Hello, ", Color::Gold, Styles::empty()),
+///         OwnedSpan::new_styled("Steve", Color::Gold, Styles::BOLD),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OwnedSpanIter {
+    buf: String,
+    /// The byte offset in `buf` that parsing picks up from
+    offset: usize,
+    start_char: char,
+    color: Color,
+    styles: Styles,
+}
+
+impl OwnedSpanIter {
+    /// Create a new [`OwnedSpanIter`] that takes ownership of `s`
+    pub fn new(s: impl Into<String>) -> Self {
+        Self {
+            buf: s.into(),
+            offset: 0,
+            start_char: '§',
+            color: Color::White,
+            styles: Styles::empty(),
+        }
+    }
+
+    /// Set the start character used while parsing
+    ///
+    /// See [`SpanIter::with_start_char`].
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.start_char = c;
+        self
+    }
+
+    /// Get the input string back, dropping the iterator
+    pub fn into_inner(self) -> String {
+        self.buf
+    }
+}
+
+impl Iterator for OwnedSpanIter {
+    type Item = OwnedSpan;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Every span ends either at the end of the input or right after a
+        // formatting code, so parsing can start over from where the last span
+        // left off as long as the formatting is carried over
+        let mut span_iter = SpanIter::new(&self.buf[self.offset..])
+            .with_start_char(self.start_char)
+            .with_initial_state(self.color, self.styles);
+        let span = span_iter.next().map(OwnedSpan::from);
+
+        self.offset += span_iter.pos;
+        self.color = span_iter.color;
+        self.styles = span_iter.styles;
+
+        span
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Span, SpanIter};
+
+/// Either a regular [`Span`] or a player count found in the text.
+///
+/// Yielded by [`PlayerCounts`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MotdSpan<'a> {
+    /// A regular span of text that isn't part of a player count
+    Span(Span<'a>),
+    /// A player count in the `[online / max]` format
+    PlayerCount {
+        /// The number of players online
+        online: u32,
+        /// The maximum number of players
+        max: u32,
+        /// The slice of the input string the count was found in, from the `[`
+        /// to the `]`
+        ///
+        /// This includes any formatting codes between the brackets, since the
+        /// count may be split across several spans with different formatting.
+        text: &'a str,
+    },
+}
+
+/// An iterator that recognizes player counts like `[116 / 1000]` in the text
+/// of [`Span`]s.
+///
+/// A player count is a `[`, a number, a `/`, and another number, followed by a
+/// `]`. Spaces are allowed around each of the numbers, and the count may be
+/// split across several spans (which is common in MOTDs, where the numbers are
+/// often colored differently from the brackets). Anything else is yielded as
+/// a regular [`Span`], and spans are split around the counts that are found.
+///
+/// Created by [`SpanIter::player_counts`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanExt, MotdSpan, Span, Color, Styles};
+///
+/// let mut player_counts = "§7Players: §8[§a12 §7/ §c100§8]".span_iter().player_counts();
+///
+/// assert_eq!(
+///     player_counts.next().unwrap(),
+///     MotdSpan::Span(Span::new_styled("Players: ", Color::Gray, Styles::empty()))
+/// );
+/// assert_eq!(
+///     player_counts.next().unwrap(),
+///     MotdSpan::PlayerCount { online: 12, max: 100, text: "[§a12 §7/ §c100§8]" }
+/// );
+/// assert!(player_counts.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlayerCounts<'a> {
+    span_iter: SpanIter<'a>,
+    /// The rest of a span that was split around a player count, which still
+    /// needs to be yielded
+    pending: Option<Span<'a>>,
+}
+
+impl<'a> PlayerCounts<'a> {
+    pub(crate) fn new(span_iter: SpanIter<'a>) -> Self {
+        Self {
+            span_iter,
+            pending: None,
+        }
+    }
+
+    /// Try to match a player count starting at the `[` at `start` in the text
+    /// of `span`
+    ///
+    /// On a match, returns the count along with the rest of the span it ended
+    /// in and the state of the parser after that span.
+    fn match_count(
+        &self,
+        span: Span<'a>,
+        start: usize,
+    ) -> Option<(MotdSpan<'a>, Span<'a>, SpanIter<'a>)> {
+        let buf = self.span_iter.buf;
+        let offset = |s: &str| s.as_ptr() as usize - buf.as_ptr() as usize;
+
+        let count_start = offset(&span.text()[start..]);
+        let mut span_iter = self.span_iter.clone();
+        let mut matcher = Matcher::Open;
+        let mut current = span;
+        let mut text = &span.text()[start + 1..];
+
+        loop {
+            for (idx, c) in text.char_indices() {
+                matcher = matcher.feed(c)?;
+
+                if let Matcher::Done { online, max } = matcher {
+                    let rest = &text[idx + 1..];
+                    let count = MotdSpan::PlayerCount {
+                        online,
+                        max,
+                        text: &buf[count_start..offset(rest)],
+                    };
+
+                    return Some((count, current.with_text(rest), span_iter));
+                }
+            }
+
+            current = span_iter.next()?;
+            text = current.text();
+        }
+    }
+}
+
+impl<'a> Iterator for PlayerCounts<'a> {
+    type Item = MotdSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = match self.pending.take() {
+            Some(span) => span,
+            None => self.span_iter.next()?,
+        };
+        let text = span.text();
+
+        for (start, _) in text.match_indices('[') {
+            if let Some((count, rest, span_iter)) = self.match_count(span, start) {
+                // Yield the text before the count first, and come back to the
+                // count on the next call
+                if start > 0 {
+                    self.pending = Some(span.with_text(&text[start..]));
+                    return Some(MotdSpan::Span(span.with_text(&text[..start])));
+                }
+
+                self.span_iter = span_iter;
+                if !rest.is_empty() {
+                    self.pending = Some(rest);
+                }
+
+                return Some(count);
+            }
+        }
+
+        Some(MotdSpan::Span(span))
+    }
+}
+
+/// The state of matching a player count, after the opening `[`
+#[derive(Debug, Copy, Clone)]
+enum Matcher {
+    Open,
+    Online(u32),
+    AfterOnline(u32),
+    Slash(u32),
+    Max(u32, u32),
+    AfterMax(u32, u32),
+    Done { online: u32, max: u32 },
+}
+
+impl Matcher {
+    /// Advance the matcher by one char, returning `None` if the text can't be
+    /// a player count
+    fn feed(self, c: char) -> Option<Self> {
+        let digit = c.to_digit(10);
+        let push_digit = |n: u32| n.checked_mul(10)?.checked_add(digit?);
+
+        Some(match (self, c) {
+            (Matcher::Open, ' ') => Matcher::Open,
+            (Matcher::Open, _) => Matcher::Online(digit?),
+            (Matcher::Online(online), ' ') => Matcher::AfterOnline(online),
+            (Matcher::Online(online) | Matcher::AfterOnline(online), '/') => Matcher::Slash(online),
+            (Matcher::Online(online), _) => Matcher::Online(push_digit(online)?),
+            (Matcher::AfterOnline(online), ' ') => Matcher::AfterOnline(online),
+            (Matcher::Slash(online), ' ') => Matcher::Slash(online),
+            (Matcher::Slash(online), _) => Matcher::Max(online, digit?),
+            (Matcher::Max(online, max), ' ') => Matcher::AfterMax(online, max),
+            (Matcher::Max(online, max) | Matcher::AfterMax(online, max), ']') => {
+                Matcher::Done { online, max }
+            }
+            (Matcher::Max(online, max), _) => Matcher::Max(online, push_digit(max)?),
+            (Matcher::AfterMax(online, max), ' ') => Matcher::AfterMax(online, max),
+            _ => return None,
+        })
+    }
+}
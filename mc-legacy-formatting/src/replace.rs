@@ -0,0 +1,78 @@
+use alloc::string::String;
+
+use crate::{find_all_visible, LegacyStringBuilder, SpanIter};
+
+/// Replace every place `needle` shows up in the visible text of `s` with
+/// `replacement`, keeping the formatting of the rest of the string.
+///
+/// Matches are found with [`find_all_visible`], so a needle can match text
+/// that's split across differently-formatted spans. Like [`str::replace`],
+/// matches are found from left to right and don't overlap: where two could
+/// overlap, only the one that starts first is replaced.
+///
+/// The replacement gets the formatting of the first matched char, and any
+/// codes in it are applied on top of that. The formatting codes between the
+/// matched chars are removed along with them, and the text after a match gets
+/// whatever codes are needed to switch back to its original formatting. Codes
+/// that don't affect any text are dropped, the same way
+/// [`minify`](crate::minify) drops them.
+///
+/// `s` is parsed with `§` as the start char, and start chars in the text
+/// aren't escaped. An empty `needle` never matches.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::replace_visible;
+///
+/// assert_eq!(replace_visible("§aSte§bve §fjoined", "Steve", "Alex"), "§aAlex§b §fjoined");
+/// assert_eq!(replace_visible("§cdarn §lit", "darn", "****"), "§c**** §lit");
+/// ```
+pub fn replace_visible(s: &str, needle: &str, replacement: &str) -> String {
+    let mut builder = LegacyStringBuilder::new().with_escapes(false);
+    let mut matches = find_all_visible(s, needle).map(|m| m.chars).peekable();
+    let mut pos = 0;
+
+    for (codes, span) in SpanIter::new(s).with_codes() {
+        let text = span.text();
+        let end = pos + text.chars().count();
+        let mut codes = Some(codes);
+        let mut at = pos;
+
+        let mut push_piece = |builder: &mut LegacyStringBuilder, from: usize, to: usize| {
+            let piece = &text[byte_offset(text, from - pos)..byte_offset(text, to - pos)];
+            match codes.take() {
+                Some(codes) => builder.push_with_codes(codes, span.with_text(piece)),
+                None => builder.push(span.with_text(piece)),
+            }
+        };
+
+        while let Some(m) = matches.peek().filter(|m| m.start < end).cloned() {
+            if m.start >= pos {
+                push_piece(&mut builder, at, m.start);
+
+                SpanIter::new(replacement)
+                    .with_initial_state(span.color(), span.styles())
+                    .for_each(|span| builder.push(span));
+            }
+
+            at = m.end.min(end);
+            if m.end > end {
+                break;
+            }
+            matches.next();
+        }
+
+        push_piece(&mut builder, at, end);
+        pos = end;
+    }
+
+    builder.finish()
+}
+
+/// Get the byte offset of the `n`th char of `text`
+fn byte_offset(text: &str, n: usize) -> usize {
+    text.char_indices()
+        .nth(n)
+        .map_or(text.len(), |(idx, _)| idx)
+}
@@ -0,0 +1,475 @@
+use alloc::{borrow::Cow, string::String};
+
+use crate::{
+    apply_code, code_ranges, legacy_display::Encoder, CodeCase, CodeKind, Color, Span, SpanIter,
+    Styles,
+};
+
+/// Turn `spans` back into a string with legacy formatting codes, using
+/// `start_char` as the start char.
+///
+/// Codes are only emitted when the formatting changes from one span to the
+/// next, and only the codes necessary to make that change are used. See
+/// [`LegacyStringBuilder`] for the details.
+///
+/// Any occurrence of `start_char` in the text of a span is escaped by doubling
+/// it, so a lossless round-trip requires parsing the output with
+/// [`SpanIter::with_doubled_escape`](crate::SpanIter::with_doubled_escape)
+/// enabled.
+///
+/// `spans` can be [`Span`]s, [`CowSpan`](crate::CowSpan)s, or
+/// [`OwnedSpan`](crate::OwnedSpan)s.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{to_legacy_string, SpanExt, Span, Color, Styles};
+///
+/// let spans = [
+///     Span::new_styled("Tom ", Color::Gold, Styles::BOLD),
+///     Span::new_styled("& Jerry", Color::Gold, Styles::empty()),
+/// ];
+/// let s = to_legacy_string(&spans, '&');
+///
+/// assert_eq!(s, "&6&lTom &6&& Jerry");
+/// assert_eq!(
+///     s.span_iter().with_start_char('&').with_doubled_escape(true).collect::<Vec<_>>(),
+///     vec![
+///         Span::new_styled("Tom ", Color::Gold, Styles::BOLD),
+///         Span::new_styled("& Jerry", Color::Gold, Styles::empty()),
+///     ]
+/// );
+/// ```
+pub fn to_legacy_string<'a, S>(spans: &'a [S], start_char: char) -> String
+where
+    &'a S: Into<Span<'a>>,
+{
+    let mut builder = LegacyStringBuilder::new().with_start_char(start_char);
+    spans.iter().for_each(|span| builder.push(span.into()));
+    builder.finish()
+}
+
+/// Prefix `text` with the codes to give it `color` and `styles`, using
+/// `start_char` as the start char.
+///
+/// The color code always comes first (even for [`Color::White`], which gets
+/// `f` rather than the reset code), followed by the code for each style, since
+/// a color code clears any styles that come before it. This makes the result
+/// safe to paste after other formatted text. [`Color::Rgb`] is written in the
+/// `§x§r§r§g§g§b§b` format, the same way [`LegacyStringBuilder`] writes it.
+///
+/// `text` is copied as it is; use [`escape`] first if it might contain
+/// `start_char`.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{code_str, Color, Styles};
+///
+/// assert_eq!(code_str("text", Color::Red, Styles::BOLD, '§'), "§c§ltext");
+/// assert_eq!(
+///     code_str("Hi", Color::White, Styles::BOLD | Styles::ITALIC, '&'),
+///     "&f&l&oHi"
+/// );
+/// ```
+pub fn code_str(text: &str, color: Color, styles: Styles, start_char: char) -> String {
+    let mut encoder = Encoder::new(start_char);
+    let mut out = String::with_capacity(text.len() + 8);
+
+    // Writing to a `String` can't fail
+    let _ = encoder.write_all_codes(&mut out, color, styles);
+    out.push_str(text);
+    out
+}
+
+/// Remove formatting codes from `s` that don't have any visible effect.
+///
+/// This drops duplicate codes, codes that are immediately overridden (such as a
+/// style followed by a color, which resets it), and codes at the end of the
+/// string that aren't followed by any text, leaving the minimal codes needed to
+/// format the text the same way (see [`LegacyStringBuilder`]). The parsed text
+/// and its formatting are unchanged, although adjacent spans with the same
+/// formatting may be merged into one.
+///
+/// Start chars in the text (which aren't part of valid codes) are left as they
+/// are rather than being escaped. Codes are written in lowercase; see
+/// [`minify_with_code_case`] to choose the case.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::minify;
+///
+/// assert_eq!(minify("§f§f §7§l§6§lGold §l§cred§a§b"), " §6§lGold §cred");
+/// ```
+pub fn minify(s: &str) -> String {
+    minify_with_code_case(s, CodeCase::Lower)
+}
+
+/// Remove formatting codes from `s` that don't have any visible effect,
+/// writing the codes that are left in `case`.
+///
+/// See [`minify`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{minify_with_code_case, CodeCase};
+///
+/// assert_eq!(minify_with_code_case("§c§LBig §lred", CodeCase::Upper), "§C§LBig red");
+/// ```
+pub fn minify_with_code_case(s: &str, case: CodeCase) -> String {
+    let mut builder = LegacyStringBuilder::new()
+        .with_escapes(false)
+        .with_code_case(case);
+    SpanIter::new(s).for_each(|span| builder.push(span));
+    builder.finish()
+}
+
+/// Escape every occurrence of `start_char` in `s` so that none of them are
+/// interpreted as formatting codes.
+///
+/// Start chars are escaped by doubling them, the same way
+/// [`LegacyStringBuilder`] escapes span text, so the output should be parsed
+/// with [`SpanIter::with_doubled_escape`](crate::SpanIter::with_doubled_escape)
+/// enabled to get `s` back as literal text. This is useful for embedding
+/// untrusted input (such as player names) in legacy text.
+///
+/// `s` is returned as it is if it doesn't contain `start_char`.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{escape, SpanExt};
+///
+/// let name = escape("§kBob", '§');
+/// assert_eq!(name, "§§kBob");
+///
+/// let s = format!("§6Welcome, §r{}§6!", name);
+/// let text: String = s
+///     .span_iter()
+///     .with_doubled_escape(true)
+///     .map(|span| span.text())
+///     .collect();
+///
+/// assert_eq!(text, "Welcome, §kBob!");
+/// ```
+pub fn escape(s: &str, start_char: char) -> Cow<'_, str> {
+    if !s.contains(start_char) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut out = String::with_capacity(s.len() + start_char.len_utf8());
+    for c in s.chars() {
+        if c == start_char {
+            out.push(c);
+        }
+        out.push(c);
+    }
+
+    Cow::Owned(out)
+}
+
+/// Rewrite the formatting codes in `s` to use the start char `to` instead of
+/// `from`.
+///
+/// Only start chars that are actually followed by a valid code char (exactly the
+/// codes [`SpanIter`] would recognize) are rewritten, so literal start chars in
+/// the text are left alone. The code chars themselves are kept as they were
+/// written.
+///
+/// Occurrences of `to` that are already in the text are copied as they are,
+/// which means they could be parsed as codes in the output. If `s` might
+/// contain `to`, use [`translate_start_char_escaped`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::translate_start_char;
+///
+/// assert_eq!(
+///     translate_start_char("&6It's a lot easier to type &b& &6than &b§", '&', '§'),
+///     "§6It's a lot easier to type §b& §6than §b§"
+/// );
+/// assert_eq!(translate_start_char("Tom & Jerry", '&', '§'), "Tom & Jerry");
+/// ```
+pub fn translate_start_char(s: &str, from: char, to: char) -> String {
+    translate(s, from, to, false)
+}
+
+/// Rewrite the formatting codes in `s` to use the start char `to` instead of
+/// `from`, escaping occurrences of `to` in the text.
+///
+/// This works the same way as [`translate_start_char`], except that any `to`
+/// char that's part of the text is escaped by doubling it. Parsing the output
+/// with `to` as the start char and
+/// [`SpanIter::with_doubled_escape`](crate::SpanIter::with_doubled_escape)
+/// enabled gives the same spans as parsing `s` with `from` as the start char,
+/// even if `s` contained text that would look like a code after translating it.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{translate_start_char, translate_start_char_escaped};
+///
+/// // `&5` isn't a code in the original, but becomes one after a naive translation
+/// assert_eq!(translate_start_char("§aOnly &5 each", '§', '&'), "&aOnly &5 each");
+/// assert_eq!(translate_start_char_escaped("§aOnly &5 each", '§', '&'), "&aOnly &&5 each");
+/// ```
+pub fn translate_start_char_escaped(s: &str, from: char, to: char) -> String {
+    translate(s, from, to, true)
+}
+
+fn translate(s: &str, from: char, to: char, escape: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    let push_text = |out: &mut String, text: &str| {
+        for c in text.chars() {
+            if escape && c == to {
+                out.push(c);
+            }
+            out.push(c);
+        }
+    };
+    let mut text_start = 0;
+
+    for (range, kind) in code_ranges(s, from) {
+        if kind == CodeKind::Invalid {
+            continue;
+        }
+
+        push_text(&mut out, &s[text_start..range.start]);
+        out.push(to);
+        out.push_str(&s[range.start + from.len_utf8()..range.end]);
+        text_start = range.end;
+    }
+
+    push_text(&mut out, &s[text_start..]);
+    out
+}
+
+/// Builds up a string with legacy formatting codes out of [`Span`]s.
+///
+/// The builder keeps track of the formatting in effect at the end of the string
+/// so far, and only emits the codes needed to get from it to the formatting of
+/// each new span:
+///
+/// * Styles are added with style codes if none of the current styles need to
+///   be removed and the color doesn't change
+/// * Otherwise a color code is emitted (which clears all styles) followed by
+///   the codes for each style, or the reset code if the new formatting is
+///   [`Color::White`] with no styles
+///
+/// [`Color::Rgb`] is written in the `§x§r§r§g§g§b§b` format used by Spigot and
+/// BungeeCord. Spans without any text don't emit anything, so the string never
+/// ends with codes that don't affect any text.
+///
+/// Literal start chars in span text are doubled; see [`to_legacy_string`].
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{LegacyStringBuilder, Span, Color, Styles};
+///
+/// let mut builder = LegacyStringBuilder::new();
+/// builder.push(Span::new_styled("Red ", Color::Red, Styles::empty()));
+/// builder.push(Span::new_styled("and bold ", Color::Red, Styles::BOLD));
+/// builder.push(Span::new_plain("and plain"));
+///
+/// assert_eq!(builder.finish(), "§cRed §land bold §rand plain");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LegacyStringBuilder {
+    out: String,
+    encoder: Encoder,
+    /// The number of chars of span text in `out`
+    visible_len: usize,
+}
+
+impl Default for LegacyStringBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LegacyStringBuilder {
+    /// Create a new, empty [`LegacyStringBuilder`] that uses `§` as the start
+    /// char
+    ///
+    /// This is the same as [`LegacyStringBuilder::default`].
+    pub fn new() -> Self {
+        Self {
+            out: String::new(),
+            encoder: Encoder::new('§'),
+            visible_len: 0,
+        }
+    }
+
+    /// Set the start char used for codes (and escaped in span text)
+    ///
+    /// This should be set before anything is pushed, since codes that were
+    /// already written aren't changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{LegacyStringBuilder, Span, Color, Styles};
+    ///
+    /// let mut builder = LegacyStringBuilder::new().with_start_char('&');
+    /// builder.push(Span::new_styled("Tom & Jerry", Color::Gold, Styles::empty()));
+    ///
+    /// assert_eq!(builder.finish(), "&6Tom && Jerry");
+    /// ```
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.encoder.start_char = c;
+        self
+    }
+
+    /// Set whether literal start chars in span text are escaped by doubling
+    /// them (the default)
+    ///
+    /// Turning this off is useful for re-serializing text that was parsed
+    /// without [`SpanIter::with_doubled_escape`](crate::SpanIter::with_doubled_escape),
+    /// where start chars that aren't part of a valid code are already
+    /// displayed as text.
+    pub fn with_escapes(mut self, escape: bool) -> Self {
+        self.encoder.escape = escape;
+        self
+    }
+
+    /// Set whether codes are written with lowercase (the default) or
+    /// uppercase chars
+    ///
+    /// This doesn't affect the codes given to
+    /// [`LegacyStringBuilder::push_with_codes`], which are always written as
+    /// they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{CodeCase, LegacyStringBuilder, Span, Color, Styles};
+    ///
+    /// let mut builder = LegacyStringBuilder::new().with_code_case(CodeCase::Upper);
+    /// builder.push(Span::new_styled("Aqua", Color::Aqua, Styles::BOLD));
+    /// builder.push(Span::new_plain(" text"));
+    ///
+    /// assert_eq!(builder.finish(), "§B§LAqua§R text");
+    /// ```
+    pub fn with_code_case(mut self, case: CodeCase) -> Self {
+        self.encoder.case = case;
+        self
+    }
+
+    /// Append the text of `span` to the string, preceded by `codes` as they
+    /// were written rather than newly generated codes
+    ///
+    /// This is meant to be used with
+    /// [`SpanIter::with_codes`](crate::SpanIter::with_codes) to re-serialize
+    /// parsed text while keeping the original casing and choice of codes. If
+    /// `codes` isn't made up entirely of valid codes, or wouldn't give `span`
+    /// the right formatting coming after the string built so far (for example,
+    /// because the span's formatting was changed after parsing), this falls
+    /// back to [`LegacyStringBuilder::push`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{LegacyStringBuilder, SpanExt, Span, Color, Styles};
+    ///
+    /// let mut builder = LegacyStringBuilder::new().with_escapes(false);
+    ///
+    /// for (codes, span) in "§D§LHello §Rworld".span_iter().with_codes() {
+    ///     let span = match span {
+    ///         Span::Plain("world") => Span::new_plain("there"),
+    ///         Span::Plain(_) => Span::new_styled("hi", Color::Red, Styles::empty()),
+    ///         span => span,
+    ///     };
+    ///     builder.push_with_codes(codes, span);
+    /// }
+    ///
+    /// assert_eq!(builder.finish(), "§D§LHello §Rthere");
+    /// ```
+    pub fn push_with_codes(&mut self, codes: &str, span: Span<'_>) {
+        if span.is_empty() {
+            return;
+        }
+
+        let (mut color, mut styles) = (self.encoder.color, self.encoder.styles);
+        let mut chars = codes.chars();
+        let mut valid = true;
+
+        while let Some(c) = chars.next() {
+            valid &= c == self.encoder.start_char
+                && chars
+                    .next()
+                    .map_or(false, |code| apply_code(code, &mut color, &mut styles));
+        }
+
+        if !valid || (color, styles) != (span.color(), span.styles()) {
+            self.push(span);
+            return;
+        }
+
+        self.out.push_str(codes);
+        self.encoder.color = color;
+        self.encoder.styles = styles;
+        self.push_text(span.text());
+    }
+
+    /// Append the text of `span` to the string, preceded by whatever codes are
+    /// needed to give it the right formatting
+    pub fn push(&mut self, span: Span<'_>) {
+        let text = span.text();
+
+        if text.is_empty() {
+            return;
+        }
+
+        // Writing to a `String` can't fail
+        let _ = self
+            .encoder
+            .write_codes(&mut self.out, span.color(), span.styles());
+        self.push_text(text);
+    }
+
+    /// Whether nothing has been written to the string yet
+    ///
+    /// Since spans without any text don't emit anything (not even codes), this
+    /// is the same as checking whether any text has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.out.is_empty()
+    }
+
+    /// Get the number of chars of text in the string so far
+    ///
+    /// Formatting codes and the chars added to escape start chars aren't
+    /// counted, so this is the number of chars that would be displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{LegacyStringBuilder, Span, Color, Styles};
+    ///
+    /// let mut builder = LegacyStringBuilder::new();
+    /// assert!(builder.is_empty());
+    ///
+    /// builder.push(Span::new_styled("§ sign", Color::Red, Styles::BOLD));
+    ///
+    /// assert!(!builder.is_empty());
+    /// assert_eq!(builder.len_visible(), 6);
+    /// assert_eq!(builder.finish(), "§c§l§§ sign");
+    /// ```
+    pub fn len_visible(&self) -> usize {
+        self.visible_len
+    }
+
+    /// Get the built string
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.visible_len += text.chars().count();
+        // Writing to a `String` can't fail
+        let _ = self.encoder.write_text(&mut self.out, text);
+    }
+}
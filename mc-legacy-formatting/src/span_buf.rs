@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+use core::{mem, mem::ManuallyDrop, ops::Deref};
+
+use crate::{Span, SpanIter};
+
+/// A reusable buffer for parsing many strings without allocating for each one.
+///
+/// Collecting every string's spans into a new [`Vec`] shows up in profiles
+/// when parsing a lot of text (such as the MOTDs of hundreds of servers).
+/// [`SpanBuf::parse_into`] reuses the buffer's allocation instead, so once
+/// it's grown large enough for the longest string, parsing doesn't allocate at
+/// all.
+///
+/// The buffer doesn't borrow from the strings parsed into it, so they only
+/// have to live as long as the [`ParsedSpans`] that [`SpanBuf::parse_into`]
+/// returns, which hands the allocation back to the buffer when it's dropped.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanBuf, Span, Color, Styles};
+///
+/// let mut buf = SpanBuf::new();
+///
+/// let mut counts = Vec::new();
+/// for motd in ["§6Gold server", "§cRed §lserver"] {
+///     let motd = motd.to_string();
+///     counts.push(buf.parse_into(&motd).len());
+/// }
+///
+/// assert_eq!(counts, vec![1, 2]);
+/// assert_eq!(&*buf.parse_into("plain"), &[Span::new_plain("plain")]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpanBuf {
+    /// Always empty; only the allocation is kept between parses
+    spans: Vec<Span<'static>>,
+}
+
+impl SpanBuf {
+    /// Create a new, empty [`SpanBuf`]
+    ///
+    /// This doesn't allocate until something is parsed into it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`SpanBuf`] with room for at least `capacity` spans
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            spans: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Parse `s` with the default [`SpanIter`] settings into the buffer
+    pub fn parse_into<'b, 'a>(&'b mut self, s: &'a str) -> ParsedSpans<'b, 'a> {
+        self.collect_into(SpanIter::new(s))
+    }
+
+    /// Collect `spans` into the buffer
+    ///
+    /// This is for when the parser needs different settings than
+    /// [`SpanBuf::parse_into`] uses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mc_legacy_formatting::{SpanBuf, SpanExt, Span, Color, Styles};
+    ///
+    /// let mut buf = SpanBuf::new();
+    /// let spans = buf.collect_into("&6Gold".span_iter().with_start_char('&'));
+    ///
+    /// assert_eq!(&*spans, &[Span::new_styled("Gold", Color::Gold, Styles::empty())]);
+    /// ```
+    pub fn collect_into<'b, 'a>(
+        &'b mut self,
+        spans: impl IntoIterator<Item = Span<'a>>,
+    ) -> ParsedSpans<'b, 'a> {
+        let mut recycled = recycle(mem::take(&mut self.spans));
+        recycled.extend(spans);
+
+        ParsedSpans {
+            buf: &mut self.spans,
+            spans: recycled,
+        }
+    }
+
+    /// Get the number of spans the buffer can hold without allocating
+    pub fn capacity(&self) -> usize {
+        self.spans.capacity()
+    }
+}
+
+/// The spans parsed into a [`SpanBuf`], which derefs to a slice of them.
+///
+/// Created by [`SpanBuf::parse_into`] and [`SpanBuf::collect_into`]. The
+/// allocation is handed back to the [`SpanBuf`] when this is dropped.
+#[derive(Debug)]
+pub struct ParsedSpans<'b, 'a> {
+    buf: &'b mut Vec<Span<'static>>,
+    spans: Vec<Span<'a>>,
+}
+
+impl<'a> Deref for ParsedSpans<'_, 'a> {
+    type Target = [Span<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.spans
+    }
+}
+
+impl Drop for ParsedSpans<'_, '_> {
+    fn drop(&mut self) {
+        *self.buf = recycle(mem::take(&mut self.spans));
+    }
+}
+
+/// Empty `spans`, keeping its allocation for spans with a different lifetime
+fn recycle<'a, 'b>(mut spans: Vec<Span<'a>>) -> Vec<Span<'b>> {
+    spans.clear();
+    let mut spans = ManuallyDrop::new(spans);
+    let capacity = spans.capacity();
+
+    // SAFETY: `Span<'a>` and `Span<'b>` only differ in their lifetime, so they
+    // have the same size and alignment, and the allocation was made by a `Vec`
+    // with that layout and `capacity`. The length is zero, so no `Span<'a>` is
+    // ever read as a `Span<'b>`, and the old `Vec` is never dropped, so the
+    // allocation has a single owner.
+    unsafe { Vec::from_raw_parts(spans.as_mut_ptr().cast::<Span<'b>>(), 0, capacity) }
+}
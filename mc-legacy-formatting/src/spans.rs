@@ -0,0 +1,117 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::{self, Display};
+
+use crate::Span;
+
+/// A lightweight view over spans that have already been collected.
+///
+/// This is an [`ExactSizeIterator`] over the spans in a slice (so it's cheap to
+/// clone and iterate again without re-parsing), and has methods for the
+/// operations that work on a whole list of spans at once.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanExt, Span, Spans};
+///
+/// let collected: Vec<Span> = "§6Gold §land bold".span_iter().collect();
+/// let spans = Spans::new(&collected);
+///
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!(spans.to_plain_string(), "Gold and bold");
+/// assert_eq!(spans.to_legacy_string('&'), "&6Gold &land bold");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Spans<'a>(&'a [Span<'a>]);
+
+impl<'a> Spans<'a> {
+    /// Create a new [`Spans`] view over `spans`
+    pub fn new(spans: &'a [Span<'a>]) -> Self {
+        Self(spans)
+    }
+
+    /// Get the spans that haven't been iterated over yet
+    pub fn as_slice(&self) -> &'a [Span<'a>] {
+        self.0
+    }
+
+    /// Get the combined display width of the spans
+    ///
+    /// See [`Span::display_width`] for how the width of each span is counted.
+    pub fn display_width(&self, tab_width: u8) -> usize {
+        self.0
+            .iter()
+            .map(|span| span.display_width(tab_width))
+            .sum()
+    }
+
+    /// Concatenate the text of the spans, dropping all formatting
+    #[cfg(feature = "alloc")]
+    pub fn to_plain_string(&self) -> String {
+        self.0.iter().map(Span::text).collect()
+    }
+
+    /// Turn the spans back into a string with legacy formatting codes
+    ///
+    /// See [`to_legacy_string`](crate::to_legacy_string) for the details.
+    #[cfg(feature = "alloc")]
+    pub fn to_legacy_string(&self, start_char: char) -> String {
+        crate::to_legacy_string(self.0, start_char)
+    }
+}
+
+impl<'a> From<&'a [Span<'a>]> for Spans<'a> {
+    fn from(spans: &'a [Span<'a>]) -> Self {
+        Self::new(spans)
+    }
+}
+
+impl<'a> Iterator for Spans<'a> {
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, rest) = self.0.split_first()?;
+        self.0 = rest;
+        Some(*first)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl ExactSizeIterator for Spans<'_> {}
+
+/// Displays every span yielded by an iterator one after the other, without
+/// any colors or styles.
+///
+/// Each span is written with its own [`Display`] impl (so strikethrough
+/// whitespace is drawn as dashes), which means a whole message can be printed
+/// in one go without looping or allocating. The iterator is cloned every time
+/// it's displayed.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{DisplaySpans, SpanExt};
+///
+/// let motd = "§a§lHypixel §7§m  §r [1.8-1.16]";
+///
+/// assert_eq!(DisplaySpans::new(motd.span_iter()).to_string(), "Hypixel -- [1.8-1.16]");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisplaySpans<I>(I);
+
+impl<I> DisplaySpans<I> {
+    /// Create a new [`DisplaySpans`] for the spans yielded by `spans`
+    pub fn new(spans: I) -> Self {
+        Self(spans)
+    }
+}
+
+impl<'a, I: Iterator<Item = Span<'a>> + Clone> Display for DisplaySpans<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.clone().try_for_each(|span| Display::fmt(&span, f))
+    }
+}
@@ -0,0 +1,217 @@
+use core::str;
+
+use crate::{code_ranges, CodeKind, Color, Span, SpanIter, Styles};
+
+/// A push-style parser for text that arrives in chunks (for example, straight
+/// out of network reads).
+///
+/// Each chunk is parsed as soon as it's pushed, and the formatting state is
+/// carried over into the next one. A start char at the very end of a chunk is
+/// held back until the next chunk shows whether it begins a code, and (with
+/// [`StreamParser::push_bytes`]) so are the bytes of a char that's split
+/// across chunks. The whole message never has to be buffered.
+///
+/// Spans are handed to a sink closure rather than returned, since they may
+/// borrow from the chunk that was just pushed (or, for a held back start char,
+/// from the parser itself). A span that would cross a chunk boundary when
+/// parsing the whole message at once is delivered in pieces, but the text and
+/// formatting are otherwise exactly the same as what [`SpanIter`] gives for the
+/// whole message.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{StreamParser, Color, Styles};
+///
+/// let mut parser = StreamParser::new();
+/// let mut texts = Vec::new();
+///
+/// for chunk in ["§6Go", "ld §", "lbold"] {
+///     parser.push_str(chunk, |span| texts.push((span.text().to_string(), span.color(), span.styles())));
+/// }
+/// parser.finish(|span| texts.push((span.text().to_string(), span.color(), span.styles())));
+///
+/// assert_eq!(
+///     texts,
+///     vec![
+///         ("Go".to_string(), Color::Gold, Styles::empty()),
+///         ("ld ".to_string(), Color::Gold, Styles::empty()),
+///         ("bold".to_string(), Color::Gold, Styles::BOLD),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamParser {
+    start_char: char,
+    /// Whether the last chunk ended with a start char that still needs to be
+    /// paired up with the first char of the next chunk
+    pending_start_char: bool,
+    /// The bytes of an incomplete UTF-8 sequence at the end of the last chunk
+    /// pushed with `push_bytes`
+    partial: [u8; 4],
+    partial_len: usize,
+    color: Color,
+    styles: Styles,
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamParser {
+    /// Create a new [`StreamParser`] that uses `§` as the start char
+    pub fn new() -> Self {
+        Self {
+            start_char: '§',
+            pending_start_char: false,
+            partial: [0; 4],
+            partial_len: 0,
+            color: Color::White,
+            styles: Styles::empty(),
+        }
+    }
+
+    /// Set the start character used while parsing
+    pub fn with_start_char(mut self, c: char) -> Self {
+        self.start_char = c;
+        self
+    }
+
+    /// Parse the next chunk of text, passing the [`Span`]s in it to `sink`
+    pub fn push_str(&mut self, chunk: &str, mut sink: impl FnMut(Span<'_>)) {
+        let mut chunk = chunk;
+
+        if self.pending_start_char {
+            let c = match chunk.chars().next() {
+                Some(c) => c,
+                None => return,
+            };
+            self.pending_start_char = false;
+            chunk = &chunk[c.len_utf8()..];
+
+            if !self.apply_code(c) {
+                // Not a valid code, so the pair is displayed as text
+                let mut buf = [0; 8];
+                let len = self.start_char.encode_utf8(&mut buf).len();
+                let len = len + c.encode_utf8(&mut buf[len..]).len();
+                // Both parts were encoded from chars
+                let text = str::from_utf8(&buf[..len]).unwrap();
+
+                sink(Span::from_formatting(text, self.color, self.styles));
+            }
+        }
+
+        // A start char at the end of the chunk that isn't paired with anything
+        // might begin a code that continues in the next chunk
+        if let Some((range, CodeKind::Invalid)) = code_ranges(chunk, self.start_char).last() {
+            if range.len() == self.start_char.len_utf8() {
+                self.pending_start_char = true;
+                chunk = &chunk[..range.start];
+            }
+        }
+
+        let mut span_iter = SpanIter::new(chunk)
+            .with_start_char(self.start_char)
+            .with_initial_state(self.color, self.styles);
+        span_iter.by_ref().for_each(&mut sink);
+
+        self.color = span_iter.color;
+        self.styles = span_iter.styles;
+    }
+
+    /// Parse the next chunk of raw bytes, decoding them as UTF-8 lossily and
+    /// passing the [`Span`]s in it to `sink`
+    ///
+    /// A char may be split across chunks. Invalid UTF-8 sequences are replaced
+    /// with `U+FFFD REPLACEMENT CHARACTER` the same way
+    /// [`span_iter_bytes`](crate::span_iter_bytes) does it.
+    pub fn push_bytes(&mut self, chunk: &[u8], mut sink: impl FnMut(Span<'_>)) {
+        let mut chunk = chunk;
+
+        if self.partial_len > 0 && !chunk.is_empty() {
+            let mut buf = self.partial;
+            let mut len = self.partial_len;
+            self.partial_len = 0;
+
+            // Complete the sequence one byte at a time
+            for (idx, &b) in chunk.iter().enumerate() {
+                buf[len] = b;
+                len += 1;
+
+                match str::from_utf8(&buf[..len]) {
+                    Ok(s) => {
+                        self.push_str(s, &mut sink);
+                        chunk = &chunk[idx + 1..];
+                        break;
+                    }
+                    Err(e) if e.error_len().is_some() => {
+                        // The byte that was just added doesn't continue the
+                        // sequence, so it's parsed again along with the rest of
+                        // the chunk
+                        self.push_str(REPLACEMENT, &mut sink);
+                        chunk = &chunk[idx..];
+                        break;
+                    }
+                    Err(_) if idx + 1 == chunk.len() => {
+                        self.partial = buf;
+                        self.partial_len = len;
+                        return;
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+
+        loop {
+            match str::from_utf8(chunk) {
+                Ok(s) => {
+                    self.push_str(s, &mut sink);
+                    return;
+                }
+                Err(e) => {
+                    let (valid, rest) = chunk.split_at(e.valid_up_to());
+                    // Already checked to be valid
+                    self.push_str(str::from_utf8(valid).unwrap(), &mut sink);
+
+                    match e.error_len() {
+                        Some(len) => {
+                            self.push_str(REPLACEMENT, &mut sink);
+                            chunk = &rest[len..];
+                        }
+                        None => {
+                            self.partial[..rest.len()].copy_from_slice(rest);
+                            self.partial_len = rest.len();
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finish parsing, passing any text that was held back to `sink`
+    ///
+    /// A start char at the very end of the input is displayed as text, and an
+    /// incomplete UTF-8 sequence is replaced with `U+FFFD REPLACEMENT
+    /// CHARACTER`.
+    pub fn finish(self, mut sink: impl FnMut(Span<'_>)) {
+        if self.pending_start_char {
+            let mut buf = [0; 4];
+            let text = self.start_char.encode_utf8(&mut buf);
+            sink(Span::from_formatting(text, self.color, self.styles));
+        }
+
+        if self.partial_len > 0 {
+            sink(Span::from_formatting(REPLACEMENT, self.color, self.styles));
+        }
+    }
+
+    /// Apply the fmt code `c` to the current state of the parser
+    fn apply_code(&mut self, c: char) -> bool {
+        crate::apply_code(c, &mut self.color, &mut self.styles)
+    }
+}
+
+const REPLACEMENT: &str = "\u{FFFD}";
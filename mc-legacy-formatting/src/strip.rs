@@ -0,0 +1,81 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::{self, Display, Write};
+
+use crate::SpanIter;
+
+/// Write the text of `s` to `out` with all of its formatting codes removed.
+///
+/// Codes are recognized the same way [`SpanIter`] recognizes them, so invalid
+/// codes are kept as text. Whitespace with the `STRIKETHROUGH` style is
+/// written as the original whitespace rather than the dashes that the
+/// [`Display`] impl of [`Span`](crate::Span) uses.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::strip_formatting;
+///
+/// let mut out = String::new();
+/// strip_formatting("&6&lGold &m   &r&zfriends", '&', &mut out).unwrap();
+///
+/// assert_eq!(out, "Gold    &zfriends");
+/// ```
+pub fn strip_formatting<W: Write>(s: &str, start_char: char, out: &mut W) -> fmt::Result {
+    SpanIter::new(s)
+        .with_start_char(start_char)
+        .try_for_each(|span| out.write_str(span.text()))
+}
+
+/// Get the text of `s` with all of its formatting codes removed.
+///
+/// See [`strip_formatting`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::strip_formatting_string;
+///
+/// assert_eq!(strip_formatting_string("§4§lRed §rand white", '§'), "Red and white");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn strip_formatting_string(s: &str, start_char: char) -> String {
+    let mut out = String::with_capacity(s.len());
+    // Writing to a `String` never fails
+    let _ = strip_formatting(s, start_char, &mut out);
+    out
+}
+
+/// Displays the text of a string with all of its formatting codes removed,
+/// without allocating.
+///
+/// Created by [`SpanIter::stripped`]; the settings of that iterator (such as
+/// its start char) are used when parsing.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::SpanExt;
+///
+/// let motd = "§a§lHypixel §7[1.8-1.16]";
+///
+/// assert_eq!(format!("MOTD: {}", motd.span_iter().stripped()), "MOTD: Hypixel [1.8-1.16]");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stripped<'a> {
+    span_iter: SpanIter<'a>,
+}
+
+impl<'a> Stripped<'a> {
+    pub(crate) fn new(span_iter: SpanIter<'a>) -> Self {
+        Self { span_iter }
+    }
+}
+
+impl Display for Stripped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.span_iter
+            .clone()
+            .try_for_each(|span| f.write_str(span.text()))
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{Color, Span, SpanIter, Styles};
+
+/// A [`Span`] flattened into a uniform shape, without the special cases.
+///
+/// Yielded by [`StyledIter`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StyledSpan<'a> {
+    /// The text to display
+    pub text: &'a str,
+    /// The color of the text
+    pub color: Color,
+    /// Styles that should be applied to the text
+    pub styles: Styles,
+}
+
+impl core::fmt::Display for StyledSpan<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+/// Dashes that the text of a [`StyledSpan`] made from a
+/// [`Span::StrikethroughWhitespace`] is sliced out of
+const DASHES: &str = "----------------------------------------------------------------";
+
+/// An iterator that yields [`StyledSpan`]s, for renderers that don't want to
+/// deal with the different kinds of [`Span`].
+///
+/// * [`Span::Plain`] becomes a [`StyledSpan`] with [`Color::White`] and
+///   [`Styles::empty()`]
+/// * [`Span::StrikethroughWhitespace`] becomes a [`StyledSpan`] whose text is
+///   the dashes it's displayed as
+///
+/// The text of the yielded spans is always the same as what the [`Display`]
+/// impl of [`Span`] writes. To avoid allocating, the dashes for a very long
+/// run of strikethrough whitespace may be split across multiple adjacent
+/// [`StyledSpan`]s.
+///
+/// Created by [`SpanExt::styled_iter`](crate::SpanExt::styled_iter) and
+/// [`SpanIter::styled`].
+///
+/// [`Display`]: core::fmt::Display
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{SpanExt, StyledSpan, Color, Styles};
+///
+/// let mut styled_iter = "§m   §rplain".styled_iter();
+///
+/// assert_eq!(
+///     styled_iter.next().unwrap(),
+///     StyledSpan { text: "---", color: Color::White, styles: Styles::STRIKETHROUGH }
+/// );
+/// assert_eq!(
+///     styled_iter.next().unwrap(),
+///     StyledSpan { text: "plain", color: Color::White, styles: Styles::empty() }
+/// );
+/// assert!(styled_iter.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StyledIter<'a> {
+    span_iter: SpanIter<'a>,
+    /// Dashes still to be yielded for the last strikethrough whitespace span,
+    /// along with its formatting
+    remaining_dashes: Option<(usize, Color, Styles)>,
+}
+
+impl<'a> StyledIter<'a> {
+    pub(crate) fn new(span_iter: SpanIter<'a>) -> Self {
+        Self {
+            span_iter,
+            remaining_dashes: None,
+        }
+    }
+
+    fn take_dashes(&mut self, len: usize, color: Color, styles: Styles) -> StyledSpan<'a> {
+        let taken = len.min(DASHES.len());
+
+        if len > taken {
+            self.remaining_dashes = Some((len - taken, color, styles));
+        }
+
+        StyledSpan {
+            text: &DASHES[..taken],
+            color,
+            styles,
+        }
+    }
+}
+
+impl<'a> Iterator for StyledIter<'a> {
+    type Item = StyledSpan<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((len, color, styles)) = self.remaining_dashes.take() {
+            return Some(self.take_dashes(len, color, styles));
+        }
+
+        Some(match self.span_iter.next()? {
+            Span::Styled {
+                text,
+                color,
+                styles,
+                ..
+            }
+            | Span::Url {
+                text,
+                color,
+                styles,
+            } => StyledSpan {
+                text,
+                color,
+                styles,
+            },
+            // The `Display` impl writes a dash for every byte
+            Span::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => self.take_dashes(text.len(), color, styles),
+            Span::Plain(text) => StyledSpan {
+                text,
+                color: Color::White,
+                styles: Styles::empty(),
+            },
+        })
+    }
+}
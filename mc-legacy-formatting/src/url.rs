@@ -0,0 +1,78 @@
+use core::ops::Range;
+
+/// Find the byte range of the first URL in `text`
+///
+/// A URL is a run of chars starting with `http://` or `https://`, or a domain
+/// name (at least two dot-separated labels, ending with a lowercase top-level
+/// domain like `www.example.com` or `discord.gg`, or with one of
+/// [`KNOWN_TLDS`] in any case) optionally followed by a port, path, query, or
+/// fragment. Requiring one of those keeps words run together at the end of a
+/// sentence (`Coming soon.Join now`) from being mistaken for domains. Punctuation at the end of the
+/// run is left out, so a URL at the end of a sentence doesn't pick up the
+/// period.
+pub(crate) fn find_url(text: &str) -> Option<Range<usize>> {
+    let mut prev: Option<char> = None;
+
+    for (idx, c) in text.char_indices() {
+        // A URL can't start in the middle of a word or of another URL
+        let at_boundary = !matches!(prev, Some(p) if p.is_alphanumeric() || "-_.@/".contains(p));
+
+        if at_boundary {
+            if let Some(len) = url_len(&text[idx..]) {
+                return Some(idx..idx + len);
+            }
+        }
+
+        prev = Some(c);
+    }
+
+    None
+}
+
+/// Get the length of the URL at the start of `s`, if there is one
+fn url_len(s: &str) -> Option<usize> {
+    let run_len = s
+        .find(|c: char| c.is_whitespace() || c.is_control() || "<>\"«»".contains(c))
+        .unwrap_or(s.len());
+    let url = s[..run_len].trim_end_matches(|c| ".,;:!?)]}'".contains(c));
+
+    for scheme in ["https://", "http://"] {
+        if let Some(rest) = url.get(scheme.len()..) {
+            if url[..scheme.len()].eq_ignore_ascii_case(scheme) {
+                return Some(url.len()).filter(|_| !rest.is_empty());
+            }
+        }
+    }
+
+    let host = url.split(|c| "/?#:".contains(c)).next()?;
+    if is_domain(host) {
+        Some(url.len())
+    } else {
+        None
+    }
+}
+
+/// Top-level domains that are recognized even when they aren't lowercase (as
+/// in `PLAY.EXAMPLE.COM`)
+const KNOWN_TLDS: [&str; 12] = [
+    "com", "net", "org", "gg", "io", "me", "co", "uk", "de", "us", "eu", "tv",
+];
+
+/// Check whether `host` looks like a domain name
+fn is_domain(host: &str) -> bool {
+    let mut labels = host.split('.');
+    let tld = match labels.next_back() {
+        Some(tld) => tld,
+        None => return false,
+    };
+
+    labels.clone().next().is_some()
+        && labels.all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && tld.len() >= 2
+        && (tld.chars().all(|c| c.is_ascii_lowercase())
+            || KNOWN_TLDS
+                .iter()
+                .any(|known| tld.eq_ignore_ascii_case(known)))
+}
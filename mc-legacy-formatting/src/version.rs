@@ -0,0 +1,31 @@
+/// The Minecraft edition (and version) that text is meant to be displayed
+/// by.
+///
+/// Used by [`SpanIter::with_target_version`](crate::SpanIter::with_target_version)
+/// to match the parsing quirks of a client and by
+/// [`Lint::with_target_version`](crate::Lint::with_target_version) to check for
+/// codes it doesn't support.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TargetVersion {
+    /// Java Edition `1.minor`, such as `Java(16)` for 1.16
+    Java(u8),
+    /// Bedrock Edition
+    Bedrock,
+}
+
+impl TargetVersion {
+    /// Whether hex colors are displayed, which they are on Java Edition 1.16
+    /// and later
+    pub const fn supports_hex(&self) -> bool {
+        matches!(self, TargetVersion::Java(minor) if *minor >= 16)
+    }
+
+    /// Whether a color code clears the styles that came before it
+    ///
+    /// Java Edition has done this since 1.6, so `§l§6` is just gold there;
+    /// older clients and Bedrock Edition leave the styles alone, so it's bold
+    /// and gold.
+    pub const fn color_resets_styles(&self) -> bool {
+        matches!(self, TargetVersion::Java(minor) if *minor >= 6)
+    }
+}
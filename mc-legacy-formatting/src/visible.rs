@@ -0,0 +1,635 @@
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+use core::{iter, ops::Range, str::CharIndices};
+
+#[cfg(feature = "alloc")]
+use crate::SpanIter;
+use crate::{classify_code, code_ranges, CodeKind};
+
+/// Count the chars in `s` that the client will display, skipping over
+/// formatting codes.
+///
+/// This gives the same result as counting the chars of the text of every
+/// [`Span`](crate::Span) parsed out of `s`, but is done in a single pass over
+/// `s` without creating any spans. Uses `§` as the start char; see
+/// [`visible_len_with_start_char`] to use a different one.
+///
+/// Chars are counted even with the `unicode` feature enabled, since that's
+/// what length limits are usually measured in; a letter followed by a
+/// combining accent counts as two. Use
+/// [`Span::display_width`](crate::Span::display_width) to count grapheme
+/// clusters instead.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::visible_len;
+///
+/// assert_eq!(visible_len("§6§lGold§r and §zwhite"), 16);
+/// assert_eq!(visible_len("§6cafe\u{301}"), 5);
+/// ```
+pub fn visible_len(s: &str) -> usize {
+    visible_len_with_start_char(s, '§')
+}
+
+/// Count the chars in `s` that the client will display when `start_char` is
+/// used as the start char.
+///
+/// See [`visible_len`] for more details.
+pub fn visible_len_with_start_char(s: &str, start_char: char) -> usize {
+    measure(s, start_char).0
+}
+
+/// Count the UTF-8 bytes of the text in `s` that the client will display,
+/// skipping over formatting codes.
+///
+/// Useful for protocols that limit text length in bytes rather than chars.
+/// Uses `§` as the start char; see [`visible_byte_len_with_start_char`] to use
+/// a different one.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::visible_byte_len;
+///
+/// // "é" is two bytes long, as is every `§`
+/// assert_eq!(visible_byte_len("§6café"), 5);
+/// ```
+pub fn visible_byte_len(s: &str) -> usize {
+    visible_byte_len_with_start_char(s, '§')
+}
+
+/// Count the UTF-8 bytes of the text in `s` that the client will display
+/// when `start_char` is used as the start char.
+///
+/// See [`visible_byte_len`] for more details.
+pub fn visible_byte_len_with_start_char(s: &str, start_char: char) -> usize {
+    measure(s, start_char).1
+}
+
+/// Cut `s` down to at most `max_visible_chars` visible chars.
+///
+/// Returns the longest prefix of `s` whose [`visible_len`] is at most
+/// `max_visible_chars`. Formatting codes don't count towards the limit, so any
+/// codes directly following the last visible char that fits are kept. The cut
+/// is always made on a char boundary, and a start char is never separated from
+/// the char following it (even if they don't make up a valid code, in which
+/// case both count as visible).
+///
+/// Like [`visible_len`], this counts chars rather than grapheme clusters (even
+/// with the `unicode` feature enabled), so a cluster such as a letter followed
+/// by a combining accent can be cut in two.
+///
+/// Uses `§` as the start char; see [`truncate_visible_with_start_char`] to use
+/// a different one.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::truncate_visible;
+///
+/// assert_eq!(truncate_visible("§6§lGolden §4Apple", 8), "§6§lGolden §4A");
+/// assert_eq!(truncate_visible("§6café", 3), "§6caf");
+/// assert_eq!(truncate_visible("§6cafe\u{301}", 4), "§6cafe");
+/// ```
+pub fn truncate_visible(s: &str, max_visible_chars: usize) -> &str {
+    truncate_visible_with_start_char(s, max_visible_chars, '§')
+}
+
+/// Cut `s` down to at most `max_visible_chars` visible chars when
+/// `start_char` is used as the start char.
+///
+/// See [`truncate_visible`] for more details.
+pub fn truncate_visible_with_start_char(
+    s: &str,
+    max_visible_chars: usize,
+    start_char: char,
+) -> &str {
+    let mut chars = s.char_indices();
+    let mut visible = 0;
+    let mut end = 0;
+
+    while let Some((idx, c)) = chars.next() {
+        let (len, next_end) = if c == start_char {
+            match chars.next() {
+                Some((code_idx, code)) if classify_code(code) != CodeKind::Invalid => {
+                    (0, code_idx + code.len_utf8())
+                }
+                Some((code_idx, code)) => (2, code_idx + code.len_utf8()),
+                None => (1, s.len()),
+            }
+        } else {
+            (1, idx + c.len_utf8())
+        };
+
+        if visible + len > max_visible_chars {
+            break;
+        }
+
+        visible += len;
+        end = next_end;
+    }
+
+    &s[..end]
+}
+
+/// Copy [`truncate_visible`] into a [`String`].
+///
+/// Nothing (such as an ellipsis) is appended to the result.
+#[cfg(feature = "alloc")]
+pub fn truncate_visible_owned(s: &str, max_visible_chars: usize) -> String {
+    truncate_visible(s, max_visible_chars).to_string()
+}
+
+/// Run `f` over each run of visible text in `s`, leaving the formatting codes
+/// between them exactly as they were.
+///
+/// Unlike calling something like [`str::to_uppercase`] on the whole string,
+/// this never touches the code chars, so their casing is kept and a hex color
+/// can't be broken up by changing its `x`. `s` is parsed with `§` as the start
+/// char and with [hex colors](crate::SpanIter::with_hex_colors) recognized, so
+/// both forms of hex color are kept as codes too. Start chars that aren't part
+/// of a valid code are displayed, so they're passed to `f` along with the rest
+/// of the text.
+///
+/// `f` is called once for every run of text between codes that isn't empty.
+/// It's up to `f` not to return text that forms new codes.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::map_visible_text;
+///
+/// let s = "§6§lGolden §x§f§f§8§8§0§0apple";
+/// let mapped = map_visible_text(s, |text| text.replace('e', "3"));
+///
+/// assert_eq!(mapped, "§6§lGold3n §x§f§f§8§8§0§0appl3");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn map_visible_text(s: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut copied = 0;
+
+    for (range, _) in SpanIter::new(s).with_hex_colors(true).with_ranges() {
+        if range.is_empty() {
+            continue;
+        }
+
+        out.push_str(&s[copied..range.start]);
+        out.push_str(&f(&s[range.clone()]));
+        copied = range.end;
+    }
+
+    out.push_str(&s[copied..]);
+    out
+}
+
+/// Uppercase the visible text in `s`, leaving its formatting codes as they
+/// are.
+///
+/// See [`map_visible_text`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::to_uppercase_visible;
+///
+/// assert_eq!(to_uppercase_visible("§b§lsky§rblock"), "§b§lSKY§rBLOCK");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_uppercase_visible(s: &str) -> String {
+    map_visible_text(s, str::to_uppercase)
+}
+
+/// Lowercase the visible text in `s`, leaving its formatting codes as they
+/// are.
+///
+/// See [`map_visible_text`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::to_lowercase_visible;
+///
+/// assert_eq!(to_lowercase_visible("§B§LSKY§RBLOCK"), "§B§Lsky§Rblock");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_lowercase_visible(s: &str) -> String {
+    map_visible_text(s, str::to_lowercase)
+}
+
+/// Find the first place `needle` shows up in the text of `s` that the client
+/// will display.
+///
+/// Formatting codes are skipped over while matching, so a needle can match
+/// text that's split across several differently-formatted spans, and it can't
+/// match text that only exists once the codes are included. Uses `§` as the
+/// start char and matches case-sensitively; see [`find_all_visible`] to change
+/// either of those or to find every match.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::find_visible;
+///
+/// let s = "§aSte§bve joined";
+/// let found = find_visible(s, "Steve").unwrap();
+///
+/// assert_eq!(found.chars, 0..5);
+/// assert_eq!(&s[found.bytes.clone()], "Ste§bve");
+/// assert_eq!(found.pieces(s, '§').collect::<Vec<_>>(), vec![3..6, 9..11]);
+///
+/// assert_eq!(find_visible(s, "§b"), None);
+/// ```
+pub fn find_visible(s: &str, needle: &str) -> Option<VisibleMatch> {
+    find_all_visible(s, needle).next()
+}
+
+/// Find every place `needle` shows up in the text of `s` that the client will
+/// display.
+///
+/// Matches don't overlap and are returned in order. An empty `needle` never
+/// matches. See [`find_visible`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::find_all_visible;
+///
+/// let matches: Vec<_> = find_all_visible("&6Na&lna &rNA", "na")
+///     .with_start_char('&')
+///     .with_ignore_case(true)
+///     .map(|found| found.chars)
+///     .collect();
+///
+/// assert_eq!(matches, vec![0..2, 2..4, 5..7]);
+/// ```
+pub fn find_all_visible<'a>(s: &'a str, needle: &'a str) -> FindVisible<'a> {
+    FindVisible {
+        needle,
+        visible: VisibleChars::new(s, '§'),
+        visible_idx: 0,
+        ignore_case: false,
+    }
+}
+
+/// A match found by [`find_visible`] or [`find_all_visible`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct VisibleMatch {
+    /// The range of visible chars that matched
+    ///
+    /// This indexes into the chars the client displays (the same chars
+    /// counted by [`visible_len`]), not into the original string.
+    pub chars: Range<usize>,
+    /// The range of bytes in the original string from the start of the first
+    /// matched char to the end of the last one
+    ///
+    /// Any formatting codes between the matched chars are included.
+    pub bytes: Range<usize>,
+}
+
+impl VisibleMatch {
+    /// Split [`VisibleMatch::bytes`] into the byte ranges of the matched text
+    /// in `s`, leaving out the formatting codes between them
+    ///
+    /// `s` and `start_char` must be the string and start char the match was
+    /// found with.
+    pub fn pieces<'a>(
+        &self,
+        s: &'a str,
+        start_char: char,
+    ) -> impl Iterator<Item = Range<usize>> + 'a {
+        let bytes = self.bytes.clone();
+        let mut codes = code_ranges(s, start_char)
+            .filter(|(_, kind)| *kind != CodeKind::Invalid)
+            .map(|(range, _)| range)
+            .skip_while(move |range| range.end <= bytes.start)
+            .take_while(move |range| range.start < bytes.end);
+        let end = self.bytes.end;
+        let mut pos = Some(self.bytes.start);
+
+        iter::from_fn(move || loop {
+            let start = pos?;
+
+            match codes.next() {
+                Some(code) => {
+                    pos = Some(code.end);
+                    if code.start > start {
+                        return Some(start..code.start);
+                    }
+                }
+                None => {
+                    pos = None;
+                    return Some(start..end).filter(|range| !range.is_empty());
+                }
+            }
+        })
+    }
+}
+
+/// An iterator over the places a needle shows up in the visible text of a
+/// string.
+///
+/// Created by [`find_all_visible`].
+#[derive(Debug, Clone)]
+pub struct FindVisible<'a> {
+    needle: &'a str,
+    visible: VisibleChars<'a>,
+    visible_idx: usize,
+    ignore_case: bool,
+}
+
+impl<'a> FindVisible<'a> {
+    /// Set the char used to start formatting codes (`§` by default)
+    pub fn with_start_char(mut self, start_char: char) -> Self {
+        self.visible.start_char = start_char;
+        self
+    }
+
+    /// Set whether letters match regardless of case (`false` by default)
+    ///
+    /// Chars are compared one at a time by their lowercase forms.
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    fn chars_match(&self, a: char, b: char) -> bool {
+        a == b || (self.ignore_case && a.to_lowercase().eq(b.to_lowercase()))
+    }
+}
+
+impl Iterator for FindVisible<'_> {
+    type Item = VisibleMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.needle.is_empty() {
+            return None;
+        }
+
+        loop {
+            let mut candidate = self.visible.clone();
+            let mut bytes: Option<Range<usize>> = None;
+            let mut len = 0;
+
+            for n in self.needle.chars() {
+                // Running out of text means nothing later can match either
+                let (idx, c) = candidate.next()?;
+                if !self.chars_match(c, n) {
+                    bytes = None;
+                    break;
+                }
+
+                let start = bytes.map_or(idx, |bytes| bytes.start);
+                bytes = Some(start..idx + c.len_utf8());
+                len += 1;
+            }
+
+            if let Some(bytes) = bytes {
+                let chars = self.visible_idx..self.visible_idx + len;
+                self.visible = candidate;
+                self.visible_idx += len;
+                return Some(VisibleMatch { chars, bytes });
+            }
+
+            self.visible.next()?;
+            self.visible_idx += 1;
+        }
+    }
+}
+
+/// Iterate over the chars in `s` that the client will display, along with
+/// their visible index and byte offset in `s`.
+///
+/// Yields `(visible_idx, byte_idx, char)`, where `visible_idx` counts the
+/// chars displayed so far (the same chars counted by [`visible_len`]) and
+/// `byte_idx` is where the char starts in `s`. Formatting codes are skipped,
+/// and both chars of an invalid code are yielded, since both are displayed.
+/// Uses `§` as the start char; see [`VisibleCharIndices::with_start_char`] to
+/// use a different one.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::visible_char_indices;
+///
+/// assert_eq!(
+///     visible_char_indices("§6é§lx").collect::<Vec<_>>(),
+///     vec![(0, 3, 'é'), (1, 8, 'x')]
+/// );
+/// ```
+pub fn visible_char_indices(s: &str) -> VisibleCharIndices<'_> {
+    VisibleCharIndices {
+        visible: VisibleChars::new(s, '§'),
+        visible_idx: 0,
+    }
+}
+
+/// An iterator over the visible chars in a string, their visible indices,
+/// and their byte offsets.
+///
+/// Created by [`visible_char_indices`].
+#[derive(Debug, Clone)]
+pub struct VisibleCharIndices<'a> {
+    visible: VisibleChars<'a>,
+    visible_idx: usize,
+}
+
+impl<'a> VisibleCharIndices<'a> {
+    /// Set the char used to start formatting codes (`§` by default)
+    pub fn with_start_char(mut self, start_char: char) -> Self {
+        self.visible.start_char = start_char;
+        self
+    }
+}
+
+impl Iterator for VisibleCharIndices<'_> {
+    type Item = (usize, usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (byte_idx, c) = self.visible.next()?;
+        let visible_idx = self.visible_idx;
+        self.visible_idx += 1;
+
+        Some((visible_idx, byte_idx, c))
+    }
+}
+
+/// Map an index into the visible text of `s` to a byte offset in `s`.
+///
+/// Returns the byte offset of the visible char at `visible_idx`. Formatting
+/// codes before that char are skipped, so the offset is always after them.
+/// A `visible_idx` equal to [`visible_len`] maps to the end of `s` (after any
+/// trailing codes), which is where a cursor at the end of the text goes.
+/// Returns `None` if `visible_idx` is past that.
+///
+/// Uses `§` as the start char; see [`visible_to_byte_with_start_char`] to use
+/// a different one.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::visible_to_byte;
+///
+/// let s = "§6Gold §lbold§r";
+///
+/// assert_eq!(visible_to_byte(s, 0), Some(3));
+/// assert_eq!(visible_to_byte(s, 5), Some(11));
+/// assert_eq!(visible_to_byte(s, 9), Some(s.len()));
+/// assert_eq!(visible_to_byte(s, 10), None);
+/// ```
+pub fn visible_to_byte(s: &str, visible_idx: usize) -> Option<usize> {
+    visible_to_byte_with_start_char(s, visible_idx, '§')
+}
+
+/// Map an index into the visible text of `s` to a byte offset in `s` when
+/// `start_char` is used as the start char.
+///
+/// See [`visible_to_byte`] for more details.
+pub fn visible_to_byte_with_start_char(
+    s: &str,
+    visible_idx: usize,
+    start_char: char,
+) -> Option<usize> {
+    let mut count = 0;
+
+    for (idx, byte_idx, _) in visible_char_indices(s).with_start_char(start_char) {
+        if idx == visible_idx {
+            return Some(byte_idx);
+        }
+        count += 1;
+    }
+
+    Some(s.len()).filter(|_| visible_idx == count)
+}
+
+/// Map a byte offset in `s` to an index into the visible text of `s`.
+///
+/// A byte offset at the start of a visible char maps to that char's index.
+/// An offset that lands on a formatting code (either its start char or the
+/// char after it) maps to the position after the code, which is the index of
+/// the next visible char, or [`visible_len`] if there isn't one. Returns
+/// `None` if `byte_idx` is past the end of `s` or isn't on a char boundary.
+///
+/// Uses `§` as the start char; see [`byte_to_visible_with_start_char`] to use
+/// a different one.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::byte_to_visible;
+///
+/// let s = "§6Gold §lbold§r";
+///
+/// assert_eq!(byte_to_visible(s, 0), Some(0));
+/// assert_eq!(byte_to_visible(s, 8), Some(5));
+/// assert_eq!(byte_to_visible(s, 10), Some(5));
+/// assert_eq!(byte_to_visible(s, 11), Some(5));
+/// assert_eq!(byte_to_visible(s, s.len()), Some(9));
+/// assert_eq!(byte_to_visible(s, 1), None);
+/// ```
+pub fn byte_to_visible(s: &str, byte_idx: usize) -> Option<usize> {
+    byte_to_visible_with_start_char(s, byte_idx, '§')
+}
+
+/// Map a byte offset in `s` to an index into the visible text of `s` when
+/// `start_char` is used as the start char.
+///
+/// See [`byte_to_visible`] for more details.
+pub fn byte_to_visible_with_start_char(
+    s: &str,
+    byte_idx: usize,
+    start_char: char,
+) -> Option<usize> {
+    if !s.is_char_boundary(byte_idx) {
+        return None;
+    }
+
+    let mut count = 0;
+
+    for (idx, char_idx, _) in visible_char_indices(s).with_start_char(start_char) {
+        if char_idx >= byte_idx {
+            return Some(idx);
+        }
+        count += 1;
+    }
+
+    Some(count)
+}
+
+/// An iterator over the visible chars in a string and their byte offsets
+///
+/// Pairs up start chars the same way [`measure`] does.
+#[derive(Debug, Clone)]
+struct VisibleChars<'a> {
+    chars: CharIndices<'a>,
+    start_char: char,
+    pending: Option<(usize, char)>,
+}
+
+impl<'a> VisibleChars<'a> {
+    fn new(s: &'a str, start_char: char) -> Self {
+        Self {
+            chars: s.char_indices(),
+            start_char,
+            pending: None,
+        }
+    }
+}
+
+impl Iterator for VisibleChars<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        loop {
+            let (idx, c) = self.chars.next()?;
+            if c != self.start_char {
+                return Some((idx, c));
+            }
+
+            match self.chars.next() {
+                Some((_, code)) if classify_code(code) != CodeKind::Invalid => {}
+                next => {
+                    // Both chars of an invalid code are displayed
+                    self.pending = next;
+                    return Some((idx, c));
+                }
+            }
+        }
+    }
+}
+
+/// Count the visible chars and bytes in `s`
+///
+/// A start char always pairs up with the char following it, the same way the
+/// parser handles it; only pairs that make up a valid code are skipped.
+fn measure(s: &str, start_char: char) -> (usize, usize) {
+    let mut chars = s.chars();
+    let mut count = (0, 0);
+
+    while let Some(c) = chars.next() {
+        if c == start_char {
+            match chars.next() {
+                Some(code) if classify_code(code) != CodeKind::Invalid => {}
+                Some(code) => {
+                    count.0 += 2;
+                    count.1 += c.len_utf8() + code.len_utf8();
+                }
+                None => {
+                    count.0 += 1;
+                    count.1 += c.len_utf8();
+                }
+            }
+        } else {
+            count.0 += 1;
+            count.1 += c.len_utf8();
+        }
+    }
+
+    count
+}
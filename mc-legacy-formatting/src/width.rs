@@ -0,0 +1,164 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::SpanIter;
+
+/// Get the number of pixels the vanilla client advances by when drawing `c`
+/// with the default font (at a GUI scale of 1), not counting the extra pixel
+/// that the `BOLD` style adds.
+///
+/// This includes the one pixel of spacing after each glyph. Widths are exact
+/// for printable ASCII; every other char is given the width of most letters
+/// (`6`), except for control chars (such as `\n`), which don't take up any
+/// space.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::glyph_width;
+///
+/// assert_eq!(glyph_width('a'), 6);
+/// assert_eq!(glyph_width('i'), 2);
+/// assert_eq!(glyph_width(' '), 4);
+/// ```
+pub fn glyph_width(c: char) -> u8 {
+    match c {
+        '!' | '\'' | ',' | '.' | ':' | ';' | 'i' | 'l' | '|' => 2,
+        '`' => 3,
+        ' ' | '"' | '[' | ']' | 'I' => 4,
+        '(' | ')' | '<' | '>' | 'f' | 'k' | 't' | '{' | '}' => 5,
+        '@' => 7,
+        c if c.is_control() => 0,
+        _ => 6,
+    }
+}
+
+/// How [`SpanIter::display_width`](crate::SpanIter::display_width) measures
+/// text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum WidthMode {
+    /// Count columns, the way a terminal lays text out
+    ///
+    /// See [`Span::display_width`](crate::Span::display_width). This is the
+    /// default.
+    Columns,
+    /// Count pixels, the way the vanilla client lays text out
+    ///
+    /// See [`Span::pixel_width`](crate::Span::pixel_width). This is what
+    /// servers use to center their MOTDs.
+    Pixels,
+}
+
+impl Default for WidthMode {
+    fn default() -> Self {
+        WidthMode::Columns
+    }
+}
+
+/// The number of pixels wide each line of a server list MOTD can be before
+/// the vanilla client clips it, for [`fits_motd`].
+pub const MOTD_LINE_WIDTH: usize = 270;
+
+/// Check whether `s` fits in the space the vanilla client gives an MOTD in
+/// the server list: at most two lines, each at most [`MOTD_LINE_WIDTH`]
+/// pixels wide.
+///
+/// Lines are measured with [`Span::pixel_width`](crate::Span::pixel_width),
+/// and `s` is split into lines the same way as [`SpanIter::lines`]. Lines
+/// after the second only count if they have any text in them. See
+/// [`fits_motd_with_width`] to use a different line width.
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::fits_motd;
+///
+/// assert!(fits_motd("§aA Minecraft Server\n§7Come play!"));
+/// assert!(!fits_motd("§aOne\n§bTwo\n§cThree"));
+/// assert!(!fits_motd(&"§l=".repeat(50)));
+/// ```
+pub fn fits_motd(s: &str) -> bool {
+    fits_motd_with_width(s, MOTD_LINE_WIDTH)
+}
+
+/// Check whether `s` fits in two lines that are each at most
+/// `max_line_width` pixels wide.
+///
+/// See [`fits_motd`] for more details.
+pub fn fits_motd_with_width(s: &str, max_line_width: usize) -> bool {
+    let mut widths = [0; 2];
+
+    for (line, span) in SpanIter::new(s).lines() {
+        let width = match widths.get_mut(line) {
+            Some(width) => width,
+            None => return false,
+        };
+
+        *width += span.pixel_width();
+        if *width > max_line_width {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Replace every char of `text` with a pseudo-random char from `charset` that
+/// has the same [`glyph_width`], the way the vanilla client draws text with
+/// the `RANDOM` (obfuscated) style.
+///
+/// The output is deterministic for a given `seed`, so it's suitable for
+/// rendering obfuscated text into a static image. If none of the chars in
+/// `charset` have the same width as a char of `text`, any char from `charset`
+/// is used instead. The output always has the same number of chars as `text`
+/// (or is a copy of `text` if `charset` is empty).
+///
+/// # Examples
+///
+/// ```
+/// use mc_legacy_formatting::{glyph_width, scramble};
+///
+/// let charset: Vec<char> = ('!'..='~').collect();
+/// let scrambled = scramble("Secret", 42, &charset);
+///
+/// assert_eq!(scrambled, scramble("Secret", 42, &charset));
+/// assert_eq!(
+///     scrambled.chars().map(glyph_width).collect::<Vec<_>>(),
+///     "Secret".chars().map(glyph_width).collect::<Vec<_>>()
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn scramble(text: &str, seed: u64, charset: &[char]) -> String {
+    if charset.is_empty() {
+        return text.into();
+    }
+
+    let mut state = seed;
+
+    text.chars()
+        .map(|c| {
+            let random = splitmix64(&mut state);
+            let width = glyph_width(c);
+            let matching = charset.iter().filter(|m| glyph_width(**m) == width);
+
+            match matching.clone().count() {
+                0 => charset[(random % charset.len() as u64) as usize],
+                count => *matching
+                    .clone()
+                    .nth((random % count as u64) as usize)
+                    .unwrap(),
+            }
+        })
+        .collect()
+}
+
+/// A tiny, good-enough PRNG so that [`scramble`] doesn't need any
+/// dependencies
+#[cfg(feature = "alloc")]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
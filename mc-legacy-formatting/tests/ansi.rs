@@ -0,0 +1,61 @@
+#![cfg(feature = "alloc")]
+
+use mc_legacy_formatting::{from_ansi, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn truecolor_and_bold() {
+    assert_eq!(
+        from_ansi("\x1b[38;2;255;136;0;1mWarning:\x1b[0m disk almost full"),
+        "§x§f§f§8§8§0§0§lWarning:§r disk almost full"
+    );
+}
+
+#[test]
+fn basic_colors() {
+    assert_eq!(
+        from_ansi("\x1b[32mok \x1b[91mfailed \x1b[39mdone"),
+        "§2ok §cfailed §rdone"
+    );
+}
+
+#[test]
+fn palette_colors_use_nearest_named() {
+    // 214 is (255, 175, 0) and 240 is (88, 88, 88)
+    assert_eq!(
+        from_ansi("\x1b[38;5;214mgold\x1b[38;5;240m gray\x1b[38;5;9m red"),
+        "§6gold§8 gray§c red"
+    );
+}
+
+#[test]
+fn styles_survive_color_changes() {
+    let s = from_ansi("\x1b[1;4;33mbold \x1b[36mcyan\x1b[22;24m plain cyan");
+
+    assert_eq!(
+        SpanIter::new(&s).collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("bold ", Color::Gold, Styles::BOLD | Styles::UNDERLINED),
+            Span::new_styled("cyan", Color::DarkAqua, Styles::BOLD | Styles::UNDERLINED),
+            Span::new_styled(" plain cyan", Color::DarkAqua, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn unknown_escapes_stripped() {
+    assert_eq!(
+        from_ansi("\x1b[2K\x1b[44mno \x1b[48;5;12mbackground\x1b[5m\x1b7 here\x1b["),
+        "no background here"
+    );
+}
+
+#[test]
+fn empty_sgr_resets() {
+    assert_eq!(from_ansi("\x1b[31;3mred\x1b[m plain"), "§4§ored§r plain");
+}
+
+#[test]
+fn start_char_in_text_escaped() {
+    assert_eq!(from_ansi("\x1b[31m§ sign"), "§4§§ sign");
+}
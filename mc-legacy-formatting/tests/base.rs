@@ -0,0 +1,111 @@
+use mc_legacy_formatting::{with_base, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+fn lore(s: &str) -> Vec<Span<'_>> {
+    with_base(SpanIter::new(s), Color::DarkPurple, Styles::ITALIC).collect()
+}
+
+#[test]
+fn base_shows_through() {
+    assert_eq!(
+        lore("A sturdy blade. §6Forged in §c§lfire§r, §8§oreforged§r twice."),
+        vec![
+            Span::new_styled("A sturdy blade. ", Color::DarkPurple, Styles::ITALIC),
+            Span::new_styled("Forged in ", Color::Gold, Styles::ITALIC),
+            Span::new_styled("fire", Color::Red, Styles::ITALIC | Styles::BOLD),
+            Span::new_styled(", ", Color::DarkPurple, Styles::ITALIC),
+            Span::new_styled("reforged", Color::DarkGray, Styles::ITALIC),
+            Span::new_styled(" twice.", Color::DarkPurple, Styles::ITALIC),
+        ]
+    );
+}
+
+#[test]
+fn styles_without_a_color() {
+    assert_eq!(
+        lore("§nunderlined"),
+        vec![Span::new_styled(
+            "underlined",
+            Color::DarkPurple,
+            Styles::ITALIC | Styles::UNDERLINED
+        )]
+    );
+}
+
+#[test]
+fn strikethrough_whitespace() {
+    assert_eq!(
+        with_base(SpanIter::new("a   b"), Color::Gray, Styles::STRIKETHROUGH).collect::<Vec<_>>(),
+        vec![Span::new_styled(
+            "a   b",
+            Color::Gray,
+            Styles::STRIKETHROUGH
+        )]
+    );
+    assert_eq!(
+        with_base(SpanIter::new("   "), Color::Gray, Styles::STRIKETHROUGH).collect::<Vec<_>>(),
+        vec![Span::new_strikethrough_whitespace(
+            "   ",
+            Color::Gray,
+            Styles::STRIKETHROUGH
+        )]
+    );
+}
+
+#[test]
+fn urls_stay_urls() {
+    let spans: Vec<Span> = with_base(
+        SpanIter::new("see example.com").with_url_detection(true),
+        Color::Gray,
+        Styles::empty(),
+    )
+    .collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            Span::new_styled("see ", Color::Gray, Styles::empty()),
+            Span::new_url("example.com", Color::Gray, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn default_base_changes_nothing() {
+    let s = "plain §6gold §lbold §rplain";
+
+    assert_eq!(
+        with_base(SpanIter::new(s), Color::White, Styles::empty()).collect::<Vec<_>>(),
+        SpanIter::new(s).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn any_span_source() {
+    let spans = [
+        Span::new_plain("a"),
+        Span::new_styled("b", Color::Aqua, Styles::BOLD),
+    ];
+
+    assert_eq!(
+        with_base(spans.iter().copied(), Color::Gray, Styles::empty()).collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("a", Color::Gray, Styles::empty()),
+            Span::new_styled("b", Color::Aqua, Styles::BOLD),
+        ]
+    );
+}
+
+#[test]
+fn keeps_background() {
+    assert_eq!(
+        with_base(
+            SpanIter::new("§^1hi").with_background_prefix(Some('^')),
+            Color::Gray,
+            Styles::ITALIC
+        )
+        .collect::<Vec<_>>(),
+        vec![Span::new_styled("hi", Color::Gray, Styles::ITALIC)
+            .with_background(Some(Color::DarkBlue))]
+    );
+}
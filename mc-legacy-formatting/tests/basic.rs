@@ -3,8 +3,9 @@ mod common;
 use common::*;
 
 use mc_legacy_formatting::{Color, Span, SpanIter, Styles};
+use proptest::prelude::*;
 
-pub fn spans_sc(start_char: char, s: &str) -> Vec<Span> {
+pub fn spans_sc(start_char: char, s: &str) -> Vec<Span<'_>> {
     SpanIter::new(s).with_start_char(start_char).collect()
 }
 
@@ -83,6 +84,43 @@ mod fake_codes {
             vec![Span::new_plain("§§§§§this has no format§ting codes§")]
         );
     }
+
+    /// The second start char is taken as the (invalid) code for the first one,
+    /// so the char after it is just text, like it is in Minecraft
+    #[test]
+    fn start_char_then_start_char() {
+        assert_eq!(spans("text§§ahi"), vec![Span::new_plain("text§§ahi")]);
+        assert_eq!(spans("§§ahi"), vec![Span::new_plain("§§ahi")]);
+    }
+
+    #[test]
+    fn fake_code_then_real_code() {
+        assert_eq!(
+            spans("text§ §ahi"),
+            vec![
+                Span::new_plain("text§ "),
+                Span::new_styled("hi", Color::Green, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn start_char_pair_then_real_code() {
+        assert_eq!(
+            spans("text§§§ahi"),
+            vec![
+                Span::new_plain("text§§"),
+                Span::new_styled("hi", Color::Green, Styles::empty()),
+            ]
+        );
+        assert_eq!(
+            spans("§§§ahi"),
+            vec![
+                Span::new_plain("§§"),
+                Span::new_styled("hi", Color::Green, Styles::empty()),
+            ]
+        );
+    }
 }
 
 mod custom_start_char {
@@ -234,6 +272,43 @@ fn multiline_message() {
     );
 }
 
+#[test]
+fn multiline_message_split_on_newline() {
+    let s = "§8Welcome to §6§lAmazing Minecraft Server\n§8§oYour hub for §d§op2w §8§ogameplay!";
+    // The only newline already ends a span
+    assert_eq!(
+        SpanIter::new(s).split_on_newline(true).collect::<Vec<_>>(),
+        spans(s)
+    );
+
+    let s = "§8Welcome to §6§lAmazing\nMinecraft Server\n\n§8§oYour hub for §d§op2w §8§ogameplay!";
+    assert_eq!(
+        SpanIter::new(s).split_on_newline(true).collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("Welcome to ", Color::DarkGray, Styles::empty()),
+            Span::new_styled("Amazing\n", Color::Gold, Styles::BOLD),
+            Span::new_styled("Minecraft Server\n", Color::Gold, Styles::BOLD),
+            Span::new_styled("\n", Color::Gold, Styles::BOLD),
+            Span::new_styled("Your hub for ", Color::DarkGray, Styles::ITALIC),
+            Span::new_styled("p2w ", Color::LightPurple, Styles::ITALIC),
+            Span::new_styled("gameplay!", Color::DarkGray, Styles::ITALIC)
+        ]
+    );
+}
+
+#[test]
+fn split_on_newline_keeps_text() {
+    for s in SERVER_MOTDS {
+        let split = SpanIter::new(s).split_on_newline(true).collect::<Vec<_>>();
+
+        for span in &split {
+            let text = span.text();
+            assert!(!text.strip_suffix('\n').unwrap_or(text).contains('\n'));
+        }
+        assert_eq!(merged(split.into_iter()), merged(SpanIter::new(s)));
+    }
+}
+
 #[test]
 fn yields_none_after_finish() {
     let s = "§lthis will be bold §o§mand this will be bold, italic, and strikethrough";
@@ -245,3 +320,1336 @@ fn yields_none_after_finish() {
         assert!(iter.next().is_none());
     }
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn collect_into_reuses_buffer() {
+    let mut buf = Vec::with_capacity(16);
+
+    SpanIter::new("§4dark red §lbold").collect_into(&mut buf);
+    assert_eq!(
+        buf,
+        vec![
+            Span::new_styled("dark red ", Color::DarkRed, Styles::empty()),
+            Span::new_styled("bold", Color::DarkRed, Styles::BOLD)
+        ]
+    );
+
+    SpanIter::new("plain").collect_into(&mut buf);
+    assert_eq!(buf, vec![Span::new_plain("plain")]);
+    assert!(buf.capacity() >= 16);
+}
+
+#[test]
+fn trailing_reset_adds_one_span() {
+    for s in SERVER_MOTDS.iter().chain(&["", "§4§l", "plain"]) {
+        let mut expected = spans(s);
+        expected.push(Span::new_plain(""));
+
+        let with_reset: Vec<_> = SpanIter::new(s).with_trailing_reset().collect();
+        assert_eq!(with_reset, expected);
+        assert_eq!(
+            SpanIter::new(s).with_trailing_reset().display_width(),
+            SpanIter::new(s).display_width()
+        );
+    }
+}
+
+#[test]
+fn trailing_reset_yields_none_after() {
+    let mut span_iter = SpanIter::new("§ctext").with_trailing_reset();
+
+    assert_eq!(
+        span_iter.next(),
+        Some(Span::new_styled("text", Color::Red, Styles::empty()))
+    );
+    assert_eq!(span_iter.next(), Some(Span::new_plain("")));
+    assert_eq!(span_iter.next(), None);
+    assert_eq!(span_iter.next(), None);
+}
+
+#[test]
+fn trailing_reset_range() {
+    let s = "§ctext";
+
+    assert_eq!(
+        SpanIter::new(s).with_trailing_reset().with_ranges().last(),
+        Some((s.len()..s.len(), Span::new_plain("")))
+    );
+}
+
+#[test]
+fn reset_disabled() {
+    let s = "§6§lGold §rstill gold §Rand bold";
+
+    assert_eq!(
+        SpanIter::new(s).with_reset_char(None).collect::<Vec<_>>(),
+        vec![Span::new_styled(
+            "Gold §rstill gold §Rand bold",
+            Color::Gold,
+            Styles::BOLD
+        )]
+    );
+}
+
+#[test]
+fn custom_reset_char() {
+    let s = "&6&lGold &rstill gold &Xwhite &cred &xwhite";
+
+    assert_eq!(
+        SpanIter::new(s)
+            .with_start_char('&')
+            .with_reset_char(Some('x'))
+            .collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("Gold &rstill gold ", Color::Gold, Styles::BOLD),
+            Span::new_plain("white "),
+            Span::new_styled("red ", Color::Red, Styles::empty()),
+            Span::new_plain("white"),
+        ]
+    );
+}
+
+#[test]
+fn reset_char_takes_priority() {
+    assert_eq!(
+        SpanIter::new("§cred §Aplain")
+            .with_reset_char(Some('a'))
+            .collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("red ", Color::Red, Styles::empty()),
+            Span::new_plain("plain"),
+        ]
+    );
+}
+
+#[test]
+fn back_to_back_codes_yield_no_empty_spans() {
+    for s in ["§a§bhi", "§a§bhi§c§d", "§a§b", "§a§b§r§lhi§a"] {
+        assert!(SpanIter::new(s).all(|span| !span.is_empty()), "{:?}", s);
+    }
+
+    assert_eq!(
+        spans("§a§bhi"),
+        vec![Span::new_styled("hi", Color::Aqua, Styles::empty())]
+    );
+}
+
+#[test]
+fn skip_empty_drops_empty_spans() {
+    for s in SERVER_MOTDS.iter().chain(&["", "§a§b", "hi§c"]) {
+        let with_reset: Vec<_> = SpanIter::new(s).with_trailing_reset().collect();
+        assert!(with_reset.last().unwrap().is_empty());
+
+        assert_eq!(
+            SpanIter::new(s)
+                .with_trailing_reset()
+                .skip_empty()
+                .collect::<Vec<_>>(),
+            spans(s)
+        );
+    }
+}
+
+#[test]
+fn is_empty_for_every_kind() {
+    for (color, styles) in [
+        (Color::White, Styles::empty()),
+        (Color::Green, Styles::STRIKETHROUGH),
+    ] {
+        assert!(Span::new_plain("").is_empty());
+        assert!(Span::new_styled("", color, styles).is_empty());
+        assert!(Span::new_strikethrough_whitespace("", color, styles).is_empty());
+        assert!(Span::new_url("", color, styles).is_empty());
+        assert!(!Span::new_strikethrough_whitespace(" ", color, styles).is_empty());
+    }
+}
+
+#[test]
+fn skip_empty_with_other_settings() {
+    for s in [
+        "§a§b",
+        "§a§b§r",
+        "§a§bhi§c",
+        "&&§a§b",
+        "§x§a§b§c§d§e§f",
+        "\n§a\n§b",
+    ] {
+        let span_iter = SpanIter::new(s)
+            .with_doubled_escape(true)
+            .with_hex_colors(true)
+            .split_on_newline(true)
+            .with_trailing_reset();
+
+        assert_eq!(
+            span_iter.clone().skip_empty().collect::<Vec<_>>(),
+            span_iter
+                .filter(|span| !span.is_empty())
+                .collect::<Vec<_>>(),
+            "{:?}",
+            s
+        );
+    }
+}
+
+mod doubled_escape {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn spans_escaped(s: &str) -> Vec<Span<'_>> {
+        SpanIter::new(s)
+            .with_start_char('&')
+            .with_doubled_escape(true)
+            .collect()
+    }
+
+    #[test]
+    fn escape_at_start_is_literal() {
+        assert_eq!(
+            spans_escaped("&&6 not gold"),
+            vec![Span::new_plain("&6 not gold")]
+        );
+    }
+
+    #[test]
+    fn escape_after_code() {
+        assert_eq!(
+            spans_escaped("&6&& gold amp"),
+            vec![Span::new_styled("& gold amp", Color::Gold, Styles::empty())]
+        );
+    }
+
+    #[test]
+    fn escape_mid_text_splits_span() {
+        assert_eq!(
+            spans_escaped("&6Tom && Jerry && co"),
+            vec![
+                Span::new_styled("Tom ", Color::Gold, Styles::empty()),
+                Span::new_styled("& Jerry ", Color::Gold, Styles::empty()),
+                Span::new_styled("& co", Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_followed_by_code() {
+        assert_eq!(
+            spans_escaped("a&&&lbold"),
+            vec![
+                Span::new_plain("a"),
+                Span::new_plain("&"),
+                Span::new_styled("bold", Color::White, Styles::BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    fn off_by_default() {
+        assert_eq!(
+            spans_sc('&', "&&6 not gold"),
+            vec![Span::new_plain("&&6 not gold")]
+        );
+    }
+}
+
+mod backslash_escape {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn spans_escaped(s: &str) -> Vec<Span<'_>> {
+        SpanIter::new(s).with_backslash_escape(true).collect()
+    }
+
+    #[test]
+    fn escaped_start_char() {
+        assert_eq!(spans_escaped(r"\§a"), vec![Span::new_plain("§a")]);
+        assert_eq!(
+            spans_escaped(r"§6gold \§a gold"),
+            vec![
+                Span::new_styled("gold ", Color::Gold, Styles::empty()),
+                Span::new_styled("§a gold", Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_backslash_then_code() {
+        assert_eq!(
+            spans_escaped(r"\\§agreen"),
+            vec![
+                Span::new_plain(r"\"),
+                Span::new_styled("green", Color::Green, Styles::empty()),
+            ]
+        );
+        assert_eq!(
+            spans_escaped(r"a\\\\§agreen"),
+            vec![
+                Span::new_plain(r"a\\"),
+                Span::new_styled("green", Color::Green, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_backslash_and_start_char() {
+        assert_eq!(
+            spans_escaped(r"a\\\§agreen"),
+            vec![Span::new_plain(r"a\"), Span::new_plain("§agreen")]
+        );
+    }
+
+    #[test]
+    fn trailing_backslash() {
+        assert_eq!(spans_escaped(r"text\"), vec![Span::new_plain(r"text\")]);
+        assert_eq!(
+            spans_escaped(r"§6text\\"),
+            vec![Span::new_styled(r"text\\", Color::Gold, Styles::empty())]
+        );
+        // A start char at the end isn't a code, so the backslashes are kept
+        assert_eq!(spans_escaped(r"text\\§"), vec![Span::new_plain(r"text\\§")]);
+    }
+
+    #[test]
+    fn backslashes_elsewhere_are_kept() {
+        assert_eq!(
+            spans_escaped(r"C:\\path\to §6\\§zfile"),
+            vec![
+                Span::new_plain(r"C:\\path\to "),
+                Span::new_styled(r"\\§zfile", Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_invalid_code_keeps_backslash() {
+        assert_eq!(spans_escaped(r"\§zfile"), vec![Span::new_plain(r"\§zfile")]);
+        assert_eq!(
+            spans_escaped(r"a\§zfile"),
+            vec![Span::new_plain(r"a\§zfile")]
+        );
+        assert_eq!(
+            spans_escaped(r"§6a\\\§zfile"),
+            vec![Span::new_styled(
+                r"a\\\§zfile",
+                Color::Gold,
+                Styles::empty()
+            )]
+        );
+        assert_eq!(spans_escaped(r"text\§"), vec![Span::new_plain(r"text\§")]);
+    }
+
+    #[test]
+    fn custom_start_char() {
+        assert_eq!(
+            SpanIter::new(r"&6Type \&a for green, Tom \& Jerry")
+                .with_start_char('&')
+                .with_backslash_escape(true)
+                .collect::<Vec<_>>(),
+            vec![
+                Span::new_styled("Type ", Color::Gold, Styles::empty()),
+                Span::new_styled(r"&a for green, Tom \& Jerry", Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn off_by_default() {
+        assert_eq!(
+            spans(r"\§agreen"),
+            vec![
+                Span::new_plain(r"\"),
+                Span::new_styled("green", Color::Green, Styles::empty()),
+            ]
+        );
+    }
+}
+
+mod hex_colors {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hex(s: &str) -> Vec<Span<'_>> {
+        SpanIter::new(s).with_hex_colors(true).collect()
+    }
+
+    #[test]
+    fn bukkit_form() {
+        assert_eq!(
+            hex("§x§1§2§A§b§c§Dtext"),
+            vec![Span::new_styled(
+                "text",
+                Color::Rgb(0x12, 0xab, 0xcd),
+                Styles::empty()
+            )]
+        );
+        assert_eq!(
+            hex("§X§1§2§a§b§c§dtext"),
+            vec![Span::new_styled(
+                "text",
+                Color::Rgb(0x12, 0xab, 0xcd),
+                Styles::empty()
+            )]
+        );
+    }
+
+    #[test]
+    fn short_form() {
+        assert_eq!(
+            SpanIter::new("before &#12abCDafter")
+                .with_start_char('&')
+                .with_hex_colors(true)
+                .collect::<Vec<_>>(),
+            vec![
+                Span::new_plain("before "),
+                Span::new_styled("after", Color::Rgb(0x12, 0xab, 0xcd), Styles::empty())
+            ]
+        );
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(
+            hex("§#FFAA00gold"),
+            vec![Span::new_styled("gold", Color::Gold, Styles::empty())]
+        );
+    }
+
+    #[test]
+    fn resets_styles() {
+        assert_eq!(
+            hex("§l§#123456a§lb"),
+            vec![
+                Span::new_styled("a", Color::Rgb(0x12, 0x34, 0x56), Styles::empty()),
+                Span::new_styled("b", Color::Rgb(0x12, 0x34, 0x56), Styles::BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    fn incomplete_codes_are_text() {
+        assert_eq!(
+            hex("§x§f§ftext"),
+            vec![Span::new_plain("§x"), Span::new_plain("text")]
+        );
+        assert_eq!(hex("§#ff55"), vec![Span::new_plain("§#ff55")]);
+        assert_eq!(hex("§#ff55zz!"), vec![Span::new_plain("§#ff55zz!")]);
+        assert_eq!(
+            hex("§x§f§f§f§f§f§z"),
+            vec![Span::new_plain("§x"), Span::new_plain("§z")]
+        );
+    }
+
+    #[test]
+    fn off_by_default() {
+        assert_eq!(spans("§#123456text"), vec![Span::new_plain("§#123456text")]);
+    }
+
+    #[test]
+    fn quantize() {
+        assert_eq!(
+            SpanIter::new("&#ff5556text")
+                .with_start_char('&')
+                .with_hex_colors(true)
+                .with_quantize_hex(true)
+                .collect::<Vec<_>>(),
+            vec![Span::new_styled("text", Color::Red, Styles::empty())]
+        );
+        assert_eq!(
+            SpanIter::new("§x§0§0§0§0§a§0text")
+                .with_hex_colors(true)
+                .with_quantize_hex(true)
+                .collect::<Vec<_>>(),
+            vec![Span::new_styled("text", Color::DarkBlue, Styles::empty())]
+        );
+    }
+
+    #[test]
+    fn quantize_needs_hex_colors() {
+        assert_eq!(
+            SpanIter::new("§#ff5556text")
+                .with_quantize_hex(true)
+                .collect::<Vec<_>>(),
+            vec![Span::new_plain("§#ff5556text")]
+        );
+    }
+
+    #[test]
+    fn reset_char_takes_priority() {
+        assert_eq!(
+            SpanIter::new("§6a§x§lb")
+                .with_hex_colors(true)
+                .with_reset_char(Some('x'))
+                .collect::<Vec<_>>(),
+            vec![
+                Span::new_styled("a", Color::Gold, Styles::empty()),
+                Span::new_styled("b", Color::White, Styles::BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn round_trip() {
+        use mc_legacy_formatting::to_legacy_string;
+
+        let spans = vec![
+            Span::new_styled("orange ", Color::Rgb(255, 136, 0), Styles::BOLD),
+            Span::new_plain("plain"),
+        ];
+
+        assert_eq!(hex(&to_legacy_string(&spans, '§')), spans);
+    }
+}
+
+mod color_defaults {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn color_defaults(s: &str) -> Vec<(bool, Span<'_>)> {
+        SpanIter::new(s).with_color_defaults().collect()
+    }
+
+    #[test]
+    fn explicit_white() {
+        assert_eq!(
+            color_defaults("§ftext"),
+            vec![(false, Span::new_plain("text"))]
+        );
+    }
+
+    #[test]
+    fn default_white() {
+        assert_eq!(
+            color_defaults("§ltext"),
+            vec![(true, Span::new_styled("text", Color::White, Styles::BOLD))]
+        );
+        assert_eq!(
+            color_defaults("text"),
+            vec![(true, Span::new_plain("text"))]
+        );
+    }
+
+    #[test]
+    fn multiple_styles_no_colors() {
+        let s = "§lthis will be bold §o§mand this will be bold, italic, and strikethrough";
+        let color_defaults = color_defaults(s);
+
+        assert_eq!(color_defaults.len(), 2);
+        assert!(color_defaults.iter().all(|(default, _)| *default));
+    }
+
+    #[test]
+    fn styles_keep_explicit_color() {
+        assert_eq!(
+            color_defaults("§f§lbold §oitalic"),
+            vec![
+                (false, Span::new_styled("bold ", Color::White, Styles::BOLD)),
+                (
+                    false,
+                    Span::new_styled("italic", Color::White, Styles::BOLD | Styles::ITALIC)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reset_defaults_color() {
+        assert_eq!(
+            color_defaults("§cred §rreset §l§fwhite"),
+            vec![
+                (false, Span::new_styled("red ", Color::Red, Styles::empty())),
+                (true, Span::new_plain("reset ")),
+                (false, Span::new_plain("white")),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_codes_keep_default() {
+        assert_eq!(
+            color_defaults("§zstill §rdefault"),
+            vec![
+                (true, Span::new_plain("§zstill ")),
+                (true, Span::new_plain("default")),
+            ]
+        );
+    }
+
+    #[test]
+    fn initial_state_is_explicit() {
+        assert_eq!(
+            SpanIter::new("text")
+                .with_initial_state(Color::White, Styles::empty())
+                .with_color_defaults()
+                .collect::<Vec<_>>(),
+            vec![(false, Span::new_plain("text"))]
+        );
+    }
+
+    #[test]
+    fn trailing_reset_is_default() {
+        assert_eq!(
+            SpanIter::new("§6gold")
+                .with_trailing_reset()
+                .with_color_defaults()
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    false,
+                    Span::new_styled("gold", Color::Gold, Styles::empty())
+                ),
+                (true, Span::new_plain("")),
+            ]
+        );
+    }
+}
+
+mod with_codes {
+    use super::*;
+    use mc_legacy_formatting::{code_ranges, CodeKind};
+    use pretty_assertions::assert_eq;
+
+    fn with_codes(s: &str) -> Vec<(&str, Span<'_>)> {
+        SpanIter::new(s).with_codes().collect()
+    }
+
+    #[test]
+    fn original_casing() {
+        assert_eq!(
+            with_codes("§D§Ltext"),
+            vec![(
+                "§D§L",
+                Span::new_styled("text", Color::LightPurple, Styles::BOLD)
+            )]
+        );
+    }
+
+    #[test]
+    fn redundant_codes_are_kept() {
+        assert_eq!(
+            with_codes("a§c§l§6§6b"),
+            vec![
+                ("", Span::new_plain("a")),
+                (
+                    "§c§l§6§6",
+                    Span::new_styled("b", Color::Gold, Styles::empty())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fake_codes_are_text() {
+        assert_eq!(
+            with_codes("§6§zgold"),
+            vec![(
+                "§6",
+                Span::new_styled("§zgold", Color::Gold, Styles::empty())
+            )]
+        );
+    }
+
+    fn assert_lossless(s: &str) {
+        let joined: String = with_codes(s)
+            .into_iter()
+            .flat_map(|(codes, span)| [codes, span.text()])
+            .collect();
+
+        // Only codes that don't affect any text are left out
+        assert!(s.starts_with(&joined), "{:?}", s);
+        let rest = &s[joined.len()..];
+        let codes_len: usize = code_ranges(rest, '§')
+            .filter(|(_, kind)| *kind != CodeKind::Invalid)
+            .map(|(range, _)| range.len())
+            .sum();
+        assert_eq!(codes_len, rest.len(), "{:?}", s);
+    }
+
+    #[test]
+    fn fixtures_lossless() {
+        for s in SERVER_MOTDS {
+            assert_lossless(s);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn lossless(s in strings_of(&['§', '6', 'D', 'l', 'R', 'z', ' ', 'é'])) {
+            assert_lossless(&s);
+        }
+    }
+}
+
+mod reset {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn restarts_after_partial_consumption() {
+        let mut iter = SpanIter::new(PLAY_MC_BLAZE_COM);
+        iter.by_ref().take(3).for_each(drop);
+        iter.reset();
+
+        assert_eq!(iter.collect::<Vec<_>>(), spans(PLAY_MC_BLAZE_COM));
+    }
+
+    #[test]
+    fn restarts_after_exhaustion() {
+        for s in SERVER_MOTDS {
+            let mut iter = SpanIter::new(s)
+                .with_trailing_reset()
+                .with_doubled_escape(true);
+            let first = iter.by_ref().collect::<Vec<_>>();
+            iter.reset();
+
+            assert_eq!(iter.collect::<Vec<_>>(), first);
+        }
+    }
+
+    #[test]
+    fn keeps_initial_state_and_options() {
+        let mut iter = SpanIter::new("&&gold &lbold\nnext")
+            .with_start_char('&')
+            .with_doubled_escape(true)
+            .split_on_newline(true)
+            .with_initial_state(Color::Gold, Styles::empty());
+        let first = iter.by_ref().collect::<Vec<_>>();
+
+        assert_eq!(
+            first,
+            vec![
+                Span::new_styled("&gold ", Color::Gold, Styles::empty()),
+                Span::new_styled("bold\n", Color::Gold, Styles::BOLD),
+                Span::new_styled("next", Color::Gold, Styles::BOLD),
+            ]
+        );
+
+        iter.reset();
+        assert_eq!(iter.collect::<Vec<_>>(), first);
+    }
+
+    #[test]
+    fn mid_split_span() {
+        let mut iter = SpanIter::new("§aa\nb").split_on_newline(true);
+        assert_eq!(
+            iter.next(),
+            Some(Span::new_styled("a\n", Color::Green, Styles::empty()))
+        );
+
+        iter.reset();
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            vec![
+                Span::new_styled("a\n", Color::Green, Styles::empty()),
+                Span::new_styled("b", Color::Green, Styles::empty()),
+            ]
+        );
+    }
+}
+
+/// `SpanIter` skips over text between start chars instead of looking at every
+/// char, so check it against a parser that does look at every char
+mod char_by_char {
+    use super::*;
+    use mc_legacy_formatting::apply_code;
+    use pretty_assertions::assert_eq;
+
+    fn make_span(text: &str, color: Color, styles: Styles) -> Span<'_> {
+        if color == Color::White && styles.is_empty() {
+            Span::new_plain(text)
+        } else if text.chars().all(|c| c.is_ascii_whitespace())
+            && styles.contains(Styles::STRIKETHROUGH)
+        {
+            Span::new_strikethrough_whitespace(text, color, styles)
+        } else {
+            Span::new_styled(text, color, styles)
+        }
+    }
+
+    fn reference_spans(s: &str, start_char: char) -> Vec<Span<'_>> {
+        let mut spans = Vec::new();
+        let mut color = Color::White;
+        let mut styles = Styles::empty();
+        let mut text_start = None;
+        let mut chars = s.char_indices();
+
+        while let Some((idx, c)) = chars.next() {
+            if c == start_char {
+                if let Some((_, code)) = chars.next() {
+                    let (mut new_color, mut new_styles) = (color, styles);
+
+                    if apply_code(code, &mut new_color, &mut new_styles) {
+                        if let Some(start) = text_start.take() {
+                            spans.push(make_span(&s[start..idx], color, styles));
+                        }
+
+                        color = new_color;
+                        styles = new_styles;
+                        continue;
+                    }
+                }
+            }
+
+            text_start.get_or_insert(idx);
+        }
+
+        if let Some(start) = text_start {
+            spans.push(make_span(&s[start..], color, styles));
+        }
+
+        spans
+    }
+
+    #[test]
+    fn server_motds() {
+        for s in SERVER_MOTDS {
+            assert_eq!(spans(s), reference_spans(s, '§'));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn random(
+            start_char in prop::sample::select(vec!['§', '&', 'a', ' ', '€', '😀']),
+            s in strings_of(&['§', '&', 'a', '6', 'L', 'm', 'r', 'z', ' ', '€', 'é', '😀']),
+        ) {
+            assert_eq!(
+                spans_sc(start_char, &s),
+                reference_spans(&s, start_char),
+                "{:?} with start char {:?}",
+                s,
+                start_char
+            );
+        }
+    }
+}
+
+mod resume {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Parse `left` and then `right`, carrying the state over between them
+    fn parse_split<'a>(left: &'a str, right: &'a str, start_char: char) -> Vec<Span<'a>> {
+        let mut first = SpanIter::new(left).with_start_char(start_char);
+        let mut spans: Vec<Span> = first.by_ref().collect();
+        let (color, styles) = first.state();
+
+        spans.extend(
+            SpanIter::new(right)
+                .with_start_char(start_char)
+                .with_initial_state(color, styles),
+        );
+        spans
+    }
+
+    #[test]
+    fn continues_formatting() {
+        assert_eq!(
+            merged(parse_split("§4ab", "cd", '§').into_iter()),
+            merged(SpanIter::new("§4abcd"))
+        );
+        assert_eq!(
+            parse_split("§4ab", "cd", '§'),
+            vec![
+                Span::new_styled("ab", Color::DarkRed, Styles::empty()),
+                Span::new_styled("cd", Color::DarkRed, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_trailing_codes() {
+        let mut iter = SpanIter::new("§4ab§l§o");
+        iter.by_ref().for_each(drop);
+
+        assert_eq!(
+            iter.state(),
+            (Color::DarkRed, Styles::BOLD | Styles::ITALIC)
+        );
+    }
+
+    #[test]
+    fn starts_with_initial_state() {
+        assert_eq!(SpanIter::new("").state(), (Color::White, Styles::empty()));
+        assert_eq!(
+            SpanIter::new("§cab")
+                .with_initial_state(Color::Gold, Styles::BOLD)
+                .state(),
+            (Color::Gold, Styles::BOLD)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn random_splits(s in strings_of(&['§', '&', 'a', '4', 'L', 'm', 'r', 'z', ' ', 'é'])) {
+            for (idx, _) in s.char_indices().skip(1) {
+                let (left, right) = s.split_at(idx);
+                // A code split across the fragments would be text in both
+                if left.ends_with('§') {
+                    continue;
+                }
+
+                assert_eq!(
+                    merged(parse_split(left, right, '§').into_iter()),
+                    merged(SpanIter::new(&s)),
+                    "{:?} split at {}",
+                    s,
+                    idx
+                );
+            }
+        }
+    }
+}
+
+mod canonicalize {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::{hash_map::DefaultHasher, HashSet};
+    use std::hash::{Hash, Hasher};
+
+    fn hash(span: &Span) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        span.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn white_without_styles_is_plain() {
+        let styled = Span::new_styled("text", Color::White, Styles::empty());
+        let plain = Span::new_plain("text");
+
+        assert_ne!(styled, plain);
+        assert_eq!(styled.canonicalize(), plain);
+        assert_eq!(hash(&styled.canonicalize()), hash(&plain));
+        assert_eq!(plain.canonicalize(), plain);
+    }
+
+    #[test]
+    fn dedup_in_hash_set() {
+        let spans = [
+            Span::new_plain("text"),
+            Span::new_styled("text", Color::White, Styles::empty()),
+            Span::new_styled("text", Color::Rgb(255, 255, 255), Styles::empty()),
+            Span::new_styled("text", Color::Rgb(255, 255, 254), Styles::empty()),
+        ];
+        let set: HashSet<Span> = spans.iter().map(|span| span.canonicalize()).collect();
+
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn span_kind_picked_for_text() {
+        assert_eq!(
+            Span::new_styled("  ", Color::Red, Styles::STRIKETHROUGH).canonicalize(),
+            Span::new_strikethrough_whitespace("  ", Color::Red, Styles::STRIKETHROUGH)
+        );
+        assert_eq!(
+            Span::new_strikethrough_whitespace("hi", Color::Red, Styles::STRIKETHROUGH)
+                .canonicalize(),
+            Span::new_styled("hi", Color::Red, Styles::STRIKETHROUGH)
+        );
+    }
+
+    #[test]
+    fn urls_and_empty_spans() {
+        let url = Span::new_url("https://example.com", Color::White, Styles::empty());
+
+        assert_eq!(url.canonicalize(), url);
+        assert_eq!(
+            Span::new_url(
+                "https://example.com",
+                Color::Rgb(85, 255, 255),
+                Styles::BOLD
+            )
+            .canonicalize(),
+            Span::new_url("https://example.com", Color::Aqua, Styles::BOLD)
+        );
+
+        for span in [
+            Span::new_styled("", Color::Gold, Styles::BOLD),
+            Span::new_strikethrough_whitespace("", Color::Red, Styles::STRIKETHROUGH),
+            Span::new_url("", Color::Gray, Styles::empty()),
+        ] {
+            assert_eq!(span.canonicalize(), Span::new_plain(""));
+        }
+    }
+
+    #[test]
+    fn parser_output_is_canonical() {
+        for s in SERVER_MOTDS {
+            for span in SpanIter::new(s).with_hex_colors(true).skip_empty() {
+                assert_eq!(span.canonicalize(), span);
+            }
+        }
+    }
+}
+
+mod background {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn spans_bg(s: &str) -> Vec<Span<'_>> {
+        SpanIter::new(s).with_background_prefix(Some('^')).collect()
+    }
+
+    fn with_bg(span: Span<'_>, background: Color) -> Span<'_> {
+        span.with_background(Some(background))
+    }
+
+    #[test]
+    fn off_by_default() {
+        assert_eq!(spans("§^4hi"), vec![Span::new_plain("§^4hi")]);
+        assert_eq!(
+            Span::new_styled("hi", Color::Red, Styles::BOLD).background(),
+            None
+        );
+    }
+
+    #[test]
+    fn background_codes() {
+        assert_eq!(
+            spans_bg("§^4§ewarn§lbold§^rafter"),
+            vec![
+                with_bg(
+                    Span::new_styled("warn", Color::Yellow, Styles::empty()),
+                    Color::DarkRed
+                ),
+                with_bg(
+                    Span::new_styled("bold", Color::Yellow, Styles::BOLD),
+                    Color::DarkRed
+                ),
+                Span::new_styled("after", Color::Yellow, Styles::BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    fn color_codes_keep_it_and_reset_clears_it() {
+        assert_eq!(
+            spans_bg("§^Ba§cb§rc"),
+            vec![
+                with_bg(Span::new_plain("a"), Color::Aqua),
+                with_bg(
+                    Span::new_styled("b", Color::Red, Styles::empty()),
+                    Color::Aqua
+                ),
+                Span::new_plain("c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_background_codes_are_text() {
+        assert_eq!(spans_bg("§^zx"), vec![Span::new_plain("§^zx")]);
+        assert_eq!(spans_bg("a§^"), vec![Span::new_plain("a§^")]);
+    }
+
+    #[test]
+    fn prefix_takes_priority() {
+        assert_eq!(
+            SpanIter::new("§r4a§rrb")
+                .with_background_prefix(Some('r'))
+                .collect::<Vec<_>>(),
+            vec![
+                with_bg(Span::new_plain("a"), Color::DarkRed),
+                Span::new_plain("b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn always_styled() {
+        assert_eq!(
+            spans_bg("§^0§m  "),
+            vec![Span::Styled {
+                text: "  ",
+                color: Color::White,
+                styles: Styles::STRIKETHROUGH,
+                background: Some(Color::Black),
+            }]
+        );
+        assert_eq!(
+            SpanIter::new("§^0https://example.com")
+                .with_background_prefix(Some('^'))
+                .with_url_detection(true)
+                .collect::<Vec<_>>(),
+            vec![with_bg(
+                Span::new_plain("https://example.com"),
+                Color::Black
+            )]
+        );
+    }
+
+    #[test]
+    fn kept_when_split() {
+        assert_eq!(
+            SpanIter::new("§^9a\nb")
+                .with_background_prefix(Some('^'))
+                .split_on_newline(true)
+                .collect::<Vec<_>>(),
+            vec![
+                with_bg(Span::new_plain("a\n"), Color::Blue),
+                with_bg(Span::new_plain("b"), Color::Blue),
+            ]
+        );
+    }
+
+    #[test]
+    fn removing_it_picks_the_kind() {
+        let span = Span::new_strikethrough_whitespace(" ", Color::Red, Styles::STRIKETHROUGH);
+
+        assert_eq!(with_bg(span, Color::Gray).with_background(None), span);
+        assert_eq!(span.with_background(None), span);
+    }
+
+    #[test]
+    fn canonicalize_keeps_it() {
+        assert_eq!(
+            with_bg(Span::new_plain("x"), Color::Rgb(0xff, 0x55, 0x55)).canonicalize(),
+            with_bg(Span::new_plain("x"), Color::Red)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_and_cow_spans_keep_it() {
+        let span = with_bg(
+            Span::new_styled("x", Color::Gold, Styles::ITALIC),
+            Color::DarkGray,
+        );
+
+        assert_eq!(span.into_owned().as_span(), span);
+        assert_eq!(mc_legacy_formatting::CowSpan::from(span).as_span(), span);
+        assert_eq!(
+            span.map_text(|_| "yy".into()).into_owned().as_span(),
+            with_bg(
+                Span::new_styled("yy", Color::Gold, Styles::ITALIC),
+                Color::DarkGray
+            )
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn html_background_color() {
+        use mc_legacy_formatting::to_html;
+
+        let span = with_bg(
+            Span::new_styled("Sign", Color::Black, Styles::empty()),
+            Color::Aqua,
+        );
+
+        assert_eq!(
+            to_html(&[span, Span::new_plain("!")]),
+            "<span style=\"color: #000000; background-color: #153f3f\">Sign</span>\
+             <span style=\"color: #ffffff\">!</span>"
+        );
+        assert_eq!(
+            to_html(&spans_bg("§^b§0Sign§^r§f!")),
+            to_html(&[span, Span::new_plain("!")])
+        );
+    }
+}
+
+mod custom_codes {
+    use super::*;
+    use mc_legacy_formatting::CustomAction;
+    use pretty_assertions::assert_eq;
+
+    fn extra(c: char, modifier: char) -> Option<CustomAction> {
+        match (c, modifier) {
+            ('x', 'a') => Some(CustomAction::Set(Color::Gold, Styles::BOLD)),
+            ('x', 'b') => Some(CustomAction::Color(Color::Blue)),
+            ('x', 'c') => Some(CustomAction::Style(Styles::ITALIC)),
+            ('x', 'r') => Some(CustomAction::Reset),
+            ('y', '★') => Some(CustomAction::Color(Color::Yellow)),
+            _ => None,
+        }
+    }
+
+    fn spans_custom(s: &str) -> Vec<Span<'_>> {
+        SpanIter::new(s)
+            .with_start_char('&')
+            .with_custom_code(Some(extra))
+            .collect()
+    }
+
+    #[test]
+    fn bold_gold() {
+        assert_eq!(
+            spans_custom("&xaextra-style a"),
+            vec![Span::new_styled("extra-style a", Color::Gold, Styles::BOLD)]
+        );
+    }
+
+    #[test]
+    fn actions() {
+        assert_eq!(
+            spans_custom("&2&lone &xbtwo &xcthree &xrfour"),
+            vec![
+                Span::new_styled("one ", Color::DarkGreen, Styles::BOLD),
+                Span::new_styled("two ", Color::Blue, Styles::empty()),
+                Span::new_styled("three ", Color::Blue, Styles::ITALIC),
+                Span::new_plain("four"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ends_a_span_of_text() {
+        assert_eq!(
+            spans_custom("&6gold&xa!&y★yellow"),
+            vec![
+                Span::new_styled("gold", Color::Gold, Styles::empty()),
+                Span::new_styled("!", Color::Gold, Styles::BOLD),
+                Span::new_styled("yellow", Color::Yellow, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unrecognized_codes_are_text() {
+        assert_eq!(spans_custom("&xz"), vec![Span::new_plain("&xz")]);
+        assert_eq!(spans_custom("&x"), vec![Span::new_plain("&x")]);
+        assert_eq!(
+            spans_custom("&6a&xzb"),
+            vec![Span::new_styled("a&xzb", Color::Gold, Styles::empty())]
+        );
+    }
+
+    #[test]
+    fn built_in_codes_take_priority() {
+        // `a` is a color code, so `b` is text rather than part of a custom code
+        fn shadowed(_: char, _: char) -> Option<CustomAction> {
+            Some(CustomAction::Reset)
+        }
+
+        assert_eq!(
+            SpanIter::new("§ab")
+                .with_custom_code(Some(shadowed))
+                .collect::<Vec<_>>(),
+            vec![Span::new_styled("b", Color::Green, Styles::empty())]
+        );
+    }
+
+    #[test]
+    fn no_hook() {
+        assert_eq!(
+            SpanIter::new("&xatext")
+                .with_start_char('&')
+                .with_custom_code(None)
+                .collect::<Vec<_>>(),
+            vec![Span::new_plain("&xatext")]
+        );
+    }
+}
+
+mod target_version {
+    use super::*;
+    use mc_legacy_formatting::TargetVersion;
+    use pretty_assertions::assert_eq;
+
+    fn spans_for(s: &str, version: TargetVersion) -> Vec<Span<'_>> {
+        SpanIter::new(s).with_target_version(version).collect()
+    }
+
+    #[test]
+    fn old_java_and_bedrock_colors_keep_styles() {
+        for version in [TargetVersion::Java(5), TargetVersion::Bedrock] {
+            assert_eq!(
+                spans_for("§l§nBold §6gold §rplain", version),
+                vec![
+                    Span::new_styled("Bold ", Color::White, Styles::BOLD | Styles::UNDERLINED),
+                    Span::new_styled("gold ", Color::Gold, Styles::BOLD | Styles::UNDERLINED),
+                    Span::new_plain("plain"),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn no_hex_colors_before_1_16() {
+        for version in [
+            TargetVersion::Java(5),
+            TargetVersion::Java(8),
+            TargetVersion::Java(15),
+            TargetVersion::Bedrock,
+        ] {
+            assert_eq!(
+                spans_for("§#ff8800orange", version),
+                vec![Span::new_plain("§#ff8800orange")]
+            );
+        }
+    }
+
+    #[test]
+    fn java_8_colors_reset_styles() {
+        assert_eq!(
+            spans_for("§l§nBold §6gold", TargetVersion::Java(8)),
+            vec![
+                Span::new_styled("Bold ", Color::White, Styles::BOLD | Styles::UNDERLINED),
+                Span::new_styled("gold", Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn java_16_has_hex_colors() {
+        let s = "§l§nBold §6gold §x§f§f§8§8§0§0orange";
+
+        assert_eq!(
+            spans_for(s, TargetVersion::Java(16)),
+            vec![
+                Span::new_styled("Bold ", Color::White, Styles::BOLD | Styles::UNDERLINED),
+                Span::new_styled("gold ", Color::Gold, Styles::empty()),
+                Span::new_styled("orange", Color::Rgb(255, 136, 0), Styles::empty()),
+            ]
+        );
+        assert_eq!(
+            spans_for(s, TargetVersion::Java(16)),
+            SpanIter::new(s).with_hex_colors(true).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn last_hex_setting_wins() {
+        let s = "§#ff8800orange";
+        let hex = vec![Span::new_styled(
+            "orange",
+            Color::Rgb(255, 136, 0),
+            Styles::empty(),
+        )];
+
+        assert_eq!(
+            SpanIter::new(s)
+                .with_target_version(TargetVersion::Java(8))
+                .with_hex_colors(true)
+                .collect::<Vec<_>>(),
+            hex
+        );
+        assert_eq!(
+            SpanIter::new(s)
+                .with_hex_colors(true)
+                .with_target_version(TargetVersion::Java(8))
+                .collect::<Vec<_>>(),
+            vec![Span::new_plain(s)]
+        );
+    }
+
+    #[test]
+    fn versions_only_differ_after_colors() {
+        for motd in SERVER_MOTDS {
+            let old = spans_for(motd, TargetVersion::Java(5));
+            let new = spans_for(motd, TargetVersion::Java(16));
+
+            assert_eq!(
+                old.iter().map(|span| span.text()).collect::<String>(),
+                new.iter().map(|span| span.text()).collect::<String>()
+            );
+            for (old, new) in old.iter().zip(&new) {
+                assert_eq!(old.color(), new.color());
+                assert!(old.styles().contains(new.styles()));
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "heapless")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{collect_spans, collect_spans_from, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn exact_fit() {
+    let spans = collect_spans::<3>("§aOne §bTwo §cThree").unwrap();
+
+    assert_eq!(
+        spans,
+        [
+            Span::new_styled("One ", Color::Green, Styles::empty()),
+            Span::new_styled("Two ", Color::Aqua, Styles::empty()),
+            Span::new_styled("Three", Color::Red, Styles::empty()),
+        ]
+    );
+    assert!(collect_spans::<0>("").unwrap().is_empty());
+}
+
+#[test]
+fn overflow() {
+    let s = "§aOne §bTwo §l§cThree";
+    let err = collect_spans::<2>(s).unwrap_err();
+
+    assert_eq!(
+        err.spans,
+        [
+            Span::new_styled("One ", Color::Green, Styles::empty()),
+            Span::new_styled("Two ", Color::Aqua, Styles::empty()),
+        ]
+    );
+    assert_eq!(err.consumed, 14);
+    assert_eq!(&s[err.consumed..], "§l§cThree");
+    assert_eq!(err.to_string(), "more than 2 spans (stopped at byte 14)");
+}
+
+#[test]
+fn zero_capacity() {
+    let err = collect_spans::<0>("§6Gold").unwrap_err();
+
+    assert!(err.spans.is_empty());
+    assert_eq!(err.consumed, 0);
+    assert_eq!(
+        err.remainder.collect::<Vec<_>>(),
+        vec![Span::new_styled("Gold", Color::Gold, Styles::empty())]
+    );
+}
+
+#[test]
+fn continue_from_remainder() {
+    let s = "§aOne §bTwo §l§cThree";
+    let err = collect_spans::<2>(s).unwrap_err();
+
+    // Formatting carries over into the spans after the first chunk
+    assert_eq!(
+        collect_spans_from::<2>(err.remainder).unwrap(),
+        [Span::new_styled("Three", Color::Red, Styles::empty())]
+    );
+
+    // Restarting from `consumed` with the state of the last span works too
+    let last = err.spans.last().unwrap();
+    let restarted =
+        SpanIter::new(&s[err.consumed..]).with_initial_state(last.color(), last.styles());
+    assert_eq!(
+        collect_spans_from::<2>(restarted).unwrap(),
+        [Span::new_styled("Three", Color::Red, Styles::empty())]
+    );
+}
+
+#[test]
+fn chunks_match_span_iter() {
+    for motd in SERVER_MOTDS {
+        let mut span_iter = SpanIter::new(motd).with_url_detection(true);
+        let mut collected = Vec::new();
+        let mut consumed = 0;
+
+        loop {
+            match collect_spans_from::<4>(span_iter) {
+                Ok(spans) => {
+                    collected.extend(spans);
+                    break;
+                }
+                Err(err) => {
+                    assert!(err.consumed > consumed, "{:?}", motd);
+                    consumed = err.consumed;
+
+                    collected.extend(err.spans);
+                    span_iter = err.remainder;
+                }
+            }
+        }
+
+        assert_eq!(
+            collected,
+            SpanIter::new(motd)
+                .with_url_detection(true)
+                .collect::<Vec<_>>()
+        );
+    }
+}
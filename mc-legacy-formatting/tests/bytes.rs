@@ -0,0 +1,46 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{span_iter_bytes, Color, OwnedSpan, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn valid_utf8_matches_str() {
+    for s in SERVER_MOTDS {
+        assert_eq!(
+            span_iter_bytes(s.as_bytes()).collect::<Vec<_>>(),
+            spans(s)
+                .into_iter()
+                .map(OwnedSpan::from)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn invalid_bytes_are_replaced() {
+    // A lone continuation byte and a truncated two-byte sequence
+    let bytes = b"\xc2\xa76Gold\x80 \xc2\xa7lbold\xc3";
+
+    assert_eq!(
+        span_iter_bytes(bytes).collect::<Vec<_>>(),
+        vec![
+            OwnedSpan::new_styled("Gold\u{FFFD} ", Color::Gold, Styles::empty()),
+            OwnedSpan::new_styled("bold\u{FFFD}", Color::Gold, Styles::BOLD),
+        ]
+    );
+}
+
+#[test]
+fn invalid_start_char_is_text() {
+    // `§` with its first byte missing isn't a start char
+    let bytes = b"\xa7cnot red";
+
+    assert_eq!(
+        span_iter_bytes(bytes).collect::<Vec<_>>(),
+        vec![OwnedSpan::new_plain("\u{FFFD}cnot red")]
+    );
+}
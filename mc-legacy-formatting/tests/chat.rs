@@ -0,0 +1,211 @@
+#![cfg(feature = "json")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    flatten_chat_component, to_chat_component, Color, OwnedSpan, Span, SpanIter, Styles,
+};
+use pretty_assertions::assert_eq;
+
+/// A description component in the shape that Paper sends for an MOTD configured
+/// with nested components
+#[test]
+fn nested_description() {
+    let component: serde_json::Value = serde_json::from_str(
+        r#"{
+            "extra": [
+                {"bold": true, "color": "gold", "text": "Mine"},
+                {"bold": true, "color": "gray", "text": "Superior "},
+                {
+                    "color": "dark_gray",
+                    "extra": [
+                        {"color": "green", "text": "1.7-1.16 "},
+                        {"bold": true, "extra": [{"text": "SUPPORT"}], "text": ""},
+                        "\n"
+                    ],
+                    "text": "» "
+                },
+                {"italic": true, "text": "§bJoin §rnow!"}
+            ],
+            "text": ""
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        flatten_chat_component(&component),
+        vec![
+            OwnedSpan::new_styled("Mine", Color::Gold, Styles::BOLD),
+            OwnedSpan::new_styled("Superior ", Color::Gray, Styles::BOLD),
+            OwnedSpan::new_styled("» ", Color::DarkGray, Styles::empty()),
+            OwnedSpan::new_styled("1.7-1.16 ", Color::Green, Styles::empty()),
+            OwnedSpan::new_styled("SUPPORT", Color::DarkGray, Styles::BOLD),
+            OwnedSpan::new_styled("\n", Color::DarkGray, Styles::empty()),
+            OwnedSpan::new_styled("Join ", Color::Aqua, Styles::empty()),
+            OwnedSpan::new_plain("now!"),
+        ]
+    );
+}
+
+/// A description in the shape that Hypixel sends, where the top-level `text`
+/// is empty and the whole MOTD is in `extra`
+#[test]
+fn description_only_in_extra() {
+    let component: serde_json::Value = serde_json::from_str(
+        r#"{
+            "extra": [
+                {
+                    "extra": [
+                        {"color": "green", "text": "Hypixel Network "},
+                        {"color": "red", "text": "[1.8-1.20]"}
+                    ],
+                    "text": ""
+                },
+                "\n§b§lNEW GAME"
+            ],
+            "text": ""
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(component["text"], "");
+    assert_eq!(
+        flatten_chat_component(&component),
+        vec![
+            OwnedSpan::new_styled("Hypixel Network ", Color::Green, Styles::empty()),
+            OwnedSpan::new_styled("[1.8-1.20]", Color::Red, Styles::empty()),
+            OwnedSpan::new_plain("\n"),
+            OwnedSpan::new_styled("NEW GAME", Color::Aqua, Styles::BOLD),
+        ]
+    );
+}
+
+#[test]
+fn plain_string_component() {
+    let component = serde_json::json!("§4§lRed and bold §7gray");
+
+    assert_eq!(
+        flatten_chat_component(&component),
+        vec![
+            OwnedSpan::new_styled("Red and bold ", Color::DarkRed, Styles::BOLD),
+            OwnedSpan::new_styled("gray", Color::Gray, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn array_component_inherits_from_first() {
+    let component = serde_json::json!([
+        {"text": "first ", "color": "aqua"},
+        {"text": "second ", "underlined": true},
+        {"text": "third", "color": "red"}
+    ]);
+
+    assert_eq!(
+        flatten_chat_component(&component),
+        vec![
+            OwnedSpan::new_styled("first ", Color::Aqua, Styles::empty()),
+            OwnedSpan::new_styled("second ", Color::Aqua, Styles::UNDERLINED),
+            OwnedSpan::new_styled("third", Color::Red, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn hex_colors() {
+    let component = serde_json::json!({
+        "text": "orange ",
+        "color": "#ff8800",
+        "extra": [
+            {"text": "red ", "color": "#FF5555"},
+            {"text": "inherited", "color": "#nothex"}
+        ]
+    });
+
+    assert_eq!(
+        flatten_chat_component(&component),
+        vec![
+            OwnedSpan::new_styled("orange ", Color::Rgb(255, 136, 0), Styles::empty()),
+            OwnedSpan::new_styled("red ", Color::Red, Styles::empty()),
+            OwnedSpan::new_styled("inherited", Color::Rgb(255, 136, 0), Styles::empty()),
+        ]
+    );
+}
+
+mod to_chat_component {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// The component a Spigot server sends for `§6Gold §lbold` (with the
+    /// fields sorted, since `serde_json` doesn't keep their order anyway)
+    const VANILLA: &str = r#"{
+        "extra": [
+            {
+                "bold": false,
+                "color": "gold",
+                "italic": false,
+                "obfuscated": false,
+                "strikethrough": false,
+                "text": "Gold ",
+                "underlined": false
+            },
+            {
+                "bold": true,
+                "color": "gold",
+                "italic": false,
+                "obfuscated": false,
+                "strikethrough": false,
+                "text": "bold",
+                "underlined": false
+            }
+        ],
+        "text": ""
+    }"#;
+
+    #[test]
+    fn matches_vanilla() {
+        let spans: Vec<Span> = SpanIter::new("§6Gold §lbold").collect();
+        let expected: serde_json::Value = serde_json::from_str(VANILLA).unwrap();
+
+        assert_eq!(to_chat_component(&spans), expected);
+    }
+
+    #[test]
+    fn explicit_false_overrides_inheritance() {
+        let spans = [
+            Span::new_plain("plain"),
+            Span::new_styled("", Color::Red, Styles::BOLD),
+            Span::new_styled("hex", Color::Rgb(255, 136, 0), Styles::ITALIC),
+        ];
+        let mut component = to_chat_component(&spans);
+
+        // Styles set on the root would otherwise be inherited by every span
+        component["bold"] = true.into();
+        component["color"] = "red".into();
+
+        assert_eq!(component["extra"].as_array().unwrap().len(), 2);
+        assert_eq!(component["extra"][1]["color"], "#ff8800");
+        assert_eq!(
+            flatten_chat_component(&component),
+            vec![
+                OwnedSpan::new_plain("plain"),
+                OwnedSpan::new_styled("hex", Color::Rgb(255, 136, 0), Styles::ITALIC),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        for s in SERVER_MOTDS {
+            let spans: Vec<OwnedSpan> =
+                SpanIter::new(s).skip_empty().map(OwnedSpan::from).collect();
+            if spans.iter().any(|span| span.as_span().text().contains('§')) {
+                continue;
+            }
+
+            assert_eq!(flatten_chat_component(&to_chat_component(&spans)), spans);
+        }
+    }
+}
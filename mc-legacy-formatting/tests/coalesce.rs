@@ -0,0 +1,176 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use std::borrow::Cow;
+
+use mc_legacy_formatting::{
+    coalesce, to_legacy_string, Color, CowSpan, DisplaySpans, Span, SpanIter, Styles,
+};
+use pretty_assertions::assert_eq;
+
+fn coalesced(s: &str) -> Vec<CowSpan<'_>> {
+    coalesce(SpanIter::new(s)).collect()
+}
+
+#[test]
+fn repeated_codes() {
+    assert_eq!(
+        coalesced("§6a§6b§cc"),
+        vec![
+            CowSpan::new_styled("ab", Color::Gold, Styles::empty()),
+            CowSpan::new_styled("c", Color::Red, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn different_backgrounds_arent_merged() {
+    let spans: Vec<CowSpan> =
+        coalesce(SpanIter::new("§6a§^1b§6c§^rd").with_background_prefix(Some('^'))).collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            CowSpan::new_styled("a", Color::Gold, Styles::empty()),
+            CowSpan::Styled {
+                text: Cow::Owned("bc".to_string()),
+                color: Color::Gold,
+                styles: Styles::empty(),
+                background: Some(Color::DarkBlue),
+            },
+            CowSpan::new_styled("d", Color::Gold, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn unmerged_spans_stay_borrowed() {
+    let spans = coalesced("§6a§6b§cc");
+
+    assert!(matches!(
+        spans[0],
+        CowSpan::Styled {
+            text: Cow::Owned(_),
+            ..
+        }
+    ));
+    assert!(matches!(
+        spans[1],
+        CowSpan::Styled {
+            text: Cow::Borrowed(_),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn plain() {
+    assert_eq!(coalesced("a§rb§fc"), vec![CowSpan::new_plain("abc")]);
+}
+
+#[test]
+fn strikethrough_whitespace() {
+    assert_eq!(
+        coalesced("§8§m  §8§m   "),
+        vec![CowSpan::new_strikethrough_whitespace(
+            "     ",
+            Color::DarkGray,
+            Styles::STRIKETHROUGH
+        )]
+    );
+    assert_eq!(
+        coalesced("§m  §mtext"),
+        vec![CowSpan::new_styled(
+            "  text",
+            Color::White,
+            Styles::STRIKETHROUGH
+        )]
+    );
+}
+
+#[test]
+fn urls_are_not_merged() {
+    let spans: Vec<CowSpan> =
+        coalesce(SpanIter::new("§6see example.com§6 now").with_url_detection(true)).collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            CowSpan::new_styled("see ", Color::Gold, Styles::empty()),
+            CowSpan::new_url("example.com", Color::Gold, Styles::empty()),
+            CowSpan::new_styled(" now", Color::Gold, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn contiguous_text_is_borrowed_from_buffer() {
+    let s = "§6one\ntwo\nthree§6!";
+    let spans: Vec<CowSpan> = coalesce(SpanIter::new(s).split_on_newline(true))
+        .with_buffer(s)
+        .collect();
+
+    assert_eq!(
+        spans,
+        vec![CowSpan::new_styled(
+            "one\ntwo\nthree!",
+            Color::Gold,
+            Styles::empty()
+        )]
+    );
+    // The codes before the `!` are in the way
+    assert!(matches!(
+        spans[0],
+        CowSpan::Styled {
+            text: Cow::Owned(_),
+            ..
+        }
+    ));
+
+    let spans: Vec<CowSpan> = coalesce(SpanIter::new(s).split_on_newline(true).take(3))
+        .with_buffer(s)
+        .collect();
+    assert!(
+        matches!(&spans[0], CowSpan::Styled { text: Cow::Borrowed(text), .. } if *text == "one\ntwo\nthree")
+    );
+}
+
+#[test]
+fn spans_from_elsewhere() {
+    let spans = [
+        Span::new_styled("a", Color::Gold, Styles::empty()),
+        Span::new_styled("b", Color::Gold, Styles::empty()),
+    ];
+
+    assert_eq!(
+        coalesce(spans.iter().copied())
+            .with_buffer("ab")
+            .collect::<Vec<_>>(),
+        vec![CowSpan::new_styled("ab", Color::Gold, Styles::empty())]
+    );
+}
+
+#[test]
+fn fixtures_render_the_same() {
+    for s in SERVER_MOTDS {
+        let spans: Vec<Span> = SpanIter::new(s).collect();
+        let coalesced = coalesced(s);
+        let borrowed: Vec<Span> = coalesced.iter().map(CowSpan::as_span).collect();
+
+        assert!(coalesced.len() <= spans.len());
+        assert_eq!(
+            to_legacy_string(&coalesced, '§'),
+            to_legacy_string(&spans, '§')
+        );
+        assert_eq!(
+            DisplaySpans::new(borrowed.iter().copied()).to_string(),
+            DisplaySpans::new(spans.iter().copied()).to_string()
+        );
+    }
+
+    assert_eq!(SpanIter::new(PURPLE_WTF).count(), 19);
+    assert_eq!(coalesced(PURPLE_WTF).len(), 17);
+}
@@ -0,0 +1,431 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    apply_code, classify_code, code_ranges, contains_formatting, first_code_offset, raw_tokens,
+    validate, CodeKind, Color, ParseError, SpanIter, Styles, Token, COLOR_CODES, RESET_CODE,
+    STYLE_CODES,
+};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+fn codes(s: &str) -> Vec<(std::ops::Range<usize>, CodeKind)> {
+    code_ranges(s, '§').collect()
+}
+
+#[test]
+fn consecutive_codes() {
+    assert_eq!(
+        codes("§1§e§d§lhi §r§o"),
+        vec![
+            (0..3, CodeKind::Color(Color::DarkBlue)),
+            (3..6, CodeKind::Color(Color::Yellow)),
+            (6..9, CodeKind::Color(Color::LightPurple)),
+            (9..12, CodeKind::Style(Styles::BOLD)),
+            (15..18, CodeKind::Reset),
+            (18..21, CodeKind::Style(Styles::ITALIC)),
+        ]
+    );
+}
+
+#[test]
+fn trailing_lone_start_char() {
+    assert_eq!(
+        codes("§ahi§"),
+        vec![
+            (0..3, CodeKind::Color(Color::Green)),
+            (5..7, CodeKind::Invalid)
+        ]
+    );
+}
+
+#[test]
+fn multi_byte_offsets() {
+    let s = "⌜§4—§§x";
+    assert_eq!(
+        codes(s),
+        vec![
+            (3..6, CodeKind::Color(Color::DarkRed)),
+            (9..13, CodeKind::Invalid),
+        ]
+    );
+    assert_eq!(&s[3..6], "§4");
+    assert_eq!(&s[9..13], "§§");
+}
+
+#[test]
+fn custom_start_char() {
+    assert_eq!(
+        code_ranges("&6It's a lot easier to type &b& &6than &b§", '&').collect::<Vec<_>>(),
+        vec![
+            (0..2, CodeKind::Color(Color::Gold)),
+            (28..30, CodeKind::Color(Color::Aqua)),
+            (30..32, CodeKind::Invalid),
+            (32..34, CodeKind::Color(Color::Gold)),
+            (39..41, CodeKind::Color(Color::Aqua)),
+        ]
+    );
+}
+
+#[test]
+fn never_overlap_span_ranges() {
+    for s in [
+        "§4§l§m⌜--------------------⌝\n   §4§lBLAZE§b-§6§lGAMING§b Network",
+        "§§§§§this has no format§ting codes§",
+        "§f§f §6§m---§e§m---§f§m---§f   §e§lLemon§f§lCloud§f §6[1.7-1.16] §f",
+        "§5§m                  §6>§7§l§6§l>§6§l[§5§l§oPurple §R §D§LRESET!",
+    ] {
+        let span_ranges: Vec<_> = SpanIter::new(s).with_ranges().map(|(r, _)| r).collect();
+
+        for (code, kind) in codes(s) {
+            if kind == CodeKind::Invalid {
+                continue;
+            }
+
+            assert!(
+                span_ranges
+                    .iter()
+                    .all(|span| code.end <= span.start || span.end <= code.start),
+                "{:?} overlaps a span in {:?}",
+                code,
+                s
+            );
+        }
+    }
+}
+
+#[test]
+fn span_ranges_match_text() {
+    let s = "§8Welcome to §6§lAmazing Minecraft Server\n§8§oYour hub for §d§op2w §8§ogameplay!";
+
+    for (range, span) in SpanIter::new(s).with_ranges() {
+        assert_eq!(&s[range], span.text());
+    }
+
+    assert_eq!(
+        SpanIter::new(s)
+            .with_ranges()
+            .map(|(r, _)| r)
+            .collect::<Vec<_>>(),
+        vec![3..14, 20..45, 51..64, 70..74, 80..89]
+    );
+    assert_eq!(spans(s).len(), 5);
+}
+
+#[test]
+fn apply_code_matches_parser() {
+    let start = (Color::Gold, Styles::BOLD);
+
+    for c in (' '..='~').chain(['§', 'é']) {
+        let (mut color, mut styles) = start;
+        let recognized = apply_code(c, &mut color, &mut styles);
+
+        let s = format!("§6§l§{}x", c);
+        let span = SpanIter::new(&s).last().unwrap();
+
+        if recognized {
+            assert_eq!(
+                (span.text(), span.color(), span.styles()),
+                ("x", color, styles),
+                "{:?}",
+                c
+            );
+        } else {
+            assert_eq!((color, styles), start, "{:?}", c);
+            assert_eq!(span.text(), format!("§{}x", c), "{:?}", c);
+        }
+    }
+}
+
+#[test]
+fn classify_code_known_values() {
+    for (c, expected) in [
+        ('0', CodeKind::Color(Color::Black)),
+        ('9', CodeKind::Color(Color::Blue)),
+        ('a', CodeKind::Color(Color::Green)),
+        ('F', CodeKind::Color(Color::White)),
+        ('k', CodeKind::Style(Styles::RANDOM)),
+        ('L', CodeKind::Style(Styles::BOLD)),
+        ('o', CodeKind::Style(Styles::ITALIC)),
+        ('r', CodeKind::Reset),
+        ('R', CodeKind::Reset),
+        ('g', CodeKind::Invalid),
+        ('x', CodeKind::Invalid),
+        (' ', CodeKind::Invalid),
+        ('§', CodeKind::Invalid),
+        ('é', CodeKind::Invalid),
+    ] {
+        assert_eq!(classify_code(c), expected, "{:?}", c);
+    }
+}
+
+#[test]
+fn classify_code_exhaustive() {
+    for c in '0'..='z' {
+        let lower = c.to_ascii_lowercase();
+        let expected = if let Some(idx) = COLOR_CODES.iter().position(|&code| code == lower) {
+            CodeKind::Color(NAMED_COLORS[idx])
+        } else if let Some(idx) = STYLE_CODES.iter().position(|&code| code == lower) {
+            CodeKind::Style(Styles::from_bits(1 << idx).unwrap())
+        } else if lower == RESET_CODE {
+            CodeKind::Reset
+        } else {
+            CodeKind::Invalid
+        };
+
+        assert_eq!(classify_code(c), expected, "{:?}", c);
+    }
+
+    // Only ASCII letters have an uppercase form that's accepted
+    assert_eq!(classify_code('Ł'), CodeKind::Invalid);
+    assert_eq!(classify_code('§'), CodeKind::Invalid);
+}
+
+#[test]
+fn classify_code_matches_code_ranges() {
+    for c in (' '..='~').chain(['§', 'é']) {
+        let s = format!("§{}", c);
+        assert_eq!(codes(&s), vec![(0..s.len(), classify_code(c))], "{:?}", c);
+    }
+}
+
+mod fake_codes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn assert_no_formatting(s: &str) {
+        assert!(!contains_formatting(s, '§'));
+        assert_eq!(first_code_offset(s, '§'), None);
+    }
+
+    #[test]
+    fn no_formatting_code() {
+        assert_no_formatting("this has no formatting codes");
+    }
+
+    #[test]
+    fn fake_code_at_start() {
+        assert_no_formatting("§this has no formatting codes");
+    }
+
+    #[test]
+    fn fake_code_space_at_start() {
+        assert_no_formatting("§ this has no formatting codes");
+    }
+
+    #[test]
+    fn fake_code_at_end() {
+        assert_no_formatting("this has no formatting codes§");
+    }
+
+    #[test]
+    fn fake_code_space_at_end() {
+        assert_no_formatting("this has no formatting codes §");
+    }
+
+    #[test]
+    fn fake_code_middle() {
+        assert_no_formatting("this ha§s no formatting codes");
+    }
+
+    #[test]
+    fn fake_code_space_middle() {
+        assert_no_formatting("this has no § formatting codes");
+    }
+
+    #[test]
+    fn a_bunch_of_fakes() {
+        assert_no_formatting("§§§§§this has no format§ting codes§");
+    }
+
+    #[test]
+    fn real_code_after_fakes() {
+        let s = "§§§§§this has a format§ting code §lhere";
+        assert!(contains_formatting(s, '§'));
+        assert_eq!(first_code_offset(s, '§'), Some(s.find("§l").unwrap()));
+    }
+
+    #[test]
+    fn other_start_char() {
+        assert_no_formatting("&4not with this start char");
+        assert_eq!(first_code_offset("&4with this one", '&'), Some(0));
+    }
+}
+
+#[test]
+fn contains_formatting_matches_parser() {
+    for s in SERVER_MOTDS {
+        assert!(contains_formatting(s, '§'));
+        assert!(!contains_formatting(
+            &SpanIter::new(s).stripped().to_string(),
+            '§'
+        ));
+    }
+}
+
+mod validate {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn error(byte_offset: usize, code: Option<char>) -> Result<(), ParseError> {
+        Err(ParseError { byte_offset, code })
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(validate("", '§'), Ok(()));
+        assert_eq!(validate("no codes", '§'), Ok(()));
+        assert_eq!(validate("§6§LGold§R §kx", '§'), Ok(()));
+    }
+
+    #[test]
+    fn first_invalid_code() {
+        assert_eq!(validate("§6ok §zbad §ybad", '§'), error(6, Some('z')));
+    }
+
+    #[test]
+    fn trailing_lone_start_char() {
+        assert_eq!(validate("§6text§", '§'), error(7, None));
+    }
+
+    #[test]
+    fn doubled_start_char() {
+        assert_eq!(validate("§§6", '§'), error(0, Some('§')));
+    }
+
+    #[test]
+    fn multi_byte() {
+        assert_eq!(validate("é😀&😀", '&'), error(6, Some('😀')));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            ParseError {
+                byte_offset: 3,
+                code: Some('z')
+            }
+            .to_string(),
+            "invalid formatting code 'z' at byte 3"
+        );
+        assert_eq!(
+            ParseError {
+                byte_offset: 5,
+                code: None
+            }
+            .to_string(),
+            "lone start char at byte 5"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_raw_tokens(s in strings_of(&['§', '6', 'l', 'z', ' ', 'é'])) {
+            let literal_start = raw_tokens(&s, '§').any(|token| token == Token::LiteralStart);
+
+            assert_eq!(validate(&s, '§').is_err(), literal_start, "{:?}", s);
+        }
+    }
+}
+
+mod raw_tokens {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tokens(s: &str) -> Vec<Token<'_>> {
+        raw_tokens(s, '§').collect()
+    }
+
+    #[test]
+    fn codes_are_not_folded() {
+        assert_eq!(
+            tokens("§l§6hi§R§kx"),
+            vec![
+                Token::Style(Styles::BOLD),
+                Token::Color(Color::Gold),
+                Token::Text("hi"),
+                Token::Reset,
+                Token::Style(Styles::RANDOM),
+                Token::Text("x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_start_chars() {
+        assert_eq!(
+            tokens("a§z§"),
+            vec![
+                Token::Text("a"),
+                Token::LiteralStart,
+                Token::Text("z"),
+                Token::LiteralStart,
+            ]
+        );
+    }
+
+    #[test]
+    fn start_char_after_literal_start_is_text() {
+        assert_eq!(tokens("§§6"), vec![Token::LiteralStart, Token::Text("§6")]);
+        assert_eq!(
+            tokens("§§§6x"),
+            vec![
+                Token::LiteralStart,
+                Token::Text("§"),
+                Token::Color(Color::Gold),
+                Token::Text("x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(tokens(""), vec![]);
+    }
+
+    proptest! {
+        #[test]
+        fn folding_matches_parser(
+            start_char in prop::sample::select(vec!['§', '&', '😀']),
+            s in strings_of(&['§', '&', '😀', '6', 'L', 'r', 'z', ' ', 'é']),
+        ) {
+            let mut folded: Vec<(String, Color, Styles)> = Vec::new();
+            let (mut color, mut styles) = (Color::White, Styles::empty());
+
+            for token in raw_tokens(&s, start_char) {
+                let text = match token {
+                    Token::Text(text) => text.to_string(),
+                    Token::LiteralStart => start_char.to_string(),
+                    Token::Color(c) => {
+                        color = c;
+                        styles = Styles::empty();
+                        continue;
+                    }
+                    Token::Style(style) => {
+                        styles.insert(style);
+                        continue;
+                    }
+                    Token::Reset => {
+                        color = Color::White;
+                        styles = Styles::empty();
+                        continue;
+                    }
+                };
+
+                match folded.last_mut() {
+                    Some(last) if (last.1, last.2) == (color, styles) => last.0.push_str(&text),
+                    _ => folded.push((text, color, styles)),
+                }
+            }
+
+            assert_eq!(
+                folded,
+                merged(SpanIter::new(&s).with_start_char(start_char)),
+                "{:?} with start char {:?}",
+                s,
+                start_char
+            );
+        }
+    }
+}
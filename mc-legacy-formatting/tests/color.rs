@@ -0,0 +1,298 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{apply_code, Color, Styles, COLOR_CODES, RESET_CODE, STYLE_CODES};
+
+#[test]
+fn rgb_approx_eq_named() {
+    assert!(Color::Rgb(255, 85, 85).approx_eq(&Color::Red));
+    assert!(Color::Red.approx_eq(&Color::Rgb(255, 85, 85)));
+    assert_ne!(Color::Rgb(255, 85, 85), Color::Red);
+
+    assert!(!Color::Rgb(255, 85, 86).approx_eq(&Color::Red));
+    assert!(!Color::Gold.approx_eq(&Color::Yellow));
+}
+
+#[test]
+fn named_approx_eq_is_eq() {
+    for a in NAMED_COLORS {
+        for b in NAMED_COLORS {
+            assert_eq!(a.approx_eq(&b), a == b);
+        }
+    }
+}
+
+/// `9` used to be parsed as [`Color::DarkBlue`], the same as `1`
+#[test]
+fn nine_is_blue() {
+    assert_eq!(Color::from_char('9'), Some(Color::Blue));
+    assert_eq!(Color::from_char('1'), Some(Color::DarkBlue));
+    assert_eq!(
+        mc_legacy_formatting::SpanIter::new("§9blue")
+            .next()
+            .unwrap()
+            .color(),
+        Color::Blue
+    );
+}
+
+#[test]
+fn hex_str_matches_rgb() {
+    for color in NAMED_COLORS.iter().copied().chain([Color::Rgb(18, 52, 86)]) {
+        let (r, g, b) = color.foreground_rgb();
+        assert_eq!(
+            color.foreground_hex_str(),
+            format!("#{:02x}{:02x}{:02x}", r, g, b).as_str()
+        );
+
+        let (r, g, b) = color.background_rgb();
+        assert_eq!(
+            color.background_hex_str(),
+            format!("#{:02x}{:02x}{:02x}", r, g, b).as_str()
+        );
+    }
+
+    assert_eq!(Color::Rgb(18, 52, 86).foreground_hex_str(), "#123456");
+    assert_eq!(Color::Rgb(18, 52, 86).background_hex_str(), "#040d15");
+}
+
+#[test]
+fn light_and_dark() {
+    let light: Vec<_> = NAMED_COLORS.iter().filter(|c| c.is_light()).collect();
+    assert_eq!(
+        light,
+        [
+            &Color::Gold,
+            &Color::Gray,
+            &Color::Green,
+            &Color::Aqua,
+            &Color::Red,
+            &Color::LightPurple,
+            &Color::Yellow,
+            &Color::White,
+        ]
+    );
+
+    // Luminance of exactly 128 is light, anything below is dark
+    assert!(Color::Rgb(128, 128, 128).is_light());
+    assert!(!Color::Rgb(127, 127, 127).is_light());
+}
+
+#[test]
+fn color_codes_round_trip() {
+    for (c, color) in COLOR_CODES.iter().zip(NAMED_COLORS) {
+        assert_eq!(Color::from_char(*c), Some(color), "{:?}", c);
+        assert_eq!(
+            Color::from_char(c.to_ascii_uppercase()),
+            Some(color),
+            "{:?}",
+            c
+        );
+        assert_eq!(Styles::from_char(*c), None);
+    }
+}
+
+#[test]
+fn nibbles_round_trip() {
+    for (n, color) in (0..16).zip(NAMED_COLORS) {
+        assert_eq!(Color::from_nibble(n), Some(color), "{:#x}", n);
+        assert_eq!(color.to_nibble(), Some(n), "{:?}", color);
+    }
+
+    for n in 16..=255 {
+        assert_eq!(Color::from_nibble(n), None, "{:#x}", n);
+    }
+    assert_eq!(Color::Rgb(0, 0, 0).to_nibble(), None);
+}
+
+#[test]
+fn style_codes_round_trip() {
+    let styles = [
+        Styles::RANDOM,
+        Styles::BOLD,
+        Styles::STRIKETHROUGH,
+        Styles::UNDERLINED,
+        Styles::ITALIC,
+    ];
+
+    for (c, style) in STYLE_CODES.iter().zip(styles) {
+        assert_eq!(Styles::from_char(*c), Some(style), "{:?}", c);
+        assert_eq!(
+            Styles::from_char(c.to_ascii_uppercase()),
+            Some(style),
+            "{:?}",
+            c
+        );
+        assert_eq!(Color::from_char(*c), None);
+    }
+}
+
+#[test]
+fn renderable_drops_random() {
+    assert_eq!((Styles::BOLD | Styles::RANDOM).renderable(), Styles::BOLD);
+    assert_eq!(Styles::RANDOM.renderable(), Styles::empty());
+    assert_eq!(Styles::all().renderable(), Styles::all() - Styles::RANDOM);
+    assert_eq!(Styles::empty().renderable(), Styles::empty());
+}
+
+#[test]
+fn reset_code() {
+    assert_eq!(Color::from_char(RESET_CODE), None);
+    assert_eq!(Styles::from_char(RESET_CODE), None);
+
+    let (mut color, mut styles) = (Color::Red, Styles::BOLD);
+    assert!(apply_code(RESET_CODE, &mut color, &mut styles));
+    assert_eq!((color, styles), (Color::White, Styles::empty()));
+}
+
+/// Every char that isn't one of the listed codes is invalid
+#[test]
+fn codes_are_exhaustive() {
+    for c in (' '..='~').chain(['§', 'é']) {
+        let listed = COLOR_CODES
+            .iter()
+            .chain(&STYLE_CODES)
+            .chain(&[RESET_CODE])
+            .any(|code| code.eq_ignore_ascii_case(&c));
+        let (mut color, mut styles) = (Color::White, Styles::empty());
+
+        assert_eq!(apply_code(c, &mut color, &mut styles), listed, "{:?}", c);
+    }
+}
+
+#[test]
+fn from_hex_str() {
+    assert_eq!(
+        Color::from_hex_str("#ff8800"),
+        Some(Color::Rgb(255, 136, 0))
+    );
+    assert_eq!(Color::from_hex_str("ff8800"), Some(Color::Rgb(255, 136, 0)));
+    assert_eq!(
+        Color::from_hex_str("#FF8800"),
+        Some(Color::Rgb(255, 136, 0))
+    );
+    assert_eq!(Color::from_hex_str("#123456"), Some(Color::Rgb(18, 52, 86)));
+}
+
+#[test]
+fn from_hex_str_snaps_to_named() {
+    for color in NAMED_COLORS {
+        assert_eq!(
+            Color::from_hex_str(&color.foreground_hex_str()),
+            Some(color)
+        );
+    }
+
+    assert_eq!(
+        Color::from_hex_str("#ff5556"),
+        Some(Color::Rgb(255, 85, 86))
+    );
+}
+
+#[test]
+fn from_hex_str_round_trips() {
+    for color in [
+        Color::Rgb(0, 0, 1),
+        Color::Rgb(254, 255, 255),
+        Color::Rgb(18, 52, 86),
+    ] {
+        assert_eq!(
+            Color::from_hex_str(&color.foreground_hex_str()),
+            Some(color)
+        );
+    }
+}
+
+#[test]
+fn from_hex_str_invalid() {
+    for s in [
+        "", "#", "#fff", "#ff880", "#ff88000", "##ff8800", "#gg8800", "#+f8800", "ff 880",
+        "#ff8800 ", "#ff88é",
+    ] {
+        assert_eq!(Color::from_hex_str(s), None, "{:?}", s);
+    }
+}
+
+#[test]
+fn xterm_names() {
+    assert_eq!(Color::Black.xterm_name(), "black");
+    assert_eq!(Color::DarkRed.xterm_name(), "red3");
+    assert_eq!(Color::Gold.xterm_name(), "orange1");
+    assert_eq!(Color::Gray.xterm_name(), "grey66");
+    assert_eq!(Color::White.xterm_name(), "white");
+
+    let mut names: Vec<_> = NAMED_COLORS.iter().map(|c| c.xterm_name()).collect();
+    assert!(names.iter().all(|name| !name.is_empty()));
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), 16);
+}
+
+#[test]
+fn xterm_name_of_rgb_is_nearest_named() {
+    for (color, name) in [
+        (Color::Rgb(250, 160, 10), "orange1"),
+        (Color::Rgb(20, 20, 20), "black"),
+        (Color::Rgb(18, 52, 86), "grey35"),
+    ] {
+        assert_eq!(color.xterm_name(), name);
+    }
+    assert_eq!(Color::Gold.xterm_name(), "orange1");
+    assert_eq!(Color::DarkGray.xterm_name(), "grey35");
+}
+
+#[test]
+fn css_classes() {
+    assert_eq!(Color::DarkRed.css_class(), Some("mc-dark-red"));
+    assert_eq!(Color::Gold.css_class(), Some("mc-gold"));
+    assert_eq!(Color::DarkGray.css_class(), Some("mc-dark-gray"));
+
+    let mut classes: Vec<_> = NAMED_COLORS
+        .iter()
+        .map(|c| c.css_class().unwrap())
+        .collect();
+    assert!(classes.iter().all(|class| {
+        class.starts_with("mc-")
+            && class.bytes().all(|b| b.is_ascii_lowercase() || b == b'-')
+            && !class.ends_with('-')
+            && !class.contains("--")
+    }));
+    classes.sort_unstable();
+    classes.dedup();
+    assert_eq!(classes.len(), 16);
+
+    // Even an RGB color that looks the same as a named one has no class
+    assert_eq!(Color::Rgb(255, 170, 0).css_class(), None);
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use arbitrary::{Arbitrary, Unstructured};
+    use mc_legacy_formatting::{Color, Styles};
+
+    #[test]
+    fn generates_every_named_color() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&bytes);
+        let mut seen = Vec::new();
+
+        // Running out of data doesn't make `arbitrary` fail, so stop at the end
+        while !u.is_empty() {
+            let color = Color::arbitrary(&mut u).unwrap();
+            if !matches!(color, Color::Rgb(..)) && !seen.contains(&color) {
+                seen.push(color);
+            }
+        }
+
+        assert_eq!(seen.len(), 16);
+    }
+
+    #[test]
+    fn styles_are_known() {
+        for b in 0..=255 {
+            let styles = Styles::arbitrary(&mut Unstructured::new(&[b])).unwrap();
+            assert!(Styles::all().contains(styles));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "color-print")]
+
+use std::sync::Once;
+
+use mc_legacy_formatting::{SpanExt, StrikethroughWhitespaceStyle};
+
+/// Make `colored` write escape sequences even though stdout isn't a terminal
+///
+/// The override is global to the test binary, so every test that prints goes
+/// through here first to see the same setting no matter what order the tests
+/// run in.
+fn force_colors() {
+    static FORCE_COLORS: Once = Once::new();
+    FORCE_COLORS.call_once(|| colored::control::set_override(true));
+}
+
+fn print(s: &str, style: StrikethroughWhitespaceStyle) -> String {
+    force_colors();
+    s.span_iter()
+        .map(|span| {
+            span.wrap_colored()
+                .with_strikethrough_whitespace_style(style)
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn dashes_by_default() {
+    force_colors();
+    let printed: String = "§m   §rtext"
+        .span_iter()
+        .map(|span| span.wrap_colored().to_string())
+        .collect();
+
+    assert_eq!(
+        printed,
+        print("§m   §rtext", StrikethroughWhitespaceStyle::Dashes)
+    );
+    assert_eq!(printed.matches('-').count(), 3);
+}
+
+#[test]
+fn spaces_with_line() {
+    let printed = print("§m   §rtext", StrikethroughWhitespaceStyle::SpacesWithLine);
+
+    assert!(!printed.contains('-'));
+    assert_eq!(printed.matches(' ').count(), 3);
+    assert!(printed.ends_with("text"));
+}
+
+#[test]
+fn only_whitespace_is_affected() {
+    let printed = print("§ma-b c", StrikethroughWhitespaceStyle::SpacesWithLine);
+
+    assert_eq!(printed.matches('-').count(), 1);
+}
+
+#[test]
+fn each_style_on_five_wide_span() {
+    for (style, c) in [
+        (StrikethroughWhitespaceStyle::Dashes, '-'),
+        (StrikethroughWhitespaceStyle::SpacesWithLine, ' '),
+        (StrikethroughWhitespaceStyle::BoxDrawing, '─'),
+        (StrikethroughWhitespaceStyle::Underscore, '_'),
+    ] {
+        let printed = print("§6§m     ", style);
+
+        assert_eq!(printed.matches(c).count(), 5, "{:?}", style);
+    }
+}
+
+mod colored_spans {
+    use super::force_colors;
+    use mc_legacy_formatting::{Color, ColoredSpans, Span, SpanExt, Styles};
+
+    fn print(s: &str) -> String {
+        force_colors();
+        ColoredSpans::new(s.span_iter()).to_string()
+    }
+
+    #[test]
+    fn writes_minimal_escape_sequences() {
+        assert_eq!(
+            print("§4red §lbold§r plain"),
+            "\x1b[31mred \x1b[1mbold\x1b[39;22m plain\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn single_trailing_reset() {
+        let printed = print("§6§lgold §obold italic §rplain §cred");
+
+        assert_eq!(printed.matches("\x1b[0m").count(), 1);
+        assert!(printed.ends_with("red\x1b[0m"));
+    }
+
+    #[test]
+    fn random_is_hidden() {
+        assert_eq!(print("§ksecret§r!"), "\x1b[97;8msecret\x1b[39;28m!\x1b[0m");
+    }
+
+    #[test]
+    fn plain_text_has_no_escapes() {
+        assert_eq!(print("just text"), "just text");
+    }
+
+    #[test]
+    fn works_on_any_span_iterator() {
+        force_colors();
+        let spans = [
+            Span::new_styled("a", Color::Gold, Styles::empty()),
+            Span::new_styled("b", Color::Gold, Styles::UNDERLINED),
+        ];
+
+        assert_eq!(
+            ColoredSpans::new(spans.iter().copied()).to_string(),
+            "\x1b[33ma\x1b[4mb\x1b[0m"
+        );
+    }
+}
+
+mod color_from_colored {
+    use mc_legacy_formatting::{color_from_colored, Color, COLOR_CODES};
+
+    #[test]
+    fn round_trips_named_colors() {
+        for color in COLOR_CODES.iter().filter_map(|&c| Color::from_char(c)) {
+            assert_eq!(color_from_colored(colored::Color::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn truecolor_is_nearest_named() {
+        for ((r, g, b), expected) in [
+            ((255, 85, 85), Color::Red),
+            ((250, 160, 10), Color::Gold),
+            ((20, 20, 20), Color::Black),
+            ((18, 52, 86), Color::DarkGray),
+        ] {
+            assert_eq!(
+                color_from_colored(colored::Color::TrueColor { r, g, b }),
+                expected
+            );
+        }
+    }
+}
+
+mod palette {
+    use super::force_colors;
+    use mc_legacy_formatting::{Color, ColoredSpans, PrintSpanColored, SpanExt, COLOR_CODES};
+
+    #[test]
+    fn default_matches_from_color() {
+        for (c, &colored) in COLOR_CODES.iter().zip(&PrintSpanColored::DEFAULT_PALETTE) {
+            assert_eq!(
+                colored::Color::from(Color::from_char(*c).unwrap()),
+                colored,
+                "{:?}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn custom_palette_is_used() {
+        force_colors();
+        let mut palette = PrintSpanColored::DEFAULT_PALETTE;
+        palette[7] = colored::Color::BrightBlack;
+
+        let printed: String = "§7gray"
+            .span_iter()
+            .map(|span| span.wrap_colored().with_palette(palette).to_string())
+            .collect();
+        let default: String = "§8gray"
+            .span_iter()
+            .map(|span| span.wrap_colored().to_string())
+            .collect();
+        assert_eq!(printed, default);
+
+        assert_eq!(
+            ColoredSpans::new("§7a§6b".span_iter())
+                .with_palette(palette)
+                .to_string(),
+            "\x1b[90ma\x1b[33mb\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn rgb_ignores_palette() {
+        force_colors();
+        let spans = "§x§f§f§8§0§0§0rgb".span_iter().with_hex_colors(true);
+
+        // `colored` decides how truecolor is written based on the terminal, so
+        // compare against the default palette rather than exact escapes
+        assert_eq!(
+            ColoredSpans::new(spans.clone())
+                .with_palette([colored::Color::Black; 16])
+                .to_string(),
+            ColoredSpans::new(spans).to_string()
+        );
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{split_columns, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+fn cells<'a>(s: &'a str, delimiter: &str) -> Vec<Vec<Span<'a>>> {
+    split_columns(SpanIter::new(s), delimiter).collect()
+}
+
+#[test]
+fn hub_mcs_gg() {
+    let cells = cells(HUB_MCS_GG, "| ");
+    let bold = Styles::BOLD;
+
+    assert_eq!(cells.len(), 2);
+    assert_eq!(
+        cells[0][cells[0].len() - 2..],
+        [
+            Span::new_styled("1.7-1.16 SUPPORT", Color::Green, bold | Styles::UNDERLINED),
+            Span::new_plain(" "),
+        ]
+    );
+    assert_eq!(
+        cells[1],
+        vec![
+            Span::new_styled("SITE", Color::Green, bold),
+            Span::new_styled(":", Color::Gray, bold),
+            Span::new_styled(
+                "www.minesuperior.com",
+                Color::Green,
+                bold | Styles::UNDERLINED
+            ),
+        ]
+    );
+
+    // Everything before the delimiter is in the first cell, unchanged
+    let before: Vec<_> = SpanIter::new(HUB_MCS_GG)
+        .take_while(|span| !span.text().starts_with('|'))
+        .collect();
+    assert_eq!(cells[0], before);
+}
+
+#[test]
+fn delimiter_inside_a_span() {
+    assert_eq!(
+        cells("§6a | b | c", " | "),
+        vec![
+            vec![Span::new_styled("a", Color::Gold, Styles::empty())],
+            vec![Span::new_styled("b", Color::Gold, Styles::empty())],
+            vec![Span::new_styled("c", Color::Gold, Styles::empty())],
+        ]
+    );
+}
+
+#[test]
+fn delimiter_across_spans() {
+    // The formatting of the delimiter carries on into the next cell
+    assert_eq!(
+        cells("§aone§r §7§l| two", " | "),
+        vec![
+            vec![Span::new_styled("one", Color::Green, Styles::empty())],
+            vec![Span::new_styled("two", Color::Gray, Styles::BOLD)],
+        ]
+    );
+}
+
+#[test]
+fn empty_cells() {
+    assert_eq!(cells("", "|"), vec![vec![]]);
+    assert_eq!(
+        cells("§a|x||", "|"),
+        vec![
+            vec![],
+            vec![Span::new_styled("x", Color::Green, Styles::empty())],
+            vec![],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn empty_delimiter_never_matches() {
+    assert_eq!(cells("§ca|b", ""), vec![spans("§ca|b")]);
+}
+
+#[test]
+fn multibyte_text() {
+    assert_eq!(
+        cells("§e★ ¦ é¦§b¦", "¦"),
+        vec![
+            vec![Span::new_styled("★ ", Color::Yellow, Styles::empty())],
+            vec![Span::new_styled(" é", Color::Yellow, Styles::empty())],
+            vec![],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn url_kind_is_kept() {
+    let s = "https://a.com|b";
+    let cells: Vec<_> = split_columns(SpanIter::new(s).with_url_detection(true), "|").collect();
+
+    assert!(matches!(cells[0][..], [Span::Url { .. }]));
+}
+
+proptest! {
+    #[test]
+    fn rejoined_cells_match_the_source(s in strings_of(&['§', 'a', 'l', 'r', '|', ' ', 'x'])) {
+        for delimiter in ["|", " |", "x x"] {
+            let rejoined = cells(&s, delimiter)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(idx, cell)| {
+                    let delimiter = Some(Span::new_plain(delimiter)).filter(|_| idx > 0);
+                    delimiter.into_iter().chain(cell)
+                })
+                .map(|span| span.text())
+                .collect::<String>();
+            let text: String = SpanIter::new(&s).map(|span| span.text()).collect();
+
+            assert_eq!(rejoined, text, "{:?} split on {:?}", s, delimiter);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn formatting_matches_the_source(s in strings_of(&['§', 'a', 'l', 'r', '|', 'x'])) {
+        let formatted: Vec<_> = cells(&s, "|")
+            .into_iter()
+            .flatten()
+            .flat_map(|span| {
+                let (color, styles) = (span.color(), span.styles());
+                span.text().chars().map(move |c| (c, color, styles))
+            })
+            .collect();
+        let expected: Vec<_> = SpanIter::new(&s)
+            .flat_map(|span| {
+                let (color, styles) = (span.color(), span.styles());
+                span.text().chars().map(move |c| (c, color, styles))
+            })
+            .filter(|&(c, _, _)| c != '|')
+            .collect();
+
+        assert_eq!(formatted, expected, "{:?}", s);
+    }
+}
@@ -1,5 +1,114 @@
-use mc_legacy_formatting::{Span, SpanIter};
+#![allow(dead_code)]
 
-pub fn spans(s: &str) -> Vec<Span> {
+use mc_legacy_formatting::{Color, Span, SpanIter, Styles};
+use proptest::prelude::*;
+
+pub fn spans(s: &str) -> Vec<Span<'_>> {
     SpanIter::new(s).collect()
 }
+
+/// Merge adjacent spans with the same formatting so that text that was split
+/// into spans differently (for example, at chunk boundaries or escaped start
+/// chars) can be compared
+pub fn merged<'a>(spans: impl Iterator<Item = Span<'a>>) -> Vec<(String, Color, Styles)> {
+    let mut merged: Vec<(String, Color, Styles)> = Vec::new();
+
+    for span in spans {
+        match merged.last_mut() {
+            Some((text, color, styles)) if (*color, *styles) == (span.color(), span.styles()) => {
+                text.push_str(span.text())
+            }
+            _ => merged.push((span.text().to_string(), span.color(), span.styles())),
+        }
+    }
+
+    merged
+}
+
+/// Short strings out of the chars in `alphabet`
+///
+/// Picking chars that are likely to trip up the pairing of start chars and
+/// codes makes for good property test inputs.
+pub fn strings_of(alphabet: &[char]) -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(alphabet.to_vec()), 0..12)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+/// The 16 named colors, in the order of their codes (`0` to `f`)
+pub const NAMED_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::DarkBlue,
+    Color::DarkGreen,
+    Color::DarkAqua,
+    Color::DarkRed,
+    Color::DarkPurple,
+    Color::Gold,
+    Color::Gray,
+    Color::DarkGray,
+    Color::Blue,
+    Color::Green,
+    Color::Aqua,
+    Color::Red,
+    Color::LightPurple,
+    Color::Yellow,
+    Color::White,
+];
+
+pub const HUB_MCS_GG: &str = " §7§l<§a§l+§7§l>§8§l§m-----§8§l[ §a§lMine§7§lSuperior§a§l Network§8§l ]§8§l§m-----§7§l<§a§l+§7§l>\n\
+            §a§l§n1.7-1.16 SUPPORT§r §7§l| §a§lSITE§7§l:§a§l§nwww.minesuperior.com";
+
+pub const PURPLE_WTF: &str = "§5§m                  §6>§7§l§6§l>§6§l[§5§l§oPurple §8§l§oPrison§6§l]§6§l<§6<§5§m                     \
+                §R §7              (§4!§7) §e§lSERVER HAS §D§LRESET! §7(§4!§7)";
+
+pub const MC_MINEHEROES_ORG: &str =
+    "§f§b§lMINE§6§lHEROES §7- §astore.mineheroes.net§a §2§l[75% Sale]\n\
+            §b§lSKYBLOCK §f§l+ §2§lKRYPTON §f§lRESET! §f§l- §6§lNEW FALL CRATE";
+
+pub const PLAY_MC_BLAZE_COM: &str =
+    "§4§l§m⌜--------------------⌝\n   §4§lBLAZE§b-§6§lGAMING§b Network\n\n        \
+            §bwww.mc-blaze.com\n            §8[§4116§7 /§4 1000§8]\n§4§l§m⌞--------------------⌟";
+
+pub const PLAY_LEMONCLOUD_NET: &str = "§f§f §6§m---§e§m---§f§m---§f   §e§lLemon§f§lCloud§f §6[1.7-1.16] §f  §f§m---§e§m---§6§m---§f  \
+                §f\n          §6§lSurvival 1.16 §e§l+ §c§lNether Reset!";
+
+pub const PLAY_OPBLOCKS_COM: &str =
+    "§c§lOPBlocks §8» §5§lM§b§li§5§ll§b§lk§5§ly§b§lW§5§la§b§ly §c§lPrison §8» §f§lOPEN NOW!     \
+                §7Join our §3Discord §7here » §cdiscord.gg/opblocks";
+
+pub const PIXEL_MC_COMPLEX_COM: &str =
+    "§b§m-----------§8§l[- §f§lComplex §b§lGaming §8§l-]§b§m----------\n§fQuests/Clans §8| \
+                §b#1 Pixelmon Network §8| §fCustom Plugins";
+
+pub const PLAY_MCPRISON_COM: &str =
+    "  §3§lMC§b§lPrison§7 | The Best Prison Experience... \n§b§lATLANTIC §a§lReleases§7 \
+                on§a§l Saturday§7 at §a§l3PM EST!";
+
+pub const PLAY_PVPWARS_NET: &str = "§6§l┍§e§l━ •§6§l•§f§l• §r[ §e§l§nP§6§l§nV§e§l§nP§f§n §6§l§nW§e§l§nA§6§l§nR§e§l§nS§r ] \
+                §f§o§l—— §4»§c»§f» §c§l5§e§l0§6§l% §c§lS§e§lA§6§lL§c§lE §f«§c«§4« §f§l•§6§l•§e§l• \
+                ━§6§l┑\n  §e§lNEWS: §c§lSB §4§l§nFIRE§r §r§l⁄ §3§l§nICE§r §b§lRESET§f§l! §f§o§m--§7 \
+                §oJoin now!";
+
+pub const MCCENTRAL_ORG: &str =
+    " §c§lI§d§l§m-§c§l§m-§6§l§m-§e§l§m-§b§l§m-§8§l[§r §b§lMCCentral §d§lFactions Reset§r \
+                §8§l]§b§l§m-§e§l§m-§6§l§m-§c§l§m-§d§l§m-§c§lI     §a§l23rd October 3pm MST§r \
+                §f§l§m->§r §6§l$1125 In Prizes";
+
+/// Has an uppercase version of each kind of code (colors, styles, and reset)
+/// mixed in with lowercase ones
+pub const SUPPORTS_UPPERCASE_STYLE_CODES: &str =
+    "§6§LBold §oand §OItalic§R §Aplain §cred §KX§r§M   §N§Rdone";
+
+/// Every MOTD collected from a real server, for tests that check properties
+/// that should hold for any input
+pub const SERVER_MOTDS: &[&str] = &[
+    HUB_MCS_GG,
+    PURPLE_WTF,
+    MC_MINEHEROES_ORG,
+    PLAY_MC_BLAZE_COM,
+    PLAY_LEMONCLOUD_NET,
+    PLAY_OPBLOCKS_COM,
+    PIXEL_MC_COMPLEX_COM,
+    PLAY_MCPRISON_COM,
+    PLAY_PVPWARS_NET,
+    MCCENTRAL_ORG,
+];
@@ -0,0 +1,26 @@
+#![cfg(any(feature = "rgb", feature = "palette"))]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::Color;
+use pretty_assertions::assert_eq;
+
+#[cfg(feature = "rgb")]
+#[test]
+fn into_rgb8() {
+    for color in NAMED_COLORS.iter().copied().chain([Color::Rgb(18, 52, 86)]) {
+        let rgb = rgb::RGB8::from(color);
+        assert_eq!((rgb.r, rgb.g, rgb.b), color.foreground_rgb());
+    }
+}
+
+#[cfg(feature = "palette")]
+#[test]
+fn into_srgb() {
+    for color in NAMED_COLORS.iter().copied().chain([Color::Rgb(18, 52, 86)]) {
+        let srgb: palette::Srgb<u8> = color.into();
+        assert_eq!(srgb.into_components(), color.foreground_rgb());
+    }
+}
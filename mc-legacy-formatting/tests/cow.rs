@@ -0,0 +1,123 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use std::borrow::Cow;
+
+use mc_legacy_formatting::{to_legacy_string, Color, CowSpan, OwnedSpan, Span, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn unmodified_spans_stay_borrowed() {
+    for span in spans(PURPLE_WTF) {
+        let cow = span.map_text(Cow::Borrowed);
+
+        assert_eq!(cow.as_span(), span);
+        assert_eq!(cow, CowSpan::from(span));
+
+        match cow {
+            CowSpan::Styled { text, .. }
+            | CowSpan::StrikethroughWhitespace { text, .. }
+            | CowSpan::Url { text, .. }
+            | CowSpan::Plain(text) => assert!(matches!(text, Cow::Borrowed(_))),
+            cow => panic!("unexpected span {:?}", cow),
+        }
+    }
+}
+
+#[test]
+fn kind_follows_new_text() {
+    let whitespace = Span::new_strikethrough_whitespace("   ", Color::Red, Styles::STRIKETHROUGH);
+    let styled = Span::new_styled("text", Color::Red, Styles::STRIKETHROUGH);
+
+    assert_eq!(
+        whitespace.map_text(|_| "dashes".into()),
+        CowSpan::new_styled("dashes", Color::Red, Styles::STRIKETHROUGH)
+    );
+    assert_eq!(
+        styled.map_text(|_| "  ".into()),
+        CowSpan::new_strikethrough_whitespace("  ", Color::Red, Styles::STRIKETHROUGH)
+    );
+    assert_eq!(
+        Span::new_plain("a").map_text(|_| "b".into()),
+        CowSpan::new_plain("b")
+    );
+    assert_eq!(
+        Span::new_url("example.com", Color::Aqua, Styles::empty()).map_text(|text| format!(
+            "https://{}",
+            text
+        )
+        .into()),
+        CowSpan::new_url("https://example.com", Color::Aqua, Styles::empty())
+    );
+}
+
+#[test]
+fn to_legacy_string_accepts_all_span_types() {
+    for s in SERVER_MOTDS {
+        let spans = spans(s);
+        let cow_spans: Vec<CowSpan> = spans.iter().map(|span| CowSpan::from(*span)).collect();
+        let owned_spans: Vec<OwnedSpan> = spans.iter().map(|span| span.into_owned()).collect();
+        let expected = to_legacy_string(&spans, '§');
+
+        assert_eq!(to_legacy_string(&cow_spans, '§'), expected);
+        assert_eq!(to_legacy_string(&owned_spans, '§'), expected);
+    }
+}
+
+#[test]
+fn into_owned() {
+    let cow = Span::new_styled("gold", Color::Gold, Styles::BOLD).map_text(|text| text.into());
+
+    assert_eq!(
+        cow.into_owned(),
+        OwnedSpan::new_styled("gold", Color::Gold, Styles::BOLD)
+    );
+}
+
+mod map_spans {
+    use super::*;
+    use mc_legacy_formatting::{map_spans, SpanIter};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn uppercase_keeps_formatting() {
+        for s in SERVER_MOTDS {
+            let spans = spans(s);
+            let upper: Vec<CowSpan> =
+                map_spans(spans.iter().copied(), |text| text.to_uppercase().into()).collect();
+
+            assert_eq!(upper.len(), spans.len());
+            for (span, upper) in spans.iter().zip(&upper) {
+                let upper = upper.as_span();
+
+                assert_eq!(upper.text(), span.text().to_uppercase());
+                assert_eq!(upper.color(), span.color());
+                assert_eq!(upper.styles(), span.styles());
+                // Uppercasing doesn't turn whitespace into anything else
+                assert_eq!(
+                    matches!(upper, Span::StrikethroughWhitespace { .. }),
+                    matches!(span, Span::StrikethroughWhitespace { .. })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn strikethrough_whitespace_is_mapped() {
+        let mapped: Vec<CowSpan> = map_spans(SpanIter::new("§7§m   §rx"), |text| {
+            text.replacen(' ', "\t", 1).into()
+        })
+        .collect();
+
+        assert_eq!(
+            mapped,
+            vec![
+                CowSpan::new_strikethrough_whitespace("\t  ", Color::Gray, Styles::STRIKETHROUGH),
+                CowSpan::new_plain("x"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{diff, diff_spans, Color, DiffSegment, OwnedSpan, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+fn styled(text: &str, color: Color, styles: Styles) -> OwnedSpan {
+    OwnedSpan::new_styled(text, color, styles)
+}
+
+#[test]
+fn pure_recolor() {
+    assert_eq!(
+        diff("§aHello", "§cHello"),
+        vec![DiffSegment::StyleChanged {
+            old: styled("Hello", Color::Green, Styles::empty()),
+            new: styled("Hello", Color::Red, Styles::empty()),
+        }]
+    );
+}
+
+#[test]
+fn partial_restyle() {
+    assert_eq!(
+        diff("§eHello world", "§eHello §lworld"),
+        vec![
+            DiffSegment::Unchanged(styled("Hello ", Color::Yellow, Styles::empty())),
+            DiffSegment::StyleChanged {
+                old: styled("world", Color::Yellow, Styles::empty()),
+                new: styled("world", Color::Yellow, Styles::BOLD),
+            },
+        ]
+    );
+}
+
+#[test]
+fn pure_text_edit() {
+    assert_eq!(
+        diff("§6Level 5", "§6Level 7"),
+        vec![
+            DiffSegment::Unchanged(styled("Level ", Color::Gold, Styles::empty())),
+            DiffSegment::TextChanged {
+                old: styled("5", Color::Gold, Styles::empty()),
+                new: styled("7", Color::Gold, Styles::empty()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn insertion_and_removal() {
+    assert_eq!(
+        diff("§aOnline §7now", "§aOnline §cplayers §7now"),
+        vec![
+            DiffSegment::Unchanged(styled("Online ", Color::Green, Styles::empty())),
+            DiffSegment::Inserted(styled("players ", Color::Red, Styles::empty())),
+            DiffSegment::Unchanged(styled("now", Color::Gray, Styles::empty())),
+        ]
+    );
+    assert_eq!(
+        diff("§aOnline §cplayers §7now", "§aOnline §7now"),
+        vec![
+            DiffSegment::Unchanged(styled("Online ", Color::Green, Styles::empty())),
+            DiffSegment::Removed(styled("players ", Color::Red, Styles::empty())),
+            DiffSegment::Unchanged(styled("now", Color::Gray, Styles::empty())),
+        ]
+    );
+}
+
+#[test]
+fn text_replaced_with_different_formatting() {
+    assert_eq!(
+        diff("§7Status: §aup", "§7Status: §cdown"),
+        vec![
+            DiffSegment::Unchanged(styled("Status: ", Color::Gray, Styles::empty())),
+            DiffSegment::Removed(styled("up", Color::Green, Styles::empty())),
+            DiffSegment::Inserted(styled("down", Color::Red, Styles::empty())),
+        ]
+    );
+}
+
+#[test]
+fn same_rendering_with_different_codes() {
+    // Redundant codes and different span boundaries don't count as changes
+    assert_eq!(
+        diff("§a§aHel§alo", "§c§aHello"),
+        vec![DiffSegment::Unchanged(styled(
+            "Hello",
+            Color::Green,
+            Styles::empty()
+        ))]
+    );
+}
+
+#[test]
+fn empty_inputs() {
+    assert_eq!(diff("", ""), vec![]);
+    assert_eq!(diff("§a§l", "§c"), vec![]);
+    assert_eq!(
+        diff("", "§aHi"),
+        vec![DiffSegment::Inserted(styled(
+            "Hi",
+            Color::Green,
+            Styles::empty()
+        ))]
+    );
+    assert_eq!(
+        diff("§aHi", ""),
+        vec![DiffSegment::Removed(styled(
+            "Hi",
+            Color::Green,
+            Styles::empty()
+        ))]
+    );
+}
+
+#[test]
+fn identical_strings() {
+    let s = "§6§lGold §r§bAqua";
+
+    assert_eq!(
+        diff(s, s),
+        vec![
+            DiffSegment::Unchanged(styled("Gold ", Color::Gold, Styles::BOLD)),
+            DiffSegment::Unchanged(styled("Aqua", Color::Aqua, Styles::empty())),
+        ]
+    );
+}
+
+#[test]
+fn diff_spans_matches_diff() {
+    let (old, new) = ("§aa§bb§cc", "§ab§bc§cd");
+
+    assert_eq!(
+        diff_spans(SpanIter::new(old), SpanIter::new(new)),
+        diff(old, new)
+    );
+}
+
+mod span_diff {
+    use super::*;
+    use mc_legacy_formatting::{span_diff, strip_formatting_string, SpanChange};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn color_flip_on_one_word() {
+        assert_eq!(
+            span_diff(
+                "§6Welcome to §aSkyblock§6!",
+                "§6Welcome to §cSkyblock§6!",
+                '§'
+            ),
+            vec![
+                SpanChange::Unchanged(styled("Welcome to ", Color::Gold, Styles::empty())),
+                SpanChange::Removed(styled("Skyblock", Color::Green, Styles::empty())),
+                SpanChange::Added(styled("Skyblock", Color::Red, Styles::empty())),
+                SpanChange::Unchanged(styled("!", Color::Gold, Styles::empty())),
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_spans() {
+        assert_eq!(
+            span_diff("&aOnline &7now", "&aOnline &cplayers &7now", '&'),
+            vec![
+                SpanChange::Unchanged(styled("Online ", Color::Green, Styles::empty())),
+                SpanChange::Added(styled("players ", Color::Red, Styles::empty())),
+                SpanChange::Unchanged(styled("now", Color::Gray, Styles::empty())),
+            ]
+        );
+        assert_eq!(
+            span_diff("&aOnline &cplayers &7now", "&aOnline &7now", '&'),
+            vec![
+                SpanChange::Unchanged(styled("Online ", Color::Green, Styles::empty())),
+                SpanChange::Removed(styled("players ", Color::Red, Styles::empty())),
+                SpanChange::Unchanged(styled("now", Color::Gray, Styles::empty())),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_edit_replaces_the_whole_span() {
+        assert_eq!(
+            span_diff("§6Level 5", "§6Level 7", '§'),
+            vec![
+                SpanChange::Removed(styled("Level 5", Color::Gold, Styles::empty())),
+                SpanChange::Added(styled("Level 7", Color::Gold, Styles::empty())),
+            ]
+        );
+    }
+
+    #[test]
+    fn redundant_codes_arent_changes() {
+        assert_eq!(
+            span_diff("§a§aHel§alo§c", "§c§aHello", '§'),
+            vec![SpanChange::Unchanged(styled(
+                "Hello",
+                Color::Green,
+                Styles::empty()
+            ))]
+        );
+    }
+
+    #[test]
+    fn empty_inputs() {
+        assert_eq!(span_diff("", "", '§'), vec![]);
+        assert_eq!(
+            span_diff("", "§aHi", '§'),
+            vec![SpanChange::Added(styled(
+                "Hi",
+                Color::Green,
+                Styles::empty()
+            ))]
+        );
+        assert_eq!(
+            span_diff("§aHi", "§l", '§'),
+            vec![SpanChange::Removed(styled(
+                "Hi",
+                Color::Green,
+                Styles::empty()
+            ))]
+        );
+    }
+
+    #[test]
+    fn sides_rebuild_the_inputs() {
+        let (old, new) = (PLAY_MCPRISON_COM, PLAY_PVPWARS_NET);
+        let changes = span_diff(old, new, '§');
+        let side = |keep: fn(&SpanChange) -> Option<&OwnedSpan>| {
+            changes
+                .iter()
+                .filter_map(keep)
+                .map(|span| span.as_span().text().to_string())
+                .collect::<String>()
+        };
+
+        let old_text = side(|change| match change {
+            SpanChange::Unchanged(span) | SpanChange::Removed(span) => Some(span),
+            SpanChange::Added(_) => None,
+        });
+        let new_text = side(|change| match change {
+            SpanChange::Unchanged(span) | SpanChange::Added(span) => Some(span),
+            SpanChange::Removed(_) => None,
+        });
+
+        assert_eq!(old_text, strip_formatting_string(old, '§'));
+        assert_eq!(new_text, strip_formatting_string(new, '§'));
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    to_legacy_string, Color, FormattedText, OwnedSpan, Span, SpanIter, Spans, Styles,
+};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn display_matches_to_legacy_string() {
+    for s in SERVER_MOTDS {
+        let spans: Vec<Span> = SpanIter::new(s).collect();
+        let text: FormattedText = spans.iter().copied().collect();
+
+        assert_eq!(text.to_string(), to_legacy_string(&spans, '§'));
+        assert_eq!(text.to_legacy_string('&'), to_legacy_string(&spans, '&'));
+    }
+}
+
+#[test]
+fn display_round_trips() {
+    for s in SERVER_MOTDS {
+        let text: FormattedText = SpanIter::new(s).collect();
+        let displayed = text.to_string();
+        let reparsed: FormattedText = SpanIter::new(&displayed)
+            .with_doubled_escape(true)
+            .collect();
+
+        assert_eq!(
+            merged(reparsed.iter().filter(|span| !span.is_empty())),
+            merged(text.iter().filter(|span| !span.is_empty()))
+        );
+    }
+}
+
+#[test]
+fn plain_text_and_width_match_spans() {
+    for s in SERVER_MOTDS {
+        let spans: Vec<Span> = SpanIter::new(s).collect();
+        let text: FormattedText = SpanIter::new(s).collect();
+
+        assert_eq!(text.plain_text(), Spans::new(&spans).to_plain_string());
+        assert_eq!(text.display_width(4), Spans::new(&spans).display_width(4));
+    }
+}
+
+#[test]
+fn push_and_extend() {
+    let mut text = FormattedText::new();
+    assert!(text.is_empty());
+    assert_eq!(text.to_string(), "");
+
+    text.push(Span::new_styled("Tom ", Color::Gold, Styles::BOLD));
+    text.push(OwnedSpan::new_styled(
+        "& Jerry",
+        Color::Gold,
+        Styles::empty(),
+    ));
+    text.extend(SpanIter::new("§r!"));
+
+    assert_eq!(text.len(), 3);
+    assert_eq!(text.to_string(), "§6§lTom §6& Jerry§r!");
+    assert_eq!(text.plain_text(), "Tom & Jerry!");
+}
+
+#[test]
+fn into_iter() {
+    let text: FormattedText = SpanIter::new("§6a§cb").collect();
+    let expected = vec![
+        OwnedSpan::new_styled("a", Color::Gold, Styles::empty()),
+        OwnedSpan::new_styled("b", Color::Red, Styles::empty()),
+    ];
+
+    assert_eq!((&text).into_iter().cloned().collect::<Vec<_>>(), expected);
+    assert_eq!(text.spans(), &expected[..]);
+    assert_eq!(text.into_iter().collect::<Vec<_>>(), expected);
+}
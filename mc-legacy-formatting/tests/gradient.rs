@@ -0,0 +1,144 @@
+#![cfg(feature = "alloc")]
+
+use mc_legacy_formatting::{gradient, Gradient, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+/// Parse `s` with hex colors, getting (char, rgb, styles) for each char
+fn parse_chars(s: &str, start_char: char) -> Vec<(char, (u8, u8, u8), Styles)> {
+    SpanIter::new(s)
+        .with_start_char(start_char)
+        .with_hex_colors(true)
+        .with_doubled_escape(true)
+        .flat_map(|span: Span| {
+            let rgb = span.color().foreground_rgb();
+            let styles = span.styles();
+            span.text().chars().map(move |c| (c, rgb, styles))
+        })
+        .collect()
+}
+
+#[test]
+fn round_trips_per_char() {
+    for start_char in ['§', '&'] {
+        let s = gradient("abcde", (255, 0, 0), (0, 0, 255), start_char);
+        let chars = parse_chars(&s, start_char);
+
+        assert_eq!(
+            chars,
+            vec![
+                ('a', (255, 0, 0), Styles::empty()),
+                ('b', (191, 0, 64), Styles::empty()),
+                ('c', (128, 0, 128), Styles::empty()),
+                ('d', (64, 0, 191), Styles::empty()),
+                ('e', (0, 0, 255), Styles::empty()),
+            ],
+            "{:?}",
+            s
+        );
+        // Each char is its own span
+        assert_eq!(
+            SpanIter::new(&s)
+                .with_start_char(start_char)
+                .with_hex_colors(true)
+                .count(),
+            5
+        );
+    }
+}
+
+#[test]
+fn whitespace_skips_steps() {
+    let s = gradient("a b  c", (0, 0, 0), (200, 200, 200), '§');
+
+    assert_eq!(
+        parse_chars(&s, '§'),
+        vec![
+            ('a', (0, 0, 0), Styles::empty()),
+            (' ', (0, 0, 0), Styles::empty()),
+            ('b', (100, 100, 100), Styles::empty()),
+            (' ', (100, 100, 100), Styles::empty()),
+            (' ', (100, 100, 100), Styles::empty()),
+            ('c', (200, 200, 200), Styles::empty()),
+        ]
+    );
+    // No codes are written for the whitespace
+    assert_eq!(s.matches("§x").count(), 3);
+}
+
+#[test]
+fn multiple_stops() {
+    let stops = [(255, 0, 0), (0, 255, 0), (0, 0, 255)];
+    let s = Gradient::new(&stops).apply("abcde");
+
+    assert_eq!(
+        parse_chars(&s, '§')
+            .into_iter()
+            .map(|(_, rgb, _)| rgb)
+            .collect::<Vec<_>>(),
+        vec![
+            (255, 0, 0),
+            (128, 128, 0),
+            (0, 255, 0),
+            (0, 128, 128),
+            (0, 0, 255),
+        ]
+    );
+}
+
+#[test]
+fn single_and_no_stops() {
+    let s = Gradient::new(&[(18, 52, 86)]).apply("ab c");
+    assert!(parse_chars(&s, '§')
+        .iter()
+        .all(|(_, rgb, _)| *rgb == (18, 52, 86)));
+    assert_eq!(s, "§x§1§2§3§4§5§6ab c");
+
+    assert_eq!(Gradient::new(&[]).apply("§6ab §lc"), "ab §lc");
+}
+
+#[test]
+fn keeps_styles() {
+    let s = gradient("§lab§r§6c", (255, 0, 0), (0, 0, 255), '§');
+
+    assert_eq!(
+        parse_chars(&s, '§'),
+        vec![
+            ('a', (255, 0, 0), Styles::BOLD),
+            ('b', (128, 0, 128), Styles::BOLD),
+            ('c', (0, 0, 255), Styles::empty()),
+        ]
+    );
+
+    let s = Gradient::new(&[(255, 0, 0), (0, 0, 255)])
+        .with_styles(false)
+        .apply("§lab§r§6c");
+    assert!(parse_chars(&s, '§')
+        .iter()
+        .all(|(_, _, styles)| styles.is_empty()));
+}
+
+#[test]
+fn replaces_existing_colors() {
+    let s = gradient("§x§1§2§3§4§5§6a§6b", (255, 0, 0), (0, 0, 255), '§');
+
+    assert_eq!(
+        parse_chars(&s, '§'),
+        vec![
+            ('a', (255, 0, 0), Styles::empty()),
+            ('b', (0, 0, 255), Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn escapes_start_chars() {
+    let s = gradient("a&zb", (255, 0, 0), (0, 0, 255), '&');
+
+    assert_eq!(
+        parse_chars(&s, '&')
+            .into_iter()
+            .map(|(c, _, _)| c)
+            .collect::<String>(),
+        "a&zb"
+    );
+}
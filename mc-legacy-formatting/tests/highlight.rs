@@ -0,0 +1,146 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use std::ops::Range;
+
+use mc_legacy_formatting::{highlight, minify, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+/// Parse `s`, getting (char, color, styles) for each visible char
+fn parse_chars(s: &str) -> Vec<(char, Color, Styles)> {
+    SpanIter::new(s)
+        .flat_map(|span: Span| {
+            let (color, styles) = (span.color(), span.styles());
+            span.text().chars().map(move |c| (c, color, styles))
+        })
+        .collect()
+}
+
+fn assert_highlighted(s: &str, range: Range<usize>, color: Color, styles: Styles) {
+    let highlighted = highlight(s, range.clone(), color, styles);
+    let expected: Vec<_> = parse_chars(s)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (c, old_color, old_styles))| {
+            if range.contains(&idx) {
+                (c, color, styles)
+            } else {
+                (c, old_color, old_styles)
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        parse_chars(&highlighted),
+        expected,
+        "{:?} -> {:?}",
+        s,
+        highlighted
+    );
+}
+
+#[test]
+fn mid_span_to_mid_span() {
+    let s = "§6Golden §cApple";
+
+    assert_eq!(
+        highlight(s, 3..11, Color::Yellow, Styles::BOLD),
+        "§6Gol§e§lden Appl§ce"
+    );
+    assert_highlighted(s, 3..11, Color::Yellow, Styles::BOLD);
+}
+
+#[test]
+fn within_one_span() {
+    let s = "§6§lGolden";
+
+    // Gold bold is restored without a reset, and the styles are turned back
+    // on since the color code cleared them
+    assert_eq!(
+        highlight(s, 2..4, Color::Red, Styles::empty()),
+        "§6§lGo§cld§6§len"
+    );
+    assert_highlighted(s, 2..4, Color::Red, Styles::empty());
+}
+
+#[test]
+fn strikethrough_whitespace_run() {
+    let s = "§7a§m     §r§7b";
+    let highlighted = highlight(s, 2..5, Color::Red, Styles::STRIKETHROUGH);
+
+    // The original `§r§7` is kept since it still gives "b" the right formatting
+    assert_eq!(highlighted, "§7a§m §c§m   §7§m §r§7b");
+    assert_eq!(
+        spans(&highlighted),
+        vec![
+            Span::new_styled("a", Color::Gray, Styles::empty()),
+            Span::new_strikethrough_whitespace(" ", Color::Gray, Styles::STRIKETHROUGH),
+            Span::new_strikethrough_whitespace("   ", Color::Red, Styles::STRIKETHROUGH),
+            Span::new_strikethrough_whitespace(" ", Color::Gray, Styles::STRIKETHROUGH),
+            Span::new_styled("b", Color::Gray, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn original_codes_kept_after_range() {
+    // `§l` still gives "bold" the right formatting after the highlight, so
+    // it's kept as written
+    let s = "§6plain §lbold";
+
+    assert_eq!(
+        highlight(s, 0..5, Color::Aqua, Styles::empty()),
+        "§bplain§6 §lbold"
+    );
+    assert_eq!(
+        highlight(s, 0..6, Color::Aqua, Styles::empty()),
+        "§bplain §6§lbold"
+    );
+}
+
+#[test]
+fn range_is_clamped() {
+    let s = "§aab";
+
+    assert_eq!(highlight(s, 1..100, Color::Red, Styles::empty()), "§aa§cb");
+    assert_eq!(highlight(s, 5..10, Color::Red, Styles::empty()), minify(s));
+    assert_eq!(highlight(s, 1..1, Color::Red, Styles::empty()), minify(s));
+}
+
+#[test]
+fn invalid_codes_arent_split() {
+    let s = "a§zb";
+
+    assert_eq!(highlight(s, 2..3, Color::Red, Styles::empty()), "a§c§z§rb");
+    assert_eq!(highlight(s, 0..2, Color::Red, Styles::empty()), "§ca§z§rb");
+}
+
+proptest! {
+    #[test]
+    fn random_inputs(s in strings_of(&['§', 'a', 'l', 'm', 'r', ' ', 'x'])) {
+        // Invalid codes widen the range, which is tested separately
+        prop_assume!(!parse_chars(&s).iter().any(|&(c, _, _)| c == '§'));
+
+        let len = parse_chars(&s).len();
+        for start in 0..=len {
+            for end in start..=len {
+                assert_highlighted(&s, start..end, Color::Red, Styles::ITALIC);
+            }
+        }
+    }
+}
+
+#[test]
+fn fixtures() {
+    for s in [HUB_MCS_GG, PURPLE_WTF, MC_MINEHEROES_ORG] {
+        let len = parse_chars(s).len();
+
+        for start in (0..len).step_by(7) {
+            assert_highlighted(s, start..start + 5, Color::Yellow, Styles::UNDERLINED);
+        }
+    }
+}
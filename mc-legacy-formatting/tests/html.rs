@@ -0,0 +1,54 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{to_html, Color, OwnedSpan, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn styles() {
+    assert_eq!(
+        to_html(&[
+            Span::new_styled("a", Color::Red, Styles::BOLD | Styles::ITALIC),
+            Span::new_strikethrough_whitespace(
+                "  ",
+                Color::Gray,
+                Styles::STRIKETHROUGH | Styles::UNDERLINED
+            ),
+            Span::new_styled("?", Color::Rgb(255, 136, 0), Styles::RANDOM),
+        ]),
+        "<span style=\"color: #ff5555; font-weight: bold; font-style: italic\">a</span>\
+         <span style=\"color: #aaaaaa; text-decoration: underline line-through\">  </span>\
+         <span style=\"color: #ff8800\">?</span>"
+    );
+}
+
+#[test]
+fn text_is_escaped() {
+    assert_eq!(
+        to_html(&[Span::new_plain("<b>\"Tom\" & 'Jerry'</b>")]),
+        "<span style=\"color: #ffffff\">&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;</span>"
+    );
+}
+
+#[test]
+fn empty_spans_are_left_out() {
+    let spans: Vec<Span> = SpanIter::new("§a§lx§r").with_trailing_reset().collect();
+
+    assert_eq!(
+        to_html(&spans),
+        "<span style=\"color: #55ff55; font-weight: bold\">x</span>"
+    );
+    assert_eq!(to_html::<Span>(&[]), "");
+}
+
+#[test]
+fn owned_spans() {
+    for s in SERVER_MOTDS {
+        let owned: Vec<OwnedSpan> = spans(s).into_iter().map(OwnedSpan::from).collect();
+
+        assert_eq!(to_html(&owned), to_html(&spans(s)));
+    }
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use common::*;
+
+#[cfg(feature = "alloc")]
+use mc_legacy_formatting::collapse_blank_lines;
+use mc_legacy_formatting::{Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[cfg(feature = "alloc")]
+#[test]
+fn collapse_interior_newlines() {
+    assert_eq!(
+        collapse_blank_lines(spans(PLAY_MC_BLAZE_COM), 1)[4..6],
+        [
+            Span::new_styled(" Network\n", Color::Aqua, Styles::empty()),
+            Span::new_styled("        ", Color::Aqua, Styles::empty()),
+        ]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn collapse_across_spans() {
+    let s = "one\n§a\n§b\n\ntwo";
+    assert_eq!(
+        collapse_blank_lines(spans(s), 2),
+        vec![
+            Span::new_plain("one\n"),
+            Span::new_styled("\n", Color::Green, Styles::empty()),
+            Span::new_styled("two", Color::Aqua, Styles::empty()),
+        ]
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn nothing_to_collapse() {
+    assert_eq!(
+        collapse_blank_lines(spans(PLAY_MC_BLAZE_COM), 2),
+        spans(PLAY_MC_BLAZE_COM)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn remove_all_newlines() {
+    let s = "§6a\nb\n\nc";
+    assert_eq!(
+        collapse_blank_lines(spans(s), 0),
+        vec![
+            Span::new_styled("a", Color::Gold, Styles::empty()),
+            Span::new_styled("b", Color::Gold, Styles::empty()),
+            Span::new_styled("c", Color::Gold, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn lines_match_raw_lines() {
+    for s in SERVER_MOTDS {
+        for (i, raw_line) in s.split('\n').enumerate() {
+            let expected: String = SpanIter::new(raw_line).map(|span| span.text()).collect();
+            let actual: String = SpanIter::new(s)
+                .lines()
+                .filter(|(line, _)| *line == i)
+                .map(|(_, span)| span.text())
+                .collect();
+
+            assert_eq!(actual, expected, "line {} of {:?}", i, s);
+        }
+    }
+}
+
+#[test]
+fn lines_never_contain_newlines() {
+    for s in SERVER_MOTDS {
+        assert!(SpanIter::new(s)
+            .lines()
+            .all(|(_, span)| !span.text().contains('\n')));
+    }
+}
+
+#[test]
+fn lines_blaze() {
+    let lines: Vec<_> = SpanIter::new(PLAY_MC_BLAZE_COM).lines().collect();
+
+    assert_eq!(
+        lines[..3],
+        [
+            (
+                0,
+                Span::new_styled(
+                    "⌜--------------------⌝",
+                    Color::DarkRed,
+                    Styles::BOLD | Styles::STRIKETHROUGH
+                )
+            ),
+            (
+                1,
+                Span::new_strikethrough_whitespace(
+                    "   ",
+                    Color::DarkRed,
+                    Styles::BOLD | Styles::STRIKETHROUGH
+                )
+            ),
+            (1, Span::new_styled("BLAZE", Color::DarkRed, Styles::BOLD)),
+        ]
+    );
+    // The blank line 2 doesn't yield anything
+    assert_eq!(
+        lines[6],
+        (
+            3,
+            Span::new_styled("        ", Color::Aqua, Styles::empty())
+        )
+    );
+}
+
+#[test]
+fn lines_skip_blank_lines() {
+    assert_eq!(
+        SpanIter::new("a\n\n§cb\n").lines().collect::<Vec<_>>(),
+        vec![
+            (0, Span::new_plain("a")),
+            (2, Span::new_styled("b", Color::Red, Styles::empty())),
+        ]
+    );
+}
+
+mod crlf {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MOTD: &str = "§6line1\r\n§lline2";
+
+    #[test]
+    fn two_lines() {
+        assert_eq!(
+            SpanIter::new(MOTD).lines().collect::<Vec<_>>(),
+            vec![
+                (0, Span::new_styled("line1", Color::Gold, Styles::empty())),
+                (1, Span::new_styled("line2", Color::Gold, Styles::BOLD)),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_lines_as_lf() {
+        for s in SERVER_MOTDS {
+            let crlf = s.replace('\n', "\r\n");
+
+            assert_eq!(
+                SpanIter::new(&crlf).lines().collect::<Vec<_>>(),
+                SpanIter::new(s).lines().collect::<Vec<_>>(),
+                "{:?}",
+                crlf
+            );
+            assert_eq!(
+                SpanIter::new(&crlf).display_width(),
+                SpanIter::new(s).display_width()
+            );
+        }
+    }
+
+    #[test]
+    fn unchanged_width() {
+        assert_eq!(
+            SpanIter::new(MOTD).display_width(),
+            SpanIter::new("§6line1\n§lline2").display_width()
+        );
+        assert_eq!(SpanIter::new(MOTD).display_width(), 10);
+        assert_eq!(Span::new_plain("a\rb").display_width(0), 2);
+    }
+
+    #[test]
+    fn lone_cr_is_a_line_break() {
+        assert_eq!(
+            SpanIter::new("a\rb\r\rc").lines().collect::<Vec<_>>(),
+            vec![
+                (0, Span::new_plain("a")),
+                (1, Span::new_plain("b")),
+                (3, Span::new_plain("c")),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_across_spans() {
+        assert_eq!(
+            SpanIter::new("a\r§6\nb").lines().collect::<Vec<_>>(),
+            vec![
+                (0, Span::new_plain("a")),
+                (1, Span::new_styled("b", Color::Gold, Styles::empty())),
+            ]
+        );
+        assert_eq!(
+            SpanIter::new("a\r§6\n\nb").lines().collect::<Vec<_>>(),
+            vec![
+                (0, Span::new_plain("a")),
+                (2, Span::new_styled("b", Color::Gold, Styles::empty())),
+            ]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn collapse() {
+        assert_eq!(
+            collapse_blank_lines(spans("§6a\r\n\r\n\r\nb"), 1),
+            vec![
+                Span::new_styled("a\r\n", Color::Gold, Styles::empty()),
+                Span::new_styled("b", Color::Gold, Styles::empty()),
+            ]
+        );
+        assert_eq!(
+            collapse_blank_lines(spans("a\r\n\n\r\r\nb"), 2),
+            vec![Span::new_plain("a\r\n\n"), Span::new_plain("b")]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn collapse_lone_cr() {
+        assert_eq!(
+            collapse_blank_lines(spans("a\r\r\rb"), 1),
+            vec![Span::new_plain("a\r"), Span::new_plain("b")]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn collapse_split_across_spans() {
+        let s = "a\r§6\n\r\n§cb";
+
+        assert_eq!(
+            collapse_blank_lines(spans(s), 1),
+            vec![
+                Span::new_plain("a\r"),
+                Span::new_styled("\n", Color::Gold, Styles::empty()),
+                Span::new_styled("b", Color::Red, Styles::empty()),
+            ]
+        );
+        assert_eq!(
+            collapse_blank_lines(spans(s), 0),
+            vec![
+                Span::new_plain("a"),
+                Span::new_styled("b", Color::Red, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_on_newline() {
+        assert_eq!(
+            SpanIter::new(MOTD)
+                .split_on_newline(true)
+                .collect::<Vec<_>>(),
+            vec![
+                Span::new_styled("line1\r\n", Color::Gold, Styles::empty()),
+                Span::new_styled("line2", Color::Gold, Styles::BOLD),
+            ]
+        );
+        assert_eq!(
+            SpanIter::new("a\rb\r\nc")
+                .split_on_newline(true)
+                .collect::<Vec<_>>(),
+            vec![
+                Span::new_plain("a\r"),
+                Span::new_plain("b\r\n"),
+                Span::new_plain("c"),
+            ]
+        );
+    }
+}
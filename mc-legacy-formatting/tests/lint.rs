@@ -0,0 +1,288 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    lint, lint_with_start_char, Diagnostic, DiagnosticKind, Severity, Styles, TargetVersion,
+};
+use pretty_assertions::assert_eq;
+
+fn diagnostics(s: &str) -> Vec<Diagnostic> {
+    lint(s).collect()
+}
+
+fn diagnostic(range: std::ops::Range<usize>, kind: DiagnosticKind) -> Diagnostic {
+    Diagnostic { range, kind }
+}
+
+#[test]
+fn messy_fixture() {
+    assert_eq!(
+        diagnostics("§f§f §7§l§6§ltext§o"),
+        vec![
+            diagnostic(0..3, DiagnosticKind::RedundantColor),
+            diagnostic(7..10, DiagnosticKind::RedundantColor),
+            diagnostic(10..13, DiagnosticKind::WipedStyle),
+            diagnostic(23..26, DiagnosticKind::TrailingCodes),
+        ]
+    );
+}
+
+#[test]
+fn clean_text() {
+    assert_eq!(diagnostics(""), vec![]);
+    assert_eq!(diagnostics("plain text"), vec![]);
+    assert_eq!(diagnostics("§6§lGold §r§7gray"), vec![]);
+}
+
+#[test]
+fn resets_override_too() {
+    assert_eq!(
+        diagnostics("§a§l§rtext"),
+        vec![
+            diagnostic(0..3, DiagnosticKind::RedundantColor),
+            diagnostic(3..6, DiagnosticKind::WipedStyle),
+        ]
+    );
+}
+
+#[test]
+fn trailing_codes_are_reported_once() {
+    assert_eq!(
+        diagnostics("text§a§L§r"),
+        vec![diagnostic(4..13, DiagnosticKind::TrailingCodes)]
+    );
+    assert_eq!(
+        diagnostics("§6"),
+        vec![diagnostic(0..3, DiagnosticKind::TrailingCodes)]
+    );
+}
+
+#[test]
+fn uppercase_codes() {
+    assert_eq!(
+        diagnostics("§Ltext §C§Ftext"),
+        vec![
+            diagnostic(0..3, DiagnosticKind::UppercaseCode('L')),
+            diagnostic(8..11, DiagnosticKind::RedundantColor),
+            diagnostic(8..11, DiagnosticKind::UppercaseCode('C')),
+            diagnostic(11..14, DiagnosticKind::UppercaseCode('F')),
+        ]
+    );
+}
+
+#[test]
+fn unknown_codes() {
+    assert_eq!(
+        diagnostics("§zoops §§a§"),
+        vec![
+            diagnostic(0..3, DiagnosticKind::UnknownCode(Some('z'))),
+            diagnostic(8..12, DiagnosticKind::UnknownCode(Some('§'))),
+            diagnostic(13..15, DiagnosticKind::UnknownCode(None)),
+        ]
+    );
+}
+
+#[test]
+fn too_long() {
+    let s = "§6Hi §zthere";
+
+    // The start char of an unknown code counts as visible text
+    assert_eq!(
+        lint(s).with_max_visible_len(Some(3)).collect::<Vec<_>>(),
+        vec![
+            diagnostic(6..9, DiagnosticKind::UnknownCode(Some('z'))),
+            diagnostic(6..14, DiagnosticKind::TooLong),
+        ]
+    );
+    assert_eq!(
+        lint(s).with_max_visible_len(Some(5)).collect::<Vec<_>>(),
+        vec![
+            diagnostic(6..9, DiagnosticKind::UnknownCode(Some('z'))),
+            diagnostic(9..14, DiagnosticKind::TooLong),
+        ]
+    );
+    assert_eq!(
+        lint(s).with_max_visible_len(Some(10)).collect::<Vec<_>>(),
+        vec![diagnostic(6..9, DiagnosticKind::UnknownCode(Some('z')))]
+    );
+}
+
+#[test]
+fn custom_start_char() {
+    assert_eq!(
+        lint_with_start_char("&a&btext&", '&').collect::<Vec<_>>(),
+        vec![
+            diagnostic(0..2, DiagnosticKind::RedundantColor),
+            diagnostic(8..9, DiagnosticKind::UnknownCode(None)),
+        ]
+    );
+}
+
+#[test]
+fn severities_and_messages() {
+    let diagnostic = diagnostic(10..13, DiagnosticKind::WipedStyle);
+
+    assert_eq!(diagnostic.severity(), Severity::Warning);
+    assert_eq!(
+        diagnostic.to_string(),
+        "warning: style code is cleared before any text at bytes 10..13"
+    );
+    assert_eq!(DiagnosticKind::RedundantColor.severity(), Severity::Info);
+    assert!(Severity::Info < Severity::Warning);
+}
+
+#[test]
+fn server_motds() {
+    for motd in SERVER_MOTDS {
+        for diagnostic in lint(motd).with_max_visible_len(Some(40)) {
+            assert!(motd.get(diagnostic.range.clone()).is_some(), "{:?}", motd);
+        }
+    }
+
+    assert_eq!(
+        diagnostics(PURPLE_WTF),
+        vec![
+            diagnostic(28..31, DiagnosticKind::RedundantColor),
+            diagnostic(31..34, DiagnosticKind::WipedStyle),
+            diagnostic(124..127, DiagnosticKind::UppercaseCode('R')),
+            diagnostic(172..175, DiagnosticKind::UppercaseCode('D')),
+            diagnostic(175..178, DiagnosticKind::UppercaseCode('L')),
+        ]
+    );
+}
+
+mod target_version {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const HEX: &str = "§x§f§f§8§8§0§0Orange §#00ff00green";
+
+    fn diagnostics_for(s: &str, version: TargetVersion) -> Vec<Diagnostic> {
+        lint(s).with_target_version(Some(version)).collect()
+    }
+
+    #[test]
+    fn hex_flagged_before_1_16() {
+        assert_eq!(
+            diagnostics_for(HEX, TargetVersion::Java(8)),
+            vec![
+                diagnostic(0..21, DiagnosticKind::HexColor),
+                diagnostic(28..37, DiagnosticKind::HexColor),
+            ]
+        );
+        assert_eq!(diagnostics_for(HEX, TargetVersion::Java(15)).len(), 2);
+        assert_eq!(diagnostics_for(HEX, TargetVersion::Bedrock).len(), 2);
+    }
+
+    #[test]
+    fn hex_passes_on_1_16() {
+        assert_eq!(diagnostics_for(HEX, TargetVersion::Java(16)), vec![]);
+        assert_eq!(diagnostics_for(HEX, TargetVersion::Java(20)), vec![]);
+    }
+
+    #[test]
+    fn supported_hex_is_checked_like_a_color() {
+        assert_eq!(
+            diagnostics_for("§#ff8800§atext§x§f§f§8§8§0§0", TargetVersion::Java(16)),
+            vec![
+                diagnostic(0..9, DiagnosticKind::RedundantColor),
+                diagnostic(16..37, DiagnosticKind::TrailingCodes),
+            ]
+        );
+    }
+
+    #[test]
+    fn incomplete_hex_isnt_a_hex_color() {
+        // Without all six digits, `§x` is just an unknown code
+        assert_eq!(
+            diagnostics_for("§x§f§fshort", TargetVersion::Java(8)),
+            vec![
+                diagnostic(0..3, DiagnosticKind::UnknownCode(Some('x'))),
+                diagnostic(3..6, DiagnosticKind::RedundantColor),
+            ]
+        );
+    }
+
+    #[test]
+    fn bedrock_colors_on_java() {
+        let s = "§gMinecoin §ecoin §Uamethyst";
+
+        assert_eq!(
+            diagnostics_for(s, TargetVersion::Java(19)),
+            vec![
+                diagnostic(0..3, DiagnosticKind::BedrockColor('g')),
+                diagnostic(20..23, DiagnosticKind::BedrockColor('U')),
+            ]
+        );
+        assert_eq!(
+            diagnostics_for(s, TargetVersion::Bedrock),
+            vec![diagnostic(20..23, DiagnosticKind::UppercaseCode('U'))]
+        );
+    }
+
+    #[test]
+    fn resin_on_java() {
+        assert_eq!(
+            diagnostics_for("§vResin", TargetVersion::Java(21)),
+            vec![diagnostic(0..3, DiagnosticKind::BedrockColor('v'))]
+        );
+        assert_eq!(diagnostics_for("§vResin", TargetVersion::Bedrock), vec![]);
+    }
+
+    #[test]
+    fn java_styles_on_bedrock() {
+        let s = "§l§mstruck §nunder";
+
+        assert_eq!(
+            diagnostics_for(s, TargetVersion::Bedrock),
+            vec![
+                diagnostic(
+                    3..6,
+                    DiagnosticKind::UnsupportedStyle(Styles::STRIKETHROUGH)
+                ),
+                diagnostic(13..16, DiagnosticKind::UnsupportedStyle(Styles::UNDERLINED)),
+            ]
+        );
+        assert_eq!(diagnostics_for(s, TargetVersion::Java(8)), vec![]);
+    }
+
+    #[test]
+    fn custom_start_char() {
+        assert_eq!(
+            lint_with_start_char("&6gold &#ff8800 &gcoin §g", '&')
+                .with_target_version(Some(TargetVersion::Java(12)))
+                .collect::<Vec<_>>(),
+            vec![
+                diagnostic(7..15, DiagnosticKind::HexColor),
+                diagnostic(16..18, DiagnosticKind::BedrockColor('g')),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixtures_are_fine_on_java() {
+        for s in SERVER_MOTDS {
+            assert_eq!(
+                diagnostics_for(s, TargetVersion::Java(8)),
+                diagnostics(s),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn display() {
+        let diagnostics = diagnostics_for("§x§f§f§8§8§0§0a §g", TargetVersion::Java(8));
+
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning: hex color isn't supported by the target version at bytes 0..21"
+        );
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "warning: color code is only supported by Bedrock Edition at bytes 23..26"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use mc_legacy_formatting::{
+    grayscale, map_style, strip_obfuscation, Color, Span, SpanIter, Styles,
+};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn sees_every_span_once() {
+    let s = "plain §6gold §lbold§r §mstruck";
+    let mut seen = Vec::new();
+    let mapped: Vec<Span> = map_style(SpanIter::new(s), |color, styles| {
+        seen.push((color, styles));
+        (color, styles)
+    })
+    .collect();
+
+    assert_eq!(mapped, SpanIter::new(s).collect::<Vec<_>>());
+    assert_eq!(
+        seen,
+        SpanIter::new(s)
+            .map(|span| (span.color(), span.styles()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn plain_is_white_without_styles() {
+    let mut seen = Vec::new();
+    let mapped: Vec<Span> = map_style(SpanIter::new("plain"), |color, styles| {
+        seen.push((color, styles));
+        (Color::Gray, styles)
+    })
+    .collect();
+
+    assert_eq!(seen, vec![(Color::White, Styles::empty())]);
+    assert_eq!(
+        mapped,
+        vec![Span::new_styled("plain", Color::Gray, Styles::empty())]
+    );
+}
+
+#[test]
+fn reclassifies_strikethrough_whitespace() {
+    let add = |color, styles| (color, styles | Styles::STRIKETHROUGH);
+    assert_eq!(
+        map_style(SpanIter::new("§6   "), add).collect::<Vec<_>>(),
+        vec![Span::new_strikethrough_whitespace(
+            "   ",
+            Color::Gold,
+            Styles::STRIKETHROUGH
+        )]
+    );
+
+    let remove = |color, styles: Styles| (color, styles - Styles::STRIKETHROUGH);
+    assert_eq!(
+        map_style(SpanIter::new("§6§m   "), remove).collect::<Vec<_>>(),
+        vec![Span::new_styled("   ", Color::Gold, Styles::empty())]
+    );
+    assert_eq!(
+        map_style(SpanIter::new("§m   "), remove).collect::<Vec<_>>(),
+        vec![Span::new_plain("   ")]
+    );
+}
+
+#[test]
+fn urls_stay_urls() {
+    let mapped: Vec<Span> = map_style(
+        SpanIter::new("§6see https://example.com").with_url_detection(true),
+        grayscale,
+    )
+    .collect();
+
+    assert_eq!(
+        mapped,
+        vec![
+            Span::new_styled("see ", Color::Gray, Styles::empty()),
+            Span::new_url("https://example.com", Color::Gray, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn grayscale_picks_nearest_gray() {
+    for gray in [Color::Black, Color::DarkGray, Color::Gray, Color::White] {
+        assert_eq!(grayscale(gray, Styles::BOLD), (gray, Styles::BOLD));
+    }
+
+    assert_eq!(grayscale(Color::DarkBlue, Styles::empty()).0, Color::Black);
+    assert_eq!(grayscale(Color::Blue, Styles::empty()).0, Color::DarkGray);
+    assert_eq!(grayscale(Color::Red, Styles::empty()).0, Color::Gray);
+    assert_eq!(grayscale(Color::Yellow, Styles::empty()).0, Color::White);
+    assert_eq!(
+        grayscale(Color::Rgb(40, 40, 40), Styles::empty()).0,
+        Color::Black
+    );
+    assert_eq!(
+        grayscale(Color::Rgb(45, 45, 45), Styles::empty()).0,
+        Color::DarkGray
+    );
+}
+
+#[test]
+fn strip_obfuscation_keeps_other_styles() {
+    let mapped: Vec<Span> =
+        map_style(SpanIter::new("§c§k§lsecret§r!"), strip_obfuscation).collect();
+
+    assert_eq!(
+        mapped,
+        vec![
+            Span::new_styled("secret", Color::Red, Styles::BOLD),
+            Span::new_plain("!"),
+        ]
+    );
+}
+
+#[test]
+fn keeps_background() {
+    let spans: Vec<Span> = SpanIter::new("§^1hi")
+        .with_background_prefix(Some('^'))
+        .collect();
+    let mapped: Vec<Span> =
+        map_style(spans.iter().copied(), |color, styles| (color, styles)).collect();
+
+    assert_eq!(mapped, spans);
+    assert_eq!(mapped[0].background(), Some(Color::DarkBlue));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, MultiSpanIter, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+/// Split `s` into chunks of roughly `size` chars, never splitting a start char
+/// from the code after it
+fn chunks(s: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut len = 0;
+    let mut prev = None;
+
+    for (idx, c) in s.char_indices() {
+        if len >= size && prev != Some('§') {
+            chunks.push(&s[start..idx]);
+            start = idx;
+            len = 0;
+        }
+
+        len += 1;
+        prev = Some(c);
+    }
+
+    chunks.push(&s[start..]);
+    chunks
+}
+
+#[test]
+fn chunks_match_joined() {
+    for s in SERVER_MOTDS {
+        for size in 1..=8 {
+            let chunks = chunks(s, size);
+            assert_eq!(chunks.concat(), *s);
+
+            let labeled: Vec<_> = MultiSpanIter::new(chunks.iter().copied()).collect();
+
+            for (idx, span) in &labeled {
+                assert!(chunks[*idx].contains(span.text()));
+            }
+            assert_eq!(
+                merged(labeled.into_iter().map(|(_, span)| span)),
+                merged(SpanIter::new(s)),
+                "chunk size {}",
+                size
+            );
+        }
+    }
+}
+
+#[test]
+fn sign_lines() {
+    let lines = ["&6&l[Shop]", "", "&bBuy: &f10", "&m   "];
+
+    assert_eq!(
+        MultiSpanIter::new(lines)
+            .with_start_char('&')
+            .collect::<Vec<_>>(),
+        vec![
+            (0, Span::new_styled("[Shop]", Color::Gold, Styles::BOLD)),
+            (2, Span::new_styled("Buy: ", Color::Aqua, Styles::empty())),
+            (2, Span::new_plain("10")),
+            (
+                3,
+                Span::new_strikethrough_whitespace("   ", Color::White, Styles::STRIKETHROUGH)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn lone_start_char_at_end_of_string() {
+    assert_eq!(
+        MultiSpanIter::new(["§ctext §", "cmore"]).collect::<Vec<_>>(),
+        vec![
+            (0, Span::new_styled("text §", Color::Red, Styles::empty())),
+            (1, Span::new_styled("cmore", Color::Red, Styles::empty())),
+        ]
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use std::borrow::Cow;
+
+use mc_legacy_formatting::{normalize_section_escapes, Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn unicode_escape_parses_as_code() {
+    let s = normalize_section_escapes("\\u00a74red");
+
+    assert_eq!(s, "§4red");
+    assert_eq!(
+        spans(&s),
+        vec![Span::new_styled("red", Color::DarkRed, Styles::empty())]
+    );
+}
+
+#[test]
+fn hex_escape_and_casing() {
+    assert_eq!(
+        normalize_section_escapes(r"\xa7aGreen \XA7lbold \u00A7r"),
+        r"§aGreen \XA7lbold §r"
+    );
+    assert_eq!(normalize_section_escapes(r"\xA7o"), "§o");
+}
+
+#[test]
+fn escaped_backslash_left_alone() {
+    assert_eq!(
+        normalize_section_escapes(r"\\u00a7 \\\u00a7c"),
+        r"\\u00a7 \\§c"
+    );
+}
+
+#[test]
+fn partial_escapes_left_alone() {
+    for s in [r"\u00a", r"\x", r"\u00b7", r"trailing \", r"\u00é7"] {
+        assert!(matches!(normalize_section_escapes(s), Cow::Borrowed(b) if b == s));
+    }
+}
+
+#[test]
+fn fixtures_unchanged() {
+    for s in SERVER_MOTDS {
+        assert!(matches!(normalize_section_escapes(s), Cow::Borrowed(_)));
+    }
+}
+
+#[test]
+fn escaped_fixtures_parse_the_same() {
+    for s in SERVER_MOTDS {
+        let escaped = s.replace('§', r"\u00a7");
+
+        assert_eq!(
+            SpanIter::new(&normalize_section_escapes(&escaped)).collect::<Vec<_>>(),
+            spans(s)
+        );
+    }
+}
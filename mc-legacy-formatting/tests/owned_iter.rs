@@ -0,0 +1,77 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, OwnedSpan, OwnedSpanIter, SpanExt, Styles};
+use pretty_assertions::assert_eq;
+
+fn owned_spans(s: &str) -> Vec<OwnedSpan> {
+    spans(s).into_iter().map(OwnedSpan::from).collect()
+}
+
+/// Build the input string inside the function and hand back an iterator over it
+fn motd_spans(motd: &str) -> OwnedSpanIter {
+    let s = motd.to_string();
+    s.into_span_iter()
+}
+
+#[test]
+fn matches_span_iter() {
+    for s in SERVER_MOTDS {
+        assert_eq!(OwnedSpanIter::new(*s).collect::<Vec<_>>(), owned_spans(s));
+    }
+}
+
+#[test]
+fn outlives_source() {
+    let iter = {
+        let source = String::from("§4§lBig red §r§7text");
+        let iter = source.clone().into_span_iter();
+        drop(source);
+        iter
+    };
+
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        vec![
+            OwnedSpan::new_styled("Big red ", Color::DarkRed, Styles::BOLD),
+            OwnedSpan::new_styled("text", Color::Gray, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn returned_from_function() {
+    let iter = motd_spans(PLAY_MC_BLAZE_COM);
+
+    assert_eq!(iter.collect::<Vec<_>>(), owned_spans(PLAY_MC_BLAZE_COM));
+}
+
+#[test]
+fn custom_start_char() {
+    let iter = OwnedSpanIter::new("&aGreen &&lbold &cred").with_start_char('&');
+
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        vec![
+            OwnedSpan::new_styled("Green &&lbold ", Color::Green, Styles::empty()),
+            OwnedSpan::new_styled("red", Color::Red, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn only_codes() {
+    assert_eq!(OwnedSpanIter::new("§a§l§r").next(), None);
+    assert_eq!(OwnedSpanIter::new("").next(), None);
+}
+
+#[test]
+fn into_inner() {
+    let mut iter = OwnedSpanIter::new("§aone §btwo");
+    iter.next();
+
+    assert_eq!(iter.into_inner(), "§aone §btwo");
+}
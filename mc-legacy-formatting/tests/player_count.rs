@@ -0,0 +1,128 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, MotdSpan, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+fn player_counts(s: &str) -> Vec<MotdSpan<'_>> {
+    SpanIter::new(s).player_counts().collect()
+}
+
+#[test]
+fn count_split_across_spans() {
+    let counts = player_counts(PLAY_MC_BLAZE_COM);
+
+    assert_eq!(
+        counts[counts.len() - 4..],
+        [
+            MotdSpan::Span(Span::new_styled(
+                "www.mc-blaze.com\n            ",
+                Color::Aqua,
+                Styles::empty()
+            )),
+            MotdSpan::PlayerCount {
+                online: 116,
+                max: 1000,
+                text: "[§4116§7 /§4 1000§8]"
+            },
+            MotdSpan::Span(Span::new_styled("\n", Color::DarkGray, Styles::empty())),
+            MotdSpan::Span(Span::new_styled(
+                "⌞--------------------⌟",
+                Color::DarkRed,
+                Styles::BOLD | Styles::STRIKETHROUGH
+            )),
+        ]
+    );
+}
+
+#[test]
+fn count_within_one_span() {
+    assert_eq!(
+        player_counts("§eOnline [5/20] now"),
+        vec![
+            MotdSpan::Span(Span::new_styled("Online ", Color::Yellow, Styles::empty())),
+            MotdSpan::PlayerCount {
+                online: 5,
+                max: 20,
+                text: "[5/20]"
+            },
+            MotdSpan::Span(Span::new_styled(" now", Color::Yellow, Styles::empty())),
+        ]
+    );
+}
+
+#[test]
+fn several_counts() {
+    assert_eq!(
+        player_counts("[ 1 / 2 ][3/4]"),
+        vec![
+            MotdSpan::PlayerCount {
+                online: 1,
+                max: 2,
+                text: "[ 1 / 2 ]"
+            },
+            MotdSpan::PlayerCount {
+                online: 3,
+                max: 4,
+                text: "[3/4]"
+            },
+        ]
+    );
+}
+
+#[test]
+fn not_counts() {
+    for s in [
+        "[VIP] players",
+        "[5 / 20",
+        "[5 / 20 players]",
+        "[/20]",
+        "[5 /]",
+        "[5 - 20]",
+        "[99999999999 / 1]",
+        "§8[§a5\n/ 20§8]",
+    ] {
+        assert_eq!(
+            player_counts(s),
+            spans(s).into_iter().map(MotdSpan::Span).collect::<Vec<_>>(),
+            "{:?}",
+            s
+        );
+    }
+}
+
+#[test]
+fn false_start_before_count() {
+    assert_eq!(
+        player_counts("[x] [1/2]"),
+        vec![
+            MotdSpan::Span(Span::new_plain("[x] ")),
+            MotdSpan::PlayerCount {
+                online: 1,
+                max: 2,
+                text: "[1/2]"
+            },
+        ]
+    );
+}
+
+#[test]
+fn fixtures_keep_text() {
+    for s in SERVER_MOTDS {
+        let text: String = SpanIter::new(s)
+            .player_counts()
+            .map(|span| match span {
+                MotdSpan::Span(span) => span.text().to_string(),
+                MotdSpan::PlayerCount { text, .. } => {
+                    SpanIter::new(text).map(|span| span.text()).collect()
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            text,
+            SpanIter::new(s).map(|span| span.text()).collect::<String>()
+        );
+    }
+}
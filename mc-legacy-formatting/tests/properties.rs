@@ -0,0 +1,126 @@
+//! Property tests for invariants that should hold for any input
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{apply_code, code_ranges, CodeKind, Color, Span, SpanIter, Styles};
+use proptest::prelude::*;
+
+/// A string made mostly of start chars and the chars that can follow them
+///
+/// Inputs are built out of single chars so that they shrink by dropping chars,
+/// and the simpler alternatives come first so that they shrink towards plain
+/// text. Both `§` and `&` are always in the mix, so whichever one isn't the
+/// start char is just text.
+fn input() -> impl Strategy<Value = String> {
+    let c = prop_oneof![
+        2 => Just('a'),
+        6 => prop_oneof![Just('§'), Just('&')],
+        4 => prop_oneof![Just('6'), Just('c'), Just('F'), Just('l'), Just('m'), Just('K'), Just('r')],
+        2 => prop_oneof![Just('z'), Just('x'), Just('#')],
+        3 => prop_oneof![Just(' '), Just('\t'), Just('\n')],
+        1 => prop_oneof![Just('é'), Just('中'), Just('😀')],
+    ];
+
+    prop::collection::vec(c, 0..40).prop_map(|chars| chars.into_iter().collect())
+}
+
+fn start_char() -> impl Strategy<Value = char> {
+    prop_oneof![Just('§'), Just('&')]
+}
+
+/// Remove every valid code from `s`, pairing start chars with the char after
+/// them the same way the parser does
+fn without_codes(s: &str, start_char: char) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        out.push(c);
+
+        if c == start_char {
+            if let Some(code) = chars.next() {
+                if apply_code(code, &mut Color::White, &mut Styles::empty()) {
+                    out.pop();
+                } else {
+                    out.push(code);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+proptest! {
+    #[test]
+    fn text_is_input_without_codes(s in input(), start_char in start_char()) {
+        let text: String = SpanIter::new(&s)
+            .with_start_char(start_char)
+            .map(|span| span.text())
+            .collect();
+
+        prop_assert_eq!(text, without_codes(&s, start_char));
+    }
+
+    #[test]
+    fn text_never_contains_codes(s in input(), start_char in start_char()) {
+        for span in SpanIter::new(&s).with_start_char(start_char) {
+            let codes: Vec<_> = code_ranges(span.text(), start_char)
+                .filter(|(_, kind)| *kind != CodeKind::Invalid)
+                .collect();
+
+            prop_assert!(codes.is_empty(), "{:?} contains {:?}", span, codes);
+        }
+    }
+
+    #[test]
+    fn terminates_and_is_fused(s in input(), start_char in start_char()) {
+        let mut span_iter = SpanIter::new(&s).with_start_char(start_char);
+
+        // Every span but a trailing empty one has at least one char of text
+        prop_assert!(span_iter.by_ref().take(s.len() + 2).count() <= s.len() + 1);
+        for _ in 0..3 {
+            prop_assert_eq!(span_iter.next(), None);
+        }
+    }
+
+    #[test]
+    fn strikethrough_whitespace_is_whitespace(s in input(), start_char in start_char()) {
+        for span in SpanIter::new(&s).with_start_char(start_char) {
+            if let Span::StrikethroughWhitespace { text, styles, .. } = span {
+                prop_assert!(text.chars().all(|c| c.is_ascii_whitespace()), "{:?}", span);
+                prop_assert!(styles.contains(Styles::STRIKETHROUGH), "{:?}", span);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+proptest! {
+    #[test]
+    fn round_trip_is_stable(s in input(), start_char in start_char()) {
+        use mc_legacy_formatting::to_legacy_string;
+
+        let spans: Vec<Span> = SpanIter::new(&s)
+            .with_start_char(start_char)
+            .filter(|span| !span.is_empty())
+            .collect();
+        let serialized = to_legacy_string(&spans, start_char);
+        // Start chars in the text are escaped by the serializer
+        let reparsed: Vec<Span> = SpanIter::new(&serialized)
+            .with_start_char(start_char)
+            .with_doubled_escape(true)
+            .filter(|span| !span.is_empty())
+            .collect();
+
+        prop_assert_eq!(
+            merged(reparsed.iter().copied()),
+            merged(spans.iter().copied()),
+            "serialized as {:?}",
+            serialized
+        );
+        prop_assert_eq!(to_legacy_string(&reparsed, start_char), serialized);
+    }
+}
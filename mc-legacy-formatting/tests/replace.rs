@@ -0,0 +1,112 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{find_all_visible, replace_visible, Color, Styles};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+#[test]
+fn across_code_boundary() {
+    let s = "§aSte§bve";
+
+    assert_eq!(replace_visible(s, "Steve", "Alex"), "§aAlex");
+    assert_eq!(
+        merged(spans(&replace_visible(s, "Steve", "Alex")).into_iter()),
+        vec![("Alex".to_string(), Color::Green, Styles::empty())]
+    );
+}
+
+#[test]
+fn formatting_after_match_is_restored() {
+    // The `§b` inside the match is removed, but the text after it still needs
+    // to be aqua
+    let s = "§aSte§bve joined";
+    let replaced = replace_visible(s, "Steve", "Alex");
+
+    assert_eq!(replaced, "§aAlex§b joined");
+    assert_eq!(
+        merged(spans(&replaced).into_iter()),
+        vec![
+            ("Alex".to_string(), Color::Green, Styles::empty()),
+            (" joined".to_string(), Color::Aqua, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn longer_and_shorter_replacements() {
+    let s = "§7Hi §6bob§7, bye §6bob";
+
+    assert_eq!(
+        replace_visible(s, "bob", "Bartholomew"),
+        "§7Hi §6Bartholomew§7, bye §6Bartholomew"
+    );
+    assert_eq!(replace_visible(s, "bob", "B"), "§7Hi §6B§7, bye §6B");
+    // The original `§7` is kept as written even though it's redundant now
+    assert_eq!(replace_visible(s, "bob", ""), "§7Hi §7, bye ");
+}
+
+#[test]
+fn match_in_the_middle_of_a_span() {
+    assert_eq!(
+        replace_visible("§c§lwhat the heck!", "heck", "****"),
+        "§c§lwhat the ****!"
+    );
+}
+
+#[test]
+fn overlapping_matches() {
+    // Matches are found from the left and don't overlap, like `str::replace`
+    assert_eq!(replace_visible("§aaaa§ba", "aa", "x"), "§axx");
+    assert_eq!(replace_visible("§aaaa", "aa", "x"), "§axa");
+}
+
+#[test]
+fn replacement_with_codes() {
+    let replaced = replace_visible("§6Hello there", "there", "§lworld");
+
+    assert_eq!(replaced, "§6Hello §lworld");
+    assert_eq!(
+        merged(spans(&replaced).into_iter()),
+        vec![
+            ("Hello ".to_string(), Color::Gold, Styles::empty()),
+            ("world".to_string(), Color::Gold, Styles::BOLD),
+        ]
+    );
+}
+
+#[test]
+fn no_matches() {
+    for s in SERVER_MOTDS {
+        assert_eq!(
+            merged(spans(&replace_visible(s, "§", "x")).into_iter()),
+            merged(spans(s).into_iter())
+        );
+        assert_eq!(
+            merged(spans(&replace_visible(s, "", "x")).into_iter()),
+            merged(spans(s).into_iter())
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_inputs(s in strings_of(&['§', 'a', 'b', 'l', 'r', ' ', 'x'])) {
+        let stripped: String = spans(&s).iter().map(|span| span.text()).collect();
+        prop_assume!(!stripped.contains('§'));
+
+        for needle in ["a", "ab", "x x", "ba"] {
+            let replaced = replace_visible(&s, needle, "_");
+            let text: String = spans(&replaced).iter().map(|span| span.text()).collect();
+
+            assert_eq!(text, stripped.replace(needle, "_"), "{:?}", s);
+            assert_eq!(
+                find_all_visible(&replaced, needle).count(),
+                stripped.replace(needle, "_").matches(needle).count()
+            );
+        }
+    }
+}
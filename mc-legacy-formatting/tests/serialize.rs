@@ -0,0 +1,637 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    code_ranges, code_str, escape, minify, minify_with_code_case, to_legacy_string,
+    translate_start_char, translate_start_char_escaped, CodeCase, CodeKind, Color,
+    LegacyStringBuilder, Span, SpanIter, Styles,
+};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+fn reparse(s: &str, start_char: char) -> Vec<Span<'_>> {
+    SpanIter::new(s)
+        .with_start_char(start_char)
+        .with_doubled_escape(true)
+        .collect()
+}
+
+#[test]
+fn fixtures_round_trip() {
+    for s in SERVER_MOTDS {
+        let serialized = to_legacy_string(&spans(s), '§');
+
+        assert_eq!(
+            merged(reparse(&serialized, '§').into_iter()),
+            merged(SpanIter::new(s)),
+        );
+    }
+}
+
+#[test]
+fn escapes_start_char_in_text() {
+    let spans = [
+        Span::new_styled(
+            "https://example.com/?a=1&b=2",
+            Color::Aqua,
+            Styles::UNDERLINED,
+        ),
+        Span::new_plain(" & more &&"),
+    ];
+    let serialized = to_legacy_string(&spans, '&');
+
+    assert_eq!(
+        serialized,
+        "&b&nhttps://example.com/?a=1&&b=2&r && more &&&&"
+    );
+    assert_eq!(
+        merged(reparse(&serialized, '&').into_iter()),
+        merged(spans.iter().copied())
+    );
+}
+
+#[test]
+fn minimal_codes() {
+    let spans = [
+        Span::new_styled("a", Color::Gold, Styles::BOLD),
+        Span::new_styled("b", Color::Gold, Styles::BOLD | Styles::ITALIC),
+        Span::new_styled("c", Color::Gold, Styles::ITALIC),
+        Span::new_styled("d", Color::Red, Styles::ITALIC),
+        Span::new_plain("e"),
+        Span::new_plain("f"),
+        Span::new_styled("g", Color::White, Styles::RANDOM),
+    ];
+
+    assert_eq!(to_legacy_string(&spans, '§'), "§6§la§ob§6§oc§c§od§ref§kg");
+}
+
+#[test]
+fn empty_spans_emit_nothing() {
+    let mut builder = LegacyStringBuilder::new();
+    builder.push(Span::new_styled("text", Color::Red, Styles::empty()));
+    builder.push(Span::new_styled("", Color::Gold, Styles::BOLD));
+
+    assert_eq!(builder.finish(), "§ctext");
+
+    let spans = [
+        Span::new_plain("X"),
+        Span::new_styled("", Color::Green, Styles::empty()),
+        Span::new_styled("", Color::Aqua, Styles::empty()),
+    ];
+    assert_eq!(to_legacy_string(&spans, '§'), "X");
+}
+
+#[test]
+fn rgb_uses_hex_format() {
+    assert_eq!(
+        to_legacy_string(
+            &[Span::new_styled(
+                "hex",
+                Color::Rgb(0x12, 0xab, 0xef),
+                Styles::BOLD
+            )],
+            '&'
+        ),
+        "&x&1&2&a&b&e&f&lhex"
+    );
+}
+
+mod minify {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn assert_same_spans(s: &str) {
+        let minified = minify(s);
+
+        assert!(minified.len() <= s.len(), "{:?}", s);
+        assert_eq!(
+            merged(SpanIter::new(&minified)),
+            merged(SpanIter::new(s)),
+            "{:?}",
+            s
+        );
+    }
+
+    #[test]
+    fn fixtures_keep_spans() {
+        for s in SERVER_MOTDS {
+            assert_same_spans(s);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn random_inputs_keep_spans(
+            s in strings_of(&['§', 'a', '6', 'L', 'm', 'r', 'z', ' ', 'x']),
+        ) {
+            assert_same_spans(&s);
+        }
+    }
+
+    #[test]
+    fn redundant_fixtures_shrink() {
+        for s in [
+            PLAY_LEMONCLOUD_NET,
+            PURPLE_WTF,
+            HUB_MCS_GG,
+            MC_MINEHEROES_ORG,
+        ] {
+            assert!(minify(s).len() < s.len(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn already_minimal() {
+        let s = "§4§lBLAZE§b-§6§lGAMING§b Network";
+        assert_eq!(minify(s), s);
+        assert_eq!(minify(&minify(PURPLE_WTF)), minify(PURPLE_WTF));
+    }
+
+    #[test]
+    fn trailing_codes_dropped() {
+        assert_eq!(
+            minify("basic stuff but then§o§a§e§a§m"),
+            "basic stuff but then"
+        );
+        assert_eq!(minify("X§a§b"), "X");
+        assert_eq!(minify("§a§b"), "");
+    }
+
+    fn assert_no_trailing_code(s: &str) {
+        let minified = minify(s);
+        let trailing = code_ranges(&minified, '§')
+            .last()
+            .filter(|(range, kind)| range.end == minified.len() && *kind != CodeKind::Invalid);
+
+        assert_eq!(trailing, None, "{:?} -> {:?}", s, minified);
+    }
+
+    #[test]
+    fn fixtures_never_end_with_a_code() {
+        for s in SERVER_MOTDS {
+            assert_no_trailing_code(s);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn never_ends_with_a_code(s in strings_of(&['§', 'a', 'L', 'r', 'z', 'x'])) {
+            assert_no_trailing_code(&s);
+        }
+    }
+
+    #[test]
+    fn start_chars_in_text_arent_escaped() {
+        assert_eq!(minify("§c§c§zfake §l§"), "§c§zfake §l§");
+    }
+
+    #[test]
+    fn code_case() {
+        // Both of these mix uppercase and lowercase codes
+        for s in [SUPPORTS_UPPERCASE_STYLE_CODES, PURPLE_WTF] {
+            for case in [CodeCase::Lower, CodeCase::Upper] {
+                let minified = minify_with_code_case(s, case);
+
+                for (range, kind) in code_ranges(&minified, '§') {
+                    if kind == CodeKind::Invalid {
+                        continue;
+                    }
+
+                    let code = minified[range].chars().nth(1).unwrap();
+                    let expected = match case {
+                        CodeCase::Lower => code.to_ascii_lowercase(),
+                        CodeCase::Upper => code.to_ascii_uppercase(),
+                    };
+                    assert_eq!(code, expected, "{:?}", minified);
+                }
+                assert_eq!(merged(SpanIter::new(&minified)), merged(SpanIter::new(s)));
+            }
+
+            assert_eq!(minify_with_code_case(s, CodeCase::Lower), minify(s));
+        }
+
+        assert_eq!(
+            minify_with_code_case(SUPPORTS_UPPERCASE_STYLE_CODES, CodeCase::Lower),
+            "§6§lBold §oand Italic§r §aplain §cred §kX§f§m   §rdone"
+        );
+        assert_eq!(
+            minify_with_code_case("§Lhi§A§BThere", CodeCase::Upper),
+            "§Lhi§BThere"
+        );
+        assert_eq!(
+            minify_with_code_case("§Lhi§A§BThere", CodeCase::Lower),
+            "§lhi§bThere"
+        );
+    }
+
+    #[test]
+    fn code_case_leaves_text_alone() {
+        let s = "§aabc §lDEF §zxyz";
+
+        assert_eq!(
+            minify_with_code_case(s, CodeCase::Upper),
+            "§Aabc §LDEF §zxyz"
+        );
+    }
+}
+
+mod preserve_codes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn reserialize(s: &str) -> String {
+        let mut builder = LegacyStringBuilder::new().with_escapes(false);
+        SpanIter::new(s)
+            .with_codes()
+            .for_each(|(codes, span)| builder.push_with_codes(codes, span));
+        builder.finish()
+    }
+
+    #[test]
+    fn fixtures_keep_casing() {
+        for s in SERVER_MOTDS {
+            let reserialized = reserialize(s);
+
+            assert_eq!(
+                merged(SpanIter::new(&reserialized)),
+                merged(SpanIter::new(s))
+            );
+            // Every code that's followed by text is kept exactly as written
+            assert_eq!(reserialized, s.trim_end_matches(|c| c == '§' || c == 'r'));
+        }
+    }
+
+    #[test]
+    fn uppercase_codes_kept() {
+        assert_eq!(reserialize("§D§Lhi §Ryo"), "§D§Lhi §Ryo");
+    }
+
+    #[test]
+    fn redundant_codes_kept() {
+        assert_eq!(reserialize("§f§f §7§l§6§lGold"), "§f§f §7§l§6§lGold");
+    }
+
+    #[test]
+    fn changed_formatting_falls_back() {
+        let mut builder = LegacyStringBuilder::new();
+
+        for (codes, span) in SpanIter::new("§D§Lpink §Aand green").with_codes() {
+            let span = match span {
+                Span::Styled {
+                    text,
+                    color: Color::Green,
+                    ..
+                } => Span::new_styled(text, Color::Green, Styles::ITALIC),
+                span => span,
+            };
+            builder.push_with_codes(codes, span);
+        }
+
+        assert_eq!(builder.finish(), "§D§Lpink §a§oand green");
+    }
+
+    #[test]
+    fn invalid_codes_fall_back() {
+        let mut builder = LegacyStringBuilder::new();
+        builder.push_with_codes("§", Span::new_styled("a", Color::Red, Styles::empty()));
+        builder.push_with_codes("§z", Span::new_styled("b", Color::Red, Styles::empty()));
+        builder.push_with_codes("§C", Span::new_styled("c", Color::Red, Styles::empty()));
+
+        assert_eq!(builder.finish(), "§cab§Cc");
+    }
+}
+
+mod translate {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ALPHABET: &[char] = &['&', '§', 'a', '6', 'L', 'r', 'z', ' ', 'x'];
+
+    fn parse(s: &str, start_char: char) -> Vec<(String, Color, Styles)> {
+        merged(SpanIter::new(s).with_start_char(start_char))
+    }
+
+    #[test]
+    fn only_valid_codes_rewritten() {
+        let s = "&6It's a lot easier to type &b& &6than &b§";
+        let translated = translate_start_char(s, '&', '§');
+
+        assert_eq!(translated, "§6It's a lot easier to type §b& §6than §b§");
+        assert_eq!(parse(&translated, '§'), parse(s, '&'));
+    }
+
+    #[test]
+    fn literal_start_chars_untouched() {
+        assert_eq!(translate_start_char("Tom & Jerry", '&', '§'), "Tom & Jerry");
+        assert_eq!(translate_start_char("&&a &", '&', '§'), "&&a &");
+        assert_eq!(translate_start_char("&&&a", '&', '§'), "&&§a");
+    }
+
+    #[test]
+    fn code_casing_kept() {
+        assert_eq!(
+            translate_start_char("&C&Lhi &Rthere", '&', '§'),
+            "§C§Lhi §Rthere"
+        );
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        for s in SERVER_MOTDS {
+            let ampersands = translate_start_char_escaped(s, '§', '&');
+            let back = translate_start_char_escaped(&ampersands, '&', '§');
+
+            assert_eq!(
+                merged(reparse(&ampersands, '&').into_iter()),
+                parse(s, '§'),
+                "{:?}",
+                s
+            );
+            assert_eq!(parse(&back, '§'), parse(s, '§'), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn escaped_target_chars_stay_text() {
+        assert_eq!(
+            translate_start_char_escaped("§aOnly &5 each", '§', '&'),
+            "&aOnly &&5 each"
+        );
+        assert_eq!(
+            merged(reparse("&aOnly &&5 each", '&').into_iter()),
+            vec![("Only &5 each".to_string(), Color::Green, Styles::empty())]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn random_inputs_keep_spans(s in strings_of(ALPHABET)) {
+            let escaped = translate_start_char_escaped(&s, '&', '§');
+            assert_eq!(
+                merged(reparse(&escaped, '§').into_iter()),
+                parse(&s, '&'),
+                "{:?}",
+                s
+            );
+
+            // Without escaping, the spans only match if there weren't any `§`s
+            // to begin with
+            if !s.contains('§') {
+                let translated = translate_start_char(&s, '&', '§');
+                assert_eq!(parse(&translated, '§'), parse(&s, '&'), "{:?}", s);
+            }
+        }
+    }
+}
+
+mod escaping {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::borrow::Cow;
+
+    #[test]
+    fn malicious_name_stays_text() {
+        for start_char in ['§', '&'] {
+            let name = "§kBob&l&r";
+            let escaped = escape(name, start_char);
+
+            assert_eq!(
+                merged(reparse(&escaped, start_char).into_iter()),
+                vec![(name.to_string(), Color::White, Styles::empty())]
+            );
+        }
+    }
+
+    #[test]
+    fn embedded_name_doesnt_leak_formatting() {
+        let s = format!("§6[§c{}§6] hi", escape("§kBob§", '§'));
+
+        assert_eq!(
+            merged(reparse(&s, '§').into_iter()),
+            vec![
+                ("[".to_string(), Color::Gold, Styles::empty()),
+                ("§kBob§".to_string(), Color::Red, Styles::empty()),
+                ("] hi".to_string(), Color::Gold, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixtures_become_literal_text() {
+        for s in SERVER_MOTDS {
+            assert_eq!(
+                merged(reparse(&escape(s, '§'), '§').into_iter()),
+                vec![(s.to_string(), Color::White, Styles::empty())]
+            );
+        }
+    }
+
+    #[test]
+    fn borrowed_without_start_char() {
+        assert!(matches!(escape("Bob", '§'), Cow::Borrowed("Bob")));
+        assert!(matches!(escape("§Bob", '&'), Cow::Borrowed("§Bob")));
+        assert_eq!(escape("a&&b", '&'), "a&&&&b");
+    }
+}
+
+mod code_str {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn color_then_styles() {
+        assert_eq!(code_str("text", Color::Red, Styles::BOLD, '§'), "§c§ltext");
+        assert_eq!(
+            code_str("x", Color::Gray, Styles::all(), '&'),
+            "&7&k&l&m&n&ox"
+        );
+    }
+
+    #[test]
+    fn white_gets_a_color_code() {
+        assert_eq!(
+            code_str("plain", Color::White, Styles::empty(), '§'),
+            "§fplain"
+        );
+        assert_eq!(code_str("", Color::Aqua, Styles::empty(), '§'), "§b");
+    }
+
+    #[test]
+    fn rgb() {
+        assert_eq!(
+            code_str("hex", Color::Rgb(0x12, 0xab, 0xef), Styles::ITALIC, '§'),
+            "§x§1§2§a§b§e§f§ohex"
+        );
+    }
+
+    #[test]
+    fn overrides_preceding_formatting() {
+        for color in [Color::White, Color::DarkRed, Color::Rgb(1, 2, 3)] {
+            for styles in [
+                Styles::empty(),
+                Styles::UNDERLINED,
+                Styles::BOLD | Styles::RANDOM,
+            ] {
+                let s = format!("§e§m§lbefore{}", code_str("after", color, styles, '§'));
+
+                assert_eq!(
+                    merged(SpanIter::new(&s).with_hex_colors(true)).last(),
+                    Some(&("after".to_string(), color, styles))
+                );
+            }
+        }
+    }
+}
+
+mod builder {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn default_is_empty() {
+        let builder = LegacyStringBuilder::default();
+
+        assert!(builder.is_empty());
+        assert_eq!(builder.len_visible(), 0);
+        assert_eq!(builder.finish(), "");
+    }
+
+    #[test]
+    fn empty_spans_leave_it_empty() {
+        let mut builder = LegacyStringBuilder::default();
+        builder.push(Span::new_styled("", Color::Red, Styles::BOLD));
+        builder.push_with_codes("§c§l", Span::new_styled("", Color::Red, Styles::BOLD));
+
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn conditional_appending() {
+        let mut builder = LegacyStringBuilder::default().with_start_char('&');
+
+        for (name, online) in [("Alice", true), ("Bob", false), ("Carol", true)] {
+            if !online {
+                continue;
+            }
+            if !builder.is_empty() {
+                builder.push(Span::new_plain(", "));
+            }
+            builder.push(Span::new_styled(name, Color::Green, Styles::empty()));
+        }
+
+        assert_eq!(builder.len_visible(), 12);
+        assert_eq!(builder.finish(), "&aAlice&r, &aCarol");
+    }
+
+    #[test]
+    fn len_visible_matches_parsed_text() {
+        for s in SERVER_MOTDS {
+            let mut builder = LegacyStringBuilder::default();
+            SpanIter::new(s).for_each(|span| builder.push(span));
+            let len = builder.len_visible();
+
+            assert_eq!(
+                len,
+                reparse(&builder.finish(), '§')
+                    .iter()
+                    .map(|span| span.text().chars().count())
+                    .sum::<usize>()
+            );
+        }
+    }
+}
+
+mod legacy_display {
+    use super::*;
+    use core::fmt::Write;
+    use mc_legacy_formatting::LegacyDisplay;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matches_to_legacy_string() {
+        for s in SERVER_MOTDS {
+            let spans = spans(s);
+
+            for start_char in ['§', '&'] {
+                assert_eq!(
+                    LegacyDisplay::new(&spans)
+                        .with_start_char(start_char)
+                        .to_string(),
+                    to_legacy_string(&spans, start_char)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn uppercase_matches_builder() {
+        let mut motd = spans(PLAY_PVPWARS_NET);
+        motd.push(Span::new_styled(
+            "hex",
+            Color::Rgb(0xab, 0xcd, 0xef),
+            Styles::BOLD,
+        ));
+
+        let mut builder = LegacyStringBuilder::new().with_code_case(CodeCase::Upper);
+        motd.iter().for_each(|span| builder.push(*span));
+        let upper = LegacyDisplay::new(&motd)
+            .with_code_case(CodeCase::Upper)
+            .to_string();
+
+        assert_eq!(upper, builder.finish());
+        assert!(upper.ends_with("§X§A§B§C§D§E§F§Lhex"));
+        assert_eq!(
+            SpanIter::new(&upper)
+                .with_hex_colors(true)
+                .collect::<Vec<_>>(),
+            motd
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_to_legacy_string_random(
+            s in strings_of(&['§', '&', 'a', '6', 'L', 'm', 'r', 'x', ' ']),
+        ) {
+            let spans = spans(&s);
+
+            for start_char in ['§', '&', 'a'] {
+                assert_eq!(
+                    LegacyDisplay::new(&spans)
+                        .with_start_char(start_char)
+                        .to_string(),
+                    to_legacy_string(&spans, start_char),
+                    "{:?}",
+                    s
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rgb_and_escapes() {
+        let spans = [
+            Span::new_styled("§ orange ", Color::Rgb(255, 136, 0), Styles::BOLD),
+            Span::new_styled("", Color::Red, Styles::empty()),
+            Span::new_plain("plain"),
+        ];
+
+        assert_eq!(
+            LegacyDisplay::from(&spans[..]).to_string(),
+            "§x§f§f§8§8§0§0§l§§ orange §rplain"
+        );
+    }
+
+    #[test]
+    fn write_into_existing_string() {
+        let spans = spans("§6Gold §lbold");
+        let mut out = String::from("> ");
+
+        write!(out, "{} <", LegacyDisplay::new(&spans)).unwrap();
+
+        assert_eq!(out, "> §6Gold §lbold <");
+    }
+}
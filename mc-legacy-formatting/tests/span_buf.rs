@@ -0,0 +1,90 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, Span, SpanBuf, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn successive_parses_are_independent() {
+    let mut buf = SpanBuf::new();
+
+    let first = buf.parse_into("§6Gold §lbold").to_vec();
+    let second = buf.parse_into("§cred").to_vec();
+
+    assert_eq!(
+        first,
+        vec![
+            Span::new_styled("Gold ", Color::Gold, Styles::empty()),
+            Span::new_styled("bold", Color::Gold, Styles::BOLD),
+        ]
+    );
+    assert_eq!(
+        second,
+        vec![Span::new_styled("red", Color::Red, Styles::empty())]
+    );
+
+    // Parsing starts over from the default formatting every time
+    assert_eq!(&*buf.parse_into("plain"), &[Span::new_plain("plain")]);
+}
+
+#[test]
+fn matches_span_iter() {
+    let mut buf = SpanBuf::new();
+
+    for s in SERVER_MOTDS {
+        assert_eq!(&*buf.parse_into(s), &spans(s)[..]);
+    }
+}
+
+#[test]
+fn parses_strings_that_dont_outlive_it() {
+    let mut buf = SpanBuf::new();
+
+    for s in SERVER_MOTDS {
+        let owned = s.to_string();
+        assert_eq!(&*buf.parse_into(&owned), &spans(s)[..]);
+    }
+    assert!(buf.capacity() > 0);
+}
+
+#[test]
+fn capacity_is_retained() {
+    let mut buf = SpanBuf::new();
+    assert_eq!(buf.capacity(), 0);
+
+    let longest = SERVER_MOTDS
+        .iter()
+        .map(|s| buf.parse_into(s).len())
+        .max()
+        .unwrap();
+    let capacity = buf.capacity();
+    assert!(capacity >= longest);
+
+    for s in SERVER_MOTDS.iter().chain(&["", "a"]) {
+        buf.parse_into(s);
+        assert_eq!(buf.capacity(), capacity);
+    }
+}
+
+#[test]
+fn collect_into_uses_given_settings() {
+    let mut buf = SpanBuf::with_capacity(4);
+    assert!(buf.capacity() >= 4);
+
+    let spans = buf.collect_into(
+        SpanIter::new("&6Tom && Jerry")
+            .with_start_char('&')
+            .with_doubled_escape(true),
+    );
+
+    assert_eq!(
+        &*spans,
+        &[
+            Span::new_styled("Tom ", Color::Gold, Styles::empty()),
+            Span::new_styled("& Jerry", Color::Gold, Styles::empty()),
+        ]
+    );
+}
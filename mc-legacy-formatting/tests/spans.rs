@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+
+#[cfg(feature = "alloc")]
+use mc_legacy_formatting::{strip_formatting_string, to_legacy_string};
+use mc_legacy_formatting::{Color, DisplaySpans, Span, SpanIter, Spans, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn len_counts_down() {
+    let collected = spans("§6Gold §land bold§r and plain");
+    let mut spans = Spans::new(&collected);
+
+    assert_eq!(spans.len(), 3);
+    assert_eq!(
+        spans.next(),
+        Some(Span::new_styled("Gold ", Color::Gold, Styles::empty()))
+    );
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans.as_slice(), &collected[1..]);
+    assert_eq!(spans.by_ref().count(), 2);
+    assert_eq!(spans.len(), 0);
+}
+
+#[test]
+fn reiterable() {
+    let collected = spans(PLAY_MC_BLAZE_COM);
+    let spans = Spans::from(collected.as_slice());
+
+    assert_eq!(spans.clone().collect::<Vec<_>>(), collected);
+    assert_eq!(spans.collect::<Vec<_>>(), collected);
+}
+
+#[test]
+fn display_width_matches_spans() {
+    for s in SERVER_MOTDS {
+        let collected = spans(s);
+
+        assert_eq!(
+            Spans::new(&collected).display_width(4),
+            SpanIter::new(s).with_tab_width(4).display_width()
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn to_plain_string() {
+    let collected = spans("§4§lRed and bold §7§mgray  §r!");
+
+    assert_eq!(
+        Spans::new(&collected).to_plain_string(),
+        "Red and bold gray  !"
+    );
+
+    for s in SERVER_MOTDS {
+        let collected = spans(s);
+        assert_eq!(
+            Spans::new(&collected).to_plain_string(),
+            strip_formatting_string(s, '§')
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn to_legacy_string_matches_free_fn() {
+    for s in SERVER_MOTDS {
+        let collected = spans(s);
+        assert_eq!(
+            Spans::new(&collected).to_legacy_string('&'),
+            to_legacy_string(&collected, '&')
+        );
+    }
+}
+
+#[test]
+fn display_spans_concatenates_span_display() {
+    for s in SERVER_MOTDS {
+        let expected: String = SpanIter::new(s).map(|span| span.to_string()).collect();
+
+        assert_eq!(DisplaySpans::new(SpanIter::new(s)).to_string(), expected);
+    }
+}
+
+#[test]
+fn display_spans_can_be_shown_twice() {
+    let collected = spans("§6Gold §m   §r plain");
+    let display = DisplaySpans::new(Spans::new(&collected));
+
+    assert_eq!(
+        format!("{} | {}", display, display),
+        "Gold --- plain | Gold --- plain"
+    );
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, Span, SpanIter, StreamParser, Styles};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+type Formatted = Vec<(String, Color, Styles)>;
+
+/// Parse `chunks` with a [`StreamParser`], merging the pieces of text it
+/// delivers
+fn stream_str(chunks: &[&str], start_char: char) -> Formatted {
+    let mut pieces = Vec::new();
+    let mut parser = StreamParser::new().with_start_char(start_char);
+    let mut sink = |span: Span| pieces.push((span.text().to_string(), span.color(), span.styles()));
+
+    for chunk in chunks {
+        parser.push_str(chunk, &mut sink);
+    }
+    parser.finish(&mut sink);
+
+    merge(pieces)
+}
+
+fn stream_bytes(chunks: &[&[u8]]) -> Formatted {
+    let mut pieces = Vec::new();
+    let mut parser = StreamParser::new();
+    let mut sink = |span: Span| pieces.push((span.text().to_string(), span.color(), span.styles()));
+
+    for chunk in chunks {
+        parser.push_bytes(chunk, &mut sink);
+    }
+    parser.finish(&mut sink);
+
+    merge(pieces)
+}
+
+fn merge(pieces: Formatted) -> Formatted {
+    let mut merged: Formatted = Vec::new();
+
+    for (text, color, styles) in pieces {
+        match merged.last_mut() {
+            Some(last) if (last.1, last.2) == (color, styles) => last.0.push_str(&text),
+            _ => merged.push((text, color, styles)),
+        }
+    }
+
+    merged.retain(|(text, ..)| !text.is_empty());
+    merged
+}
+
+fn whole(s: &str, start_char: char) -> Formatted {
+    merge(merged(SpanIter::new(s).with_start_char(start_char)))
+}
+
+#[test]
+fn fixtures_split_at_every_char_boundary() {
+    for s in SERVER_MOTDS {
+        for (idx, _) in s.char_indices() {
+            let (a, b) = s.split_at(idx);
+            assert_eq!(
+                stream_str(&[a, b], '§'),
+                whole(s, '§'),
+                "{:?} at {}",
+                s,
+                idx
+            );
+        }
+    }
+}
+
+#[test]
+fn fixtures_split_at_every_byte_boundary() {
+    for s in SERVER_MOTDS {
+        let bytes = s.as_bytes();
+
+        for idx in 0..=bytes.len() {
+            let (a, b) = bytes.split_at(idx);
+            assert_eq!(stream_bytes(&[a, b]), whole(s, '§'), "{:?} at {}", s, idx);
+        }
+    }
+}
+
+#[test]
+fn fixtures_one_byte_at_a_time() {
+    for s in SERVER_MOTDS {
+        let chunks = s.as_bytes().chunks(1).collect::<Vec<_>>();
+        assert_eq!(stream_bytes(&chunks), whole(s, '§'), "{:?}", s);
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_inputs_one_char_at_a_time(
+        s in strings_of(&['&', '§', 'a', '6', 'L', 'r', 'z', ' ', 'x']),
+    ) {
+        let chunks = s
+            .char_indices()
+            .map(|(idx, c)| &s[idx..idx + c.len_utf8()])
+            .collect::<Vec<_>>();
+
+        assert_eq!(stream_str(&chunks, '&'), whole(&s, '&'), "{:?}", s);
+    }
+}
+
+#[test]
+fn pending_start_char_across_empty_chunk() {
+    assert_eq!(
+        stream_str(&["§", "", "6gold"], '§'),
+        vec![("gold".to_string(), Color::Gold, Styles::empty())]
+    );
+}
+
+#[test]
+fn trailing_start_char_is_text() {
+    assert_eq!(
+        stream_str(&["§cred §"], '§'),
+        vec![("red §".to_string(), Color::Red, Styles::empty())]
+    );
+}
+
+#[test]
+fn invalid_utf8_replaced() {
+    let bytes = b"\xc2\xa7aGreen \xff\xc2";
+    let expected = vec![(
+        "Green \u{FFFD}\u{FFFD}".to_string(),
+        Color::Green,
+        Styles::empty(),
+    )];
+
+    for idx in 0..=bytes.len() {
+        let (a, b) = bytes.split_at(idx);
+        assert_eq!(stream_bytes(&[a, b]), expected, "split at {}", idx);
+    }
+
+    assert_eq!(
+        stream_bytes(&[b"\xc2", b"a\xc2\xa7l", b"bold"]),
+        vec![
+            ("\u{FFFD}a".to_string(), Color::White, Styles::empty()),
+            ("bold".to_string(), Color::White, Styles::BOLD),
+        ]
+    );
+}
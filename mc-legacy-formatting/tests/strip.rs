@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+
+#[cfg(feature = "alloc")]
+use mc_legacy_formatting::strip_formatting_string;
+use mc_legacy_formatting::{strip_formatting, SpanIter};
+use pretty_assertions::assert_eq;
+
+fn joined(s: &str, start_char: char) -> String {
+    SpanIter::new(s)
+        .with_start_char(start_char)
+        .map(|span| span.text())
+        .collect()
+}
+
+#[test]
+fn fixtures_match_joined_spans() {
+    for s in SERVER_MOTDS {
+        let mut out = String::new();
+        strip_formatting(s, '§', &mut out).unwrap();
+
+        assert_eq!(out, joined(s, '§'));
+        assert_eq!(SpanIter::new(s).stripped().to_string(), out);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn fixtures_match_joined_spans_string() {
+    for s in SERVER_MOTDS {
+        assert_eq!(strip_formatting_string(s, '§'), joined(s, '§'));
+    }
+}
+
+#[test]
+fn custom_start_char() {
+    let s = "&6&lGold§b &bAqua &zinvalid";
+    let mut out = String::new();
+    strip_formatting(s, '&', &mut out).unwrap();
+
+    assert_eq!(out, "Gold§b Aqua &zinvalid");
+    assert_eq!(
+        SpanIter::new(s).with_start_char('&').stripped().to_string(),
+        out
+    );
+}
+
+#[test]
+fn strikethrough_whitespace_keeps_spaces() {
+    let s = "§m   §rtext§m\t ";
+
+    assert_eq!(SpanIter::new(s).stripped().to_string(), "   text\t ");
+}
+
+#[test]
+fn trailing_reset_doesnt_change_output() {
+    assert_eq!(
+        SpanIter::new(PLAY_MC_BLAZE_COM)
+            .with_trailing_reset()
+            .stripped()
+            .to_string(),
+        SpanIter::new(PLAY_MC_BLAZE_COM).stripped().to_string()
+    );
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, SpanExt, SpanIter, StyledSpan, Styles};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn same_text_as_display() {
+    for s in SERVER_MOTDS {
+        let displayed: String = SpanIter::new(s).map(|span| span.to_string()).collect();
+        let styled: String = s.styled_iter().map(|span| span.text).collect();
+
+        assert_eq!(styled, displayed);
+    }
+}
+
+#[test]
+fn same_formatting_as_spans() {
+    for s in SERVER_MOTDS {
+        let styled: Vec<_> = s
+            .styled_iter()
+            .map(|span| (span.color, span.styles))
+            .collect();
+        let spans: Vec<_> = SpanIter::new(s)
+            .map(|span| (span.color(), span.styles()))
+            .collect();
+
+        assert_eq!(styled, spans);
+    }
+}
+
+#[test]
+fn long_strikethrough_whitespace() {
+    let whitespace = " ".repeat(100);
+    let s = format!("§7§m{}", whitespace);
+    let styled: Vec<_> = s.styled_iter().collect();
+
+    assert!(styled.len() > 1);
+    assert!(styled.iter().all(|span| span.color == Color::Gray
+        && span.styles == Styles::STRIKETHROUGH
+        && span.text.chars().all(|c| c == '-')));
+    assert_eq!(
+        styled.iter().map(|span| span.text.len()).sum::<usize>(),
+        100
+    );
+}
+
+#[test]
+fn plain_is_white() {
+    assert_eq!(
+        "§lbold §rplain".styled_iter().collect::<Vec<_>>(),
+        vec![
+            StyledSpan {
+                text: "bold ",
+                color: Color::White,
+                styles: Styles::BOLD
+            },
+            StyledSpan {
+                text: "plain",
+                color: Color::White,
+                styles: Styles::empty()
+            },
+        ]
+    );
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "unicode")]
+
+use mc_legacy_formatting::{Span, SpanExt};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn combining_accents() {
+    let span = Span::new_plain("re\u{301}sume\u{301}");
+
+    assert_eq!(span.text().chars().count(), 8);
+    assert_eq!(span.graphemes().count(), 6);
+    assert_eq!(span.display_width(0), 6);
+}
+
+#[test]
+fn emoji() {
+    // A family emoji made up of four people joined with zero width joiners
+    let span = Span::new_plain("hi \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}");
+
+    assert_eq!(
+        span.graphemes().collect::<Vec<_>>(),
+        [
+            "h",
+            "i",
+            " ",
+            "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}"
+        ]
+    );
+    assert_eq!(span.display_width(0), 4);
+}
+
+#[test]
+fn tabs_and_newlines() {
+    let span = Span::new_plain("a\tb\r\nc\n");
+
+    assert_eq!(span.display_width(0), 4);
+    assert_eq!(span.display_width(4), 7);
+}
+
+#[test]
+fn span_iter_display_width() {
+    let s = "§6Cafe\u{301} §lna\u{308}ive";
+
+    assert_eq!(
+        s.span_iter()
+            .map(|span| span.text().chars().count())
+            .sum::<usize>(),
+        12
+    );
+    assert_eq!(s.span_iter().display_width(), 10);
+}
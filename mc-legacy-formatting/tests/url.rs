@@ -0,0 +1,149 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, Span, SpanIter, Styles};
+use pretty_assertions::assert_eq;
+
+fn urls(s: &str) -> Vec<Span<'_>> {
+    SpanIter::new(s)
+        .with_url_detection(true)
+        .filter(|span| matches!(span, Span::Url { .. }))
+        .collect()
+}
+
+#[test]
+fn minesuperior() {
+    assert_eq!(
+        urls(HUB_MCS_GG),
+        vec![Span::new_url(
+            "www.minesuperior.com",
+            Color::Green,
+            Styles::BOLD | Styles::UNDERLINED
+        )]
+    );
+}
+
+#[test]
+fn opblocks_discord() {
+    assert_eq!(
+        urls(PLAY_OPBLOCKS_COM),
+        vec![Span::new_url(
+            "discord.gg/opblocks",
+            Color::Red,
+            Styles::empty()
+        )]
+    );
+}
+
+#[test]
+fn url_followed_by_newline() {
+    let spans = SpanIter::new(PLAY_MC_BLAZE_COM)
+        .with_url_detection(true)
+        .collect::<Vec<_>>();
+
+    assert!(spans.windows(2).any(|pair| pair
+        == [
+            Span::new_url("www.mc-blaze.com", Color::Aqua, Styles::empty()),
+            Span::new_styled("\n            ", Color::Aqua, Styles::empty()),
+        ]));
+}
+
+#[test]
+fn url_in_middle_of_span() {
+    assert_eq!(
+        SpanIter::new("§eVisit https://example.com/store?id=5, or www.example.net.")
+            .with_url_detection(true)
+            .collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("Visit ", Color::Yellow, Styles::empty()),
+            Span::new_url(
+                "https://example.com/store?id=5",
+                Color::Yellow,
+                Styles::empty()
+            ),
+            Span::new_styled(", or ", Color::Yellow, Styles::empty()),
+            Span::new_url("www.example.net", Color::Yellow, Styles::empty()),
+            Span::new_styled(".", Color::Yellow, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn plain_url() {
+    assert_eq!(
+        SpanIter::new("(play.example.com:25565)")
+            .with_url_detection(true)
+            .collect::<Vec<_>>(),
+        vec![
+            Span::new_plain("("),
+            Span::new_url("play.example.com:25565", Color::White, Styles::empty()),
+            Span::new_plain(")"),
+        ]
+    );
+}
+
+#[test]
+fn uppercase_known_tld() {
+    assert_eq!(
+        urls("§6PLAY.EXAMPLE.COM"),
+        vec![Span::new_url(
+            "PLAY.EXAMPLE.COM",
+            Color::Gold,
+            Styles::empty()
+        )]
+    );
+}
+
+#[test]
+fn not_urls() {
+    for s in [
+        "1.7-1.16 SUPPORT",
+        "e.g. this",
+        "v1.8.9",
+        "end of sentence.",
+        "https://",
+        "user@example.com",
+        "a .b.com",
+        "[75% Sale]",
+        "Coming soon.Join now",
+        "soon.Join",
+        "v2.Beta",
+    ] {
+        assert_eq!(urls(s), vec![], "{:?}", s);
+    }
+}
+
+#[test]
+fn with_split_on_newline() {
+    assert_eq!(
+        SpanIter::new("§bone\ntwo www.example.com\nthree")
+            .with_url_detection(true)
+            .split_on_newline(true)
+            .collect::<Vec<_>>(),
+        vec![
+            Span::new_styled("one\n", Color::Aqua, Styles::empty()),
+            Span::new_styled("two ", Color::Aqua, Styles::empty()),
+            Span::new_url("www.example.com", Color::Aqua, Styles::empty()),
+            Span::new_styled("\n", Color::Aqua, Styles::empty()),
+            Span::new_styled("three", Color::Aqua, Styles::empty()),
+        ]
+    );
+}
+
+#[test]
+fn off_by_default() {
+    for s in SERVER_MOTDS {
+        assert!(SpanIter::new(s).all(|span| !matches!(span, Span::Url { .. })));
+    }
+}
+
+#[test]
+fn fixtures_keep_text_and_formatting() {
+    for s in SERVER_MOTDS {
+        assert_eq!(
+            merged(SpanIter::new(s).with_url_detection(true)),
+            merged(SpanIter::new(s))
+        );
+    }
+}
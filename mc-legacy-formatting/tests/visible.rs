@@ -0,0 +1,497 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{
+    strip_formatting, visible_byte_len, visible_byte_len_with_start_char, visible_len,
+    visible_len_with_start_char,
+};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+fn stripped(s: &str, start_char: char) -> String {
+    let mut out = String::new();
+    strip_formatting(s, start_char, &mut out).unwrap();
+    out
+}
+
+fn assert_agrees(s: &str, start_char: char) {
+    let stripped = stripped(s, start_char);
+
+    assert_eq!(
+        visible_len_with_start_char(s, start_char),
+        stripped.chars().count(),
+        "{:?}",
+        s
+    );
+    assert_eq!(
+        visible_byte_len_with_start_char(s, start_char),
+        stripped.len(),
+        "{:?}",
+        s
+    );
+}
+
+#[test]
+fn fixtures_agree_with_strip() {
+    for s in SERVER_MOTDS {
+        assert_eq!(visible_len(s), stripped(s, '§').chars().count());
+        assert_eq!(visible_byte_len(s), stripped(s, '§').len());
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_inputs_agree_with_strip(
+        s in strings_of(&['§', '&', 'a', 'L', 'r', 'z', ' ', '\n', 'é', '⌜']),
+    ) {
+        assert_agrees(&s, '§');
+        assert_agrees(&s, '&');
+    }
+}
+
+#[test]
+fn doubled_start_chars() {
+    assert_eq!(visible_len("§§a"), 3);
+    assert_eq!(visible_len("x§§§a"), 3);
+    assert_eq!(visible_len("trailing §"), 10);
+}
+
+mod truncate {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use mc_legacy_formatting::truncate_visible_owned;
+    use mc_legacy_formatting::{truncate_visible, truncate_visible_with_start_char};
+    use pretty_assertions::assert_eq;
+
+    const S: &str = "ab§4cdé§lfg";
+
+    #[test]
+    fn before_code() {
+        // The code right after the cut doesn't count, so it's kept
+        assert_eq!(truncate_visible(S, 2), "ab§4");
+        assert_eq!(truncate_visible(S, 1), "a");
+    }
+
+    #[test]
+    fn inside_code() {
+        // Naively cutting 3 or 4 bytes in would split the `§4` pair (or the
+        // `§` itself)
+        for max in 0..=visible_len(S) {
+            let truncated = truncate_visible(S, max);
+            assert!(!truncated.ends_with('§'), "{:?}", truncated);
+        }
+    }
+
+    #[test]
+    fn after_code() {
+        assert_eq!(truncate_visible(S, 3), "ab§4c");
+    }
+
+    #[test]
+    fn multi_byte_char() {
+        assert_eq!(truncate_visible(S, 4), "ab§4cd");
+        assert_eq!(truncate_visible(S, 5), "ab§4cdé§l");
+        assert_eq!(truncate_visible(S, 6), "ab§4cdé§lf");
+    }
+
+    #[test]
+    fn everything_fits() {
+        assert_eq!(truncate_visible(S, 7), S);
+        assert_eq!(truncate_visible(S, 100), S);
+        assert_eq!(truncate_visible("", 0), "");
+        assert_eq!(truncate_visible("§4", 0), "§4");
+    }
+
+    #[test]
+    fn invalid_pair_isnt_split() {
+        assert_eq!(truncate_visible("a§zb", 2), "a");
+        assert_eq!(truncate_visible("a§zb", 3), "a§z");
+        assert_eq!(truncate_visible("a§", 1), "a");
+        assert_eq!(truncate_visible("a§", 2), "a§");
+    }
+
+    #[test]
+    fn custom_start_char() {
+        let s = "&6Tom & Jerry";
+
+        assert_eq!(truncate_visible_with_start_char(s, 5, '&'), "&6Tom ");
+        assert_eq!(truncate_visible_with_start_char(s, 6, '&'), "&6Tom & ");
+    }
+
+    #[test]
+    fn fixtures() {
+        for s in SERVER_MOTDS {
+            for max in 0..=visible_len(s) + 1 {
+                let truncated = truncate_visible(s, max);
+
+                assert!(s.starts_with(truncated));
+                assert!(visible_len(truncated) <= max);
+                assert!(visible_len(truncated) + 1 >= max.min(visible_len(s)));
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned() {
+        assert_eq!(truncate_visible_owned(S, 3), String::from("ab§4c"));
+    }
+}
+
+mod find {
+    use super::*;
+    use mc_legacy_formatting::{find_all_visible, find_visible, VisibleMatch};
+    use pretty_assertions::assert_eq;
+
+    fn pieces<'a>(s: &'a str, found: &VisibleMatch, start_char: char) -> Vec<&'a str> {
+        found.pieces(s, start_char).map(|range| &s[range]).collect()
+    }
+
+    #[test]
+    fn across_code_boundary() {
+        let s = "§aSte§bve";
+        let found = find_visible(s, "Steve").unwrap();
+
+        assert_eq!(found.chars, 0..5);
+        assert_eq!(&s[found.bytes.clone()], "Ste§bve");
+        assert_eq!(pieces(s, &found, '§'), vec!["Ste", "ve"]);
+    }
+
+    #[test]
+    fn across_three_spans() {
+        let s = "Hi §6St§l§oe§rve!";
+        let found = find_visible(s, "Steve").unwrap();
+
+        assert_eq!(found.chars, 3..8);
+        assert_eq!(&s[found.bytes.clone()], "St§l§oe§rve");
+        assert_eq!(pieces(s, &found, '§'), vec!["St", "e", "ve"]);
+    }
+
+    #[test]
+    fn codes_arent_searched() {
+        assert_eq!(find_visible("§aSteve", "aSteve"), None);
+        assert_eq!(find_visible("Ste§lve", "Ste§lve"), None);
+        assert_eq!(find_visible("§6§l", "6"), None);
+        assert_eq!(find_visible("§6§l", "§"), None);
+    }
+
+    #[test]
+    fn invalid_codes_are_text() {
+        let s = "a§zb";
+        let found = find_visible(s, "§zb").unwrap();
+
+        assert_eq!(found.chars, 1..4);
+        assert_eq!(pieces(s, &found, '§'), vec!["§zb"]);
+        assert_eq!(find_visible("trailing §", "g §").unwrap().chars, 7..10);
+    }
+
+    #[test]
+    fn all_matches() {
+        let s = "§aaa§baa§ca";
+        let found: Vec<_> = find_all_visible(s, "aa").collect();
+
+        // Matches don't overlap
+        assert_eq!(
+            found.iter().map(|m| m.chars.clone()).collect::<Vec<_>>(),
+            vec![0..2, 2..4]
+        );
+        assert_eq!(find_all_visible(s, "").count(), 0);
+        assert_eq!(find_all_visible("", "a").count(), 0);
+    }
+
+    #[test]
+    fn custom_start_char_and_case() {
+        let s = "&6Tom &lTOM tom";
+        let matches = |ignore_case| {
+            find_all_visible(s, "tom")
+                .with_start_char('&')
+                .with_ignore_case(ignore_case)
+                .map(|m| (m.chars, &s[m.bytes]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matches(false), vec![(8..11, "tom")]);
+        assert_eq!(
+            matches(true),
+            vec![(0..3, "Tom"), (4..7, "TOM"), (8..11, "tom")]
+        );
+    }
+
+    #[test]
+    fn agrees_with_stripped_text() {
+        for s in SERVER_MOTDS {
+            let stripped = stripped(s, '§');
+            let chars: Vec<char> = stripped.chars().collect();
+
+            for window in chars.windows(3).step_by(5) {
+                let needle: String = window.iter().collect();
+                let found = find_visible(s, &needle).unwrap();
+                let expected = stripped[..stripped.find(&needle).unwrap()].chars().count();
+
+                assert_eq!(found.chars, expected..expected + 3);
+                assert_eq!(pieces(s, &found, '§').concat(), needle);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod map_text {
+    use super::*;
+    use mc_legacy_formatting::{
+        map_visible_text, to_lowercase_visible, to_uppercase_visible, SpanIter,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// The codes before each span, parsed with hex colors
+    fn codes(s: &str) -> Vec<&str> {
+        SpanIter::new(s)
+            .with_hex_colors(true)
+            .skip_empty()
+            .with_codes()
+            .map(|(codes, _)| codes)
+            .collect()
+    }
+
+    fn text(s: &str) -> String {
+        SpanIter::new(s)
+            .with_hex_colors(true)
+            .map(|span| span.text())
+            .collect()
+    }
+
+    #[test]
+    fn codes_are_untouched() {
+        let s = "§6§Lgold §x§a§B§c§1§2§3hex §#aBc123short §rplain";
+        let upper = to_uppercase_visible(s);
+
+        assert_eq!(upper, "§6§LGOLD §x§a§B§c§1§2§3HEX §#aBc123SHORT §rPLAIN");
+        assert_eq!(codes(&upper), codes(s));
+        assert_eq!(
+            to_lowercase_visible(&upper),
+            "§6§Lgold §x§a§B§c§1§2§3hex §#aBc123short §rplain"
+        );
+    }
+
+    #[test]
+    fn invalid_codes_are_text() {
+        assert_eq!(to_uppercase_visible("a§zb§"), "A§ZB§");
+    }
+
+    #[test]
+    fn text_can_change_length() {
+        assert_eq!(to_uppercase_visible("§6straße§l!"), "§6STRASSE§l!");
+        assert_eq!(map_visible_text("§6ab§lcd", |_| String::new()), "§6§l");
+    }
+
+    #[test]
+    fn closure_sees_each_run_of_text() {
+        let mut runs = Vec::new();
+        map_visible_text("§6a§lbc§r§rd§7", |text| {
+            runs.push(text.to_string());
+            text.to_string()
+        });
+
+        assert_eq!(runs, vec!["a", "bc", "d"]);
+    }
+
+    #[test]
+    fn fixtures() {
+        for s in SERVER_MOTDS {
+            let upper = to_uppercase_visible(s);
+
+            assert_eq!(map_visible_text(s, str::to_string), *s);
+            assert_eq!(codes(&upper), codes(s));
+            assert_eq!(text(&upper), text(s).to_uppercase());
+        }
+    }
+}
+
+mod take_visible {
+    use super::*;
+    use mc_legacy_formatting::{truncate_visible, Color, Span, SpanIter, Styles};
+    use pretty_assertions::assert_eq;
+
+    fn take(s: &str, n: usize) -> Vec<Span<'_>> {
+        SpanIter::new(s).take_visible(n).collect()
+    }
+
+    #[test]
+    fn cuts_multi_byte_text_on_char_boundary() {
+        assert_eq!(
+            take("§6café §l⌜é⌝", 6),
+            vec![
+                Span::new_styled("café ", Color::Gold, Styles::empty()),
+                Span::new_styled("⌜", Color::Gold, Styles::BOLD),
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_at_span_boundary() {
+        // The empty span after the limit isn't yielded
+        assert_eq!(
+            take("§6ab§l§ccd", 2),
+            vec![Span::new_styled("ab", Color::Gold, Styles::empty())]
+        );
+        assert_eq!(take("§6ab", 0), vec![]);
+        assert_eq!(take("§6ab", 100), spans("§6ab"));
+    }
+
+    #[test]
+    fn urls_stay_urls() {
+        let spans: Vec<Span> = SpanIter::new("§9see example.com/page")
+            .with_url_detection(true)
+            .take_visible(11)
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::new_styled("see ", Color::Blue, Styles::empty()),
+                Span::new_url("example", Color::Blue, Styles::empty()),
+            ]
+        );
+    }
+
+    #[test]
+    fn agrees_with_truncate_visible() {
+        for s in SERVER_MOTDS {
+            for n in 0..=visible_len(s) {
+                let text: String = SpanIter::new(s)
+                    .take_visible(n)
+                    .map(|span| span.text())
+                    .collect();
+
+                assert_eq!(text, stripped(truncate_visible(s, n), '§'));
+            }
+        }
+    }
+}
+
+mod index_mapping {
+    use super::*;
+    use mc_legacy_formatting::{
+        byte_to_visible, byte_to_visible_with_start_char, visible_char_indices, visible_to_byte,
+        visible_to_byte_with_start_char,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Multi-byte text with dense runs of codes, including invalid ones
+    const DENSE: &str = "§6§l§m§4é§r§§★§z☃§a§b§c⌜§x§";
+
+    fn assert_round_trips(s: &str, start_char: char) {
+        let len = visible_len_with_start_char(s, start_char);
+
+        for n in 0..=len {
+            let byte = visible_to_byte_with_start_char(s, n, start_char).unwrap();
+            assert_eq!(
+                byte_to_visible_with_start_char(s, byte, start_char),
+                Some(n),
+                "{:?} at {}",
+                s,
+                n
+            );
+        }
+        assert_eq!(
+            visible_to_byte_with_start_char(s, len + 1, start_char),
+            None
+        );
+
+        for byte in (0..=s.len()).filter(|&byte| s.is_char_boundary(byte)) {
+            let n = byte_to_visible_with_start_char(s, byte, start_char).unwrap();
+            let after = visible_to_byte_with_start_char(s, n, start_char).unwrap();
+
+            // Only codes are skipped over to get to the next visible char
+            assert!(after >= byte, "{:?} at {}", s, byte);
+            assert_eq!(
+                byte_to_visible_with_start_char(s, after, start_char),
+                Some(n),
+                "{:?} at {}",
+                s,
+                byte
+            );
+        }
+        assert_eq!(
+            byte_to_visible_with_start_char(s, s.len() + 1, start_char),
+            None
+        );
+    }
+
+    #[test]
+    fn dense_codes() {
+        assert_eq!(
+            visible_char_indices(DENSE).collect::<Vec<_>>(),
+            vec![
+                (0, 12, 'é'),
+                (1, 17, '§'),
+                (2, 19, '§'),
+                (3, 21, '★'),
+                (4, 24, '§'),
+                (5, 26, 'z'),
+                (6, 27, '☃'),
+                (7, 39, '⌜'),
+                (8, 42, '§'),
+                (9, 44, 'x'),
+                (10, 45, '§'),
+            ]
+        );
+
+        // Landing on either char of a code maps to the position after it
+        assert_eq!(byte_to_visible(DENSE, 0), Some(0));
+        assert_eq!(byte_to_visible(DENSE, 2), Some(0));
+        assert_eq!(byte_to_visible(DENSE, 14), Some(1));
+        assert_eq!(byte_to_visible(DENSE, 32), Some(7));
+        assert_eq!(visible_to_byte(DENSE, 7), Some(39));
+        assert_eq!(visible_to_byte(DENSE, 11), Some(DENSE.len()));
+
+        // Not on a char boundary
+        assert_eq!(byte_to_visible(DENSE, 1), None);
+        assert_eq!(byte_to_visible(DENSE, 13), None);
+
+        assert_round_trips(DENSE, '§');
+    }
+
+    #[test]
+    fn chars_match_stripped_text() {
+        for s in SERVER_MOTDS.iter().copied().chain([DENSE]) {
+            let indices: Vec<_> = visible_char_indices(s).collect();
+
+            assert_eq!(
+                indices.iter().map(|&(_, _, c)| c).collect::<String>(),
+                stripped(s, '§')
+            );
+            for (n, &(idx, byte, c)) in indices.iter().enumerate() {
+                assert_eq!(idx, n);
+                assert!(s[byte..].starts_with(c));
+            }
+        }
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        for s in SERVER_MOTDS {
+            assert_round_trips(s, '§');
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn random_inputs_round_trip(
+            s in strings_of(&['§', '&', 'a', 'L', 'r', 'z', ' ', 'é', '⌜']),
+        ) {
+            assert_round_trips(&s, '§');
+            assert_round_trips(&s, '&');
+        }
+    }
+
+    #[test]
+    fn empty_string() {
+        assert_eq!(visible_char_indices("").next(), None);
+        assert_eq!(visible_to_byte("", 0), Some(0));
+        assert_eq!(visible_to_byte("§a", 0), Some(3));
+        assert_eq!(byte_to_visible("", 0), Some(0));
+        assert_eq!(byte_to_visible("§a", 0), Some(0));
+    }
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use common::*;
+
+use mc_legacy_formatting::{Color, Span, SpanIter, Styles, WidthMode};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn tab_width_difference() {
+    let span = Span::new_styled("Players:\t12", Color::Gray, Styles::empty());
+
+    assert_eq!(span.display_width(0), 11);
+    assert_eq!(span.display_width(4), 14);
+    assert_eq!(span.display_width(4) - span.display_width(0), 3);
+}
+
+#[test]
+fn tab_width_setting_on_iter() {
+    let s = "§7Players:\t§a12\t§7/\t§c100";
+
+    assert_eq!(SpanIter::new(s).tab_width(), 0);
+    assert_eq!(SpanIter::new(s).display_width(), 17);
+    assert_eq!(SpanIter::new(s).with_tab_width(4).display_width(), 26);
+
+    let span_iter = SpanIter::new(s).with_tab_width(8);
+    let tab_width = span_iter.tab_width();
+    assert_eq!(
+        span_iter
+            .map(|span| span.display_width(tab_width))
+            .sum::<usize>(),
+        38
+    );
+}
+
+#[test]
+fn tab_width_doesnt_change_text() {
+    let s = "§6a\tb";
+
+    assert_eq!(
+        SpanIter::new(s).with_tab_width(4).collect::<Vec<_>>(),
+        SpanIter::new(s).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn width_ignores_codes_and_newlines() {
+    assert_eq!(SpanIter::new(PLAY_MC_BLAZE_COM).display_width(), 115);
+}
+
+mod pixels {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sums_glyph_widths() {
+        assert_eq!(Span::new_plain("[Steve]").pixel_width(), 37);
+        assert_eq!(Span::new_plain("").pixel_width(), 0);
+    }
+
+    #[test]
+    fn bold_adds_a_pixel_per_glyph() {
+        let text = "Hi, I'm Steve!";
+        let plain = Span::new_styled(text, Color::Gold, Styles::ITALIC);
+        let bold = Span::new_styled(text, Color::Gold, Styles::ITALIC | Styles::BOLD);
+
+        assert_eq!(
+            bold.pixel_width() - plain.pixel_width(),
+            text.chars().count()
+        );
+    }
+
+    #[test]
+    fn no_bold_pixel_for_zero_width_chars() {
+        let span = Span::new_styled("a\nb", Color::Gold, Styles::BOLD);
+
+        assert_eq!(span.pixel_width(), 14);
+    }
+
+    #[test]
+    fn strikethrough_whitespace() {
+        let span = Span::new_strikethrough_whitespace(
+            "   ",
+            Color::DarkGray,
+            Styles::STRIKETHROUGH | Styles::BOLD,
+        );
+
+        assert_eq!(span.pixel_width(), 15);
+    }
+
+    #[test]
+    fn width_mode_on_iter() {
+        let s = "§a§lHypixel §7[1.8-1.16]";
+
+        assert_eq!(SpanIter::new(s).width_mode(), WidthMode::Columns);
+        assert_eq!(SpanIter::new(s).display_width(), 18);
+        // 46 for the bold "Hypixel " and 48 for "[1.8-1.16]"
+        assert_eq!(
+            SpanIter::new(s)
+                .with_width_mode(WidthMode::Pixels)
+                .display_width(),
+            46 + 48
+        );
+    }
+
+    #[test]
+    fn tab_width_only_affects_columns() {
+        let s = "§6a\tb";
+
+        assert_eq!(
+            SpanIter::new(s)
+                .with_width_mode(WidthMode::Pixels)
+                .with_tab_width(4)
+                .display_width(),
+            12
+        );
+    }
+}
+
+mod glyphs {
+    use mc_legacy_formatting::glyph_width;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ascii_widths() {
+        let widths: Vec<_> = "Hi, I'm [Steve]!".chars().map(glyph_width).collect();
+        assert_eq!(widths, vec![6, 2, 2, 4, 4, 2, 6, 4, 4, 6, 5, 6, 6, 6, 4, 2]);
+    }
+
+    #[test]
+    fn control_chars_have_no_width() {
+        assert_eq!(glyph_width('\n'), 0);
+        assert_eq!(glyph_width('\r'), 0);
+    }
+
+    #[test]
+    fn non_ascii_uses_default_width() {
+        assert_eq!(glyph_width('é'), 6);
+        assert_eq!(glyph_width('⌜'), 6);
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod scramble {
+    use mc_legacy_formatting::{glyph_width, scramble};
+    use pretty_assertions::assert_eq;
+
+    fn charset() -> Vec<char> {
+        ('!'..='~').collect()
+    }
+
+    #[test]
+    fn same_length() {
+        for text in [
+            "",
+            "a",
+            "Obfuscated text!",
+            "wide @@@ and narrow ii!!",
+            "ünïcödé",
+        ] {
+            assert_eq!(
+                scramble(text, 7, &charset()).chars().count(),
+                text.chars().count()
+            );
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let text = "The quick brown fox";
+
+        assert_eq!(
+            scramble(text, 1234, &charset()),
+            scramble(text, 1234, &charset())
+        );
+        assert_ne!(
+            scramble(text, 1234, &charset()),
+            scramble(text, 4321, &charset())
+        );
+    }
+
+    #[test]
+    fn keeps_glyph_widths() {
+        let text = "Hi, I'm [Steve]!";
+
+        for seed in 0..20 {
+            assert_eq!(
+                scramble(text, seed, &charset())
+                    .chars()
+                    .map(glyph_width)
+                    .collect::<Vec<_>>(),
+                text.chars().map(glyph_width).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn falls_back_to_any_char() {
+        // Nothing in the charset is as narrow as `i`
+        let scrambled = scramble("iii", 3, &['a', 'b']);
+
+        assert_eq!(scrambled.chars().count(), 3);
+        assert!(scrambled.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn empty_charset() {
+        assert_eq!(scramble("text", 3, &[]), "text");
+    }
+}
+
+mod motd {
+    use super::*;
+    use mc_legacy_formatting::{fits_motd, fits_motd_with_width, MOTD_LINE_WIDTH};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn server_motds() {
+        let overflowing: Vec<_> = SERVER_MOTDS
+            .iter()
+            .filter(|motd| !fits_motd(motd))
+            .collect();
+
+        assert_eq!(
+            overflowing,
+            vec![
+                &PURPLE_WTF,
+                &MC_MINEHEROES_ORG,
+                &PLAY_MC_BLAZE_COM,
+                &PLAY_OPBLOCKS_COM,
+                &PLAY_PVPWARS_NET,
+                &MCCENTRAL_ORG,
+            ]
+        );
+    }
+
+    #[test]
+    fn too_many_lines() {
+        // `PLAY_MC_BLAZE_COM` has six lines, all of them short
+        assert!(!fits_motd_with_width(PLAY_MC_BLAZE_COM, usize::MAX));
+        assert!(fits_motd("§aOne\n§bTwo\n"));
+        assert!(!fits_motd("§aOne\n\n§cThree"));
+    }
+
+    #[test]
+    fn exactly_at_the_limit() {
+        // `a` is 6 pixels wide, so 45 of them are exactly 270
+        let line = "a".repeat(MOTD_LINE_WIDTH / 6);
+
+        assert!(fits_motd(&format!("{}\n{}", line, line)));
+        assert!(!fits_motd(&format!("{}\n{}a", line, line)));
+        assert!(!fits_motd(&format!("§l{}", line)));
+    }
+
+    #[test]
+    fn custom_width() {
+        // The second line of `MC_MINEHEROES_ORG` is 276 pixels wide
+        assert!(!fits_motd_with_width(MC_MINEHEROES_ORG, 275));
+        assert!(fits_motd_with_width(MC_MINEHEROES_ORG, 276));
+        assert!(fits_motd_with_width("", 0));
+    }
+}
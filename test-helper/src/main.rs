@@ -1,5 +1,10 @@
 //! A little tool that accepts an input string with format codes and prints out
 //! a vec of span constructors that can be copied into a test case
+//!
+//! The generated code matches the constructors used in
+//! `mc-legacy-formatting/tests/from_servers.rs`. Span text is printed with its
+//! `Debug` impl so that quotes, backslashes, and newlines are escaped and the
+//! output is always a valid Rust string literal.
 
 use dialoguer::Input;
 use mc_legacy_formatting::{Span, SpanExt, Styles};
@@ -10,36 +15,71 @@ fn main() {
         .interact()
         .unwrap();
     let s = &input[1..input.len() - 1];
+    let start_char = Input::<String>::new()
+        .with_prompt("Start char")
+        .default("§".into())
+        .validate_with(|c: &String| match parse_start_char(c) {
+            Some(_) => Ok(()),
+            None => Err("expected exactly one char"),
+        })
+        .interact()
+        .unwrap();
+    let start_char = parse_start_char(&start_char).unwrap();
 
     println!();
     println!("vec![");
-    s.span_iter().for_each(|s| match s {
-        Span::Styled {
-            text,
-            color,
-            styles,
-        } => println!(
-            "\tSpan::new_styled(\"{}\", Color::{:?}, {}),",
-            text,
-            color,
-            handle_styles(styles)
-        ),
-        Span::StrikethroughWhitespace {
-            text,
-            color,
-            styles,
-        } => println!(
-            "\tSpan::new_strikethrough_whitespace(\"{}\", Color::{:?}, {}),",
-            text,
-            color,
-            handle_styles(styles)
-        ),
-        Span::Plain(text) => println!("\tSpan::new_plain(\"{}\"),", text),
-    });
+    s.span_iter()
+        .with_start_char(start_char)
+        .for_each(|s| match s {
+            Span::Styled {
+                text,
+                color,
+                styles,
+                ..
+            } => println!(
+                "\tSpan::new_styled({:?}, Color::{:?}, {}),",
+                text,
+                color,
+                handle_styles(styles)
+            ),
+            Span::StrikethroughWhitespace {
+                text,
+                color,
+                styles,
+            } => println!(
+                "\tSpan::new_strikethrough_whitespace({:?}, Color::{:?}, {}),",
+                text,
+                color,
+                handle_styles(styles)
+            ),
+            Span::Plain(text) => println!("\tSpan::new_plain({:?}),", text),
+            Span::Url {
+                text,
+                color,
+                styles,
+            } => println!(
+                "\tSpan::new_url({:?}, Color::{:?}, {}),",
+                text,
+                color,
+                handle_styles(styles)
+            ),
+            span => println!("\t{:?},", span),
+        });
     println!("]");
     println!();
 }
 
+/// Parses the start char entered at the prompt, which may be more than one byte
+/// long (like the default `§`)
+fn parse_start_char(s: &str) -> Option<char> {
+    let mut chars = s.trim().chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
 fn handle_styles(styles: Styles) -> String {
     if styles.is_empty() {
         "Styles::empty()".into()